@@ -0,0 +1,160 @@
+use std::f64::consts::PI;
+
+/// Second order IIR filter (direct form II transposed), coefficients are normalized by a0
+#[derive(Debug, Clone, Copy)]
+pub struct Biquad {
+    b0: f64,
+    b1: f64,
+    b2: f64,
+    a1: f64,
+    a2: f64,
+    z1: f64,
+    z2: f64,
+}
+
+impl Biquad {
+    pub fn new(b0: f64, b1: f64, b2: f64, a0: f64, a1: f64, a2: f64) -> Self {
+        Self {
+            b0: b0 / a0,
+            b1: b1 / a0,
+            b2: b2 / a0,
+            a1: a1 / a0,
+            a2: a2 / a0,
+            z1: 0.0,
+            z2: 0.0,
+        }
+    }
+
+    // coefficients see https://www.w3.org/TR/audio-eq-cookbook/
+
+    pub fn high_pass(sample_rate: f64, frequency: f64, q: f64) -> Self {
+        let (cos, alpha) = Self::cos_alpha(sample_rate, frequency, q);
+        Self::new(
+            (1.0 + cos) / 2.0,
+            -(1.0 + cos),
+            (1.0 + cos) / 2.0,
+            1.0 + alpha,
+            -2.0 * cos,
+            1.0 - alpha,
+        )
+    }
+
+    pub fn peaking(sample_rate: f64, frequency: f64, q: f64, gain_db: f64) -> Self {
+        let (cos, alpha) = Self::cos_alpha(sample_rate, frequency, q);
+        let a = 10f64.powf(gain_db / 40.0);
+        Self::new(
+            1.0 + alpha * a,
+            -2.0 * cos,
+            1.0 - alpha * a,
+            1.0 + alpha / a,
+            -2.0 * cos,
+            1.0 - alpha / a,
+        )
+    }
+
+    pub fn low_shelf(sample_rate: f64, frequency: f64, q: f64, gain_db: f64) -> Self {
+        let (cos, alpha) = Self::cos_alpha(sample_rate, frequency, q);
+        let a = 10f64.powf(gain_db / 40.0);
+        let sqrt_a = 2.0 * a.sqrt() * alpha;
+        Self::new(
+            a * ((a + 1.0) - (a - 1.0) * cos + sqrt_a),
+            2.0 * a * ((a - 1.0) - (a + 1.0) * cos),
+            a * ((a + 1.0) - (a - 1.0) * cos - sqrt_a),
+            (a + 1.0) + (a - 1.0) * cos + sqrt_a,
+            -2.0 * ((a - 1.0) + (a + 1.0) * cos),
+            (a + 1.0) + (a - 1.0) * cos - sqrt_a,
+        )
+    }
+
+    pub fn high_shelf(sample_rate: f64, frequency: f64, q: f64, gain_db: f64) -> Self {
+        let (cos, alpha) = Self::cos_alpha(sample_rate, frequency, q);
+        let a = 10f64.powf(gain_db / 40.0);
+        let sqrt_a = 2.0 * a.sqrt() * alpha;
+        Self::new(
+            a * ((a + 1.0) + (a - 1.0) * cos + sqrt_a),
+            -2.0 * a * ((a - 1.0) + (a + 1.0) * cos),
+            a * ((a + 1.0) + (a - 1.0) * cos - sqrt_a),
+            (a + 1.0) - (a - 1.0) * cos + sqrt_a,
+            2.0 * ((a - 1.0) - (a + 1.0) * cos),
+            (a + 1.0) - (a - 1.0) * cos - sqrt_a,
+        )
+    }
+
+    fn cos_alpha(sample_rate: f64, frequency: f64, q: f64) -> (f64, f64) {
+        // keep the frequency below nyquist
+        let frequency = frequency.min(sample_rate * 0.49);
+        let w0 = 2.0 * PI * frequency / sample_rate;
+        (w0.cos(), w0.sin() / (2.0 * q))
+    }
+
+    pub fn process(&mut self, input: f64) -> f64 {
+        let output = self.b0 * input + self.z1;
+        self.z1 = self.b1 * input - self.a1 * output + self.z2;
+        self.z2 = self.b2 * input - self.a2 * output;
+        output
+    }
+
+    pub fn reset(&mut self) {
+        self.z1 = 0.0;
+        self.z2 = 0.0;
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE_RATE: f64 = 48000.0;
+
+    /// gain in dB of a sine at `frequency` after the filter settled
+    fn gain_db(mut filter: Biquad, frequency: f64) -> f64 {
+        let samples = SAMPLE_RATE as usize;
+        let mut input_energy = 0.0;
+        let mut output_energy = 0.0;
+        for i in 0..samples {
+            let input = (2.0 * PI * frequency * i as f64 / SAMPLE_RATE).sin();
+            let output = filter.process(input);
+            if i >= samples / 2 {
+                input_energy += input * input;
+                output_energy += output * output;
+            }
+        }
+        10.0 * (output_energy / input_energy).log10()
+    }
+
+    #[test]
+    fn peaking_boosts_the_centre_frequency_only() {
+        let filter = Biquad::peaking(SAMPLE_RATE, 3000.0, 1.0, 4.0);
+        assert!((gain_db(filter, 3000.0) - 4.0).abs() < 0.1);
+        assert!(gain_db(filter, 200.0).abs() < 0.2);
+    }
+
+    #[test]
+    fn high_pass_removes_rumble() {
+        let filter = Biquad::high_pass(SAMPLE_RATE, 100.0, 0.707);
+        assert!(gain_db(filter, 20.0) < -25.0);
+        assert!((gain_db(filter, 100.0) + 3.0).abs() < 0.2);
+        assert!(gain_db(filter, 1000.0).abs() < 0.1);
+    }
+
+    #[test]
+    fn shelves_boost_their_side_of_the_spectrum() {
+        let low_shelf = Biquad::low_shelf(SAMPLE_RATE, 200.0, 0.707, 6.0);
+        assert!((gain_db(low_shelf, 30.0) - 6.0).abs() < 0.2);
+        assert!(gain_db(low_shelf, 5000.0).abs() < 0.2);
+
+        let high_shelf = Biquad::high_shelf(SAMPLE_RATE, 5000.0, 0.707, -6.0);
+        assert!((gain_db(high_shelf, 18000.0) + 6.0).abs() < 0.3);
+        assert!(gain_db(high_shelf, 200.0).abs() < 0.2);
+    }
+
+    #[test]
+    fn reset_clears_the_state() {
+        let mut filter = Biquad::peaking(SAMPLE_RATE, 1000.0, 1.0, 6.0);
+        let first = filter.process(1.0);
+        filter.process(0.5);
+        filter.reset();
+        assert_eq!(filter.process(1.0), first);
+    }
+}
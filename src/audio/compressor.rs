@@ -0,0 +1,164 @@
+use std::time::Duration;
+
+const MIN_LEVEL: f32 = 1e-6;
+
+/// Feed forward compressor with makeup gain and a hard limiter. The envelope
+/// is shared by all channels, so the stereo image does not move.
+pub struct Compressor {
+    threshold_db: f32,
+    ratio: f32,
+    attack_coeff: f32,
+    release_coeff: f32,
+    makeup_gain: f32,
+    ceiling: f32,
+    envelope_db: f32,
+}
+
+impl Compressor {
+    /// `sample_rate` is the rate of the interleaved samples (frames per second * channels)
+    pub fn new(
+        sample_rate: u32,
+        threshold_db: f32,
+        ratio: f32,
+        attack: Duration,
+        release: Duration,
+        makeup_db: f32,
+        ceiling_db: f32,
+    ) -> Self {
+        Self {
+            threshold_db,
+            ratio: ratio.max(1.0),
+            attack_coeff: Self::time_coeff(sample_rate, attack),
+            release_coeff: Self::time_coeff(sample_rate, release),
+            makeup_gain: Self::db_to_linear(makeup_db),
+            ceiling: Self::db_to_linear(ceiling_db),
+            envelope_db: -120.0,
+        }
+    }
+
+    fn time_coeff(sample_rate: u32, time: Duration) -> f32 {
+        let samples = time.as_secs_f32() * sample_rate as f32;
+        if samples <= 0.0 {
+            return 0.0;
+        }
+        (-1.0 / samples).exp()
+    }
+
+    fn db_to_linear(db: f32) -> f32 {
+        10f32.powf(db / 20.0)
+    }
+
+    /// current gain reduction in dB (<= 0)
+    pub fn gain_reduction_db(&self) -> f32 {
+        let over = self.envelope_db - self.threshold_db;
+        if over > 0.0 {
+            -over * (1.0 - 1.0 / self.ratio)
+        } else {
+            0.0
+        }
+    }
+
+    pub fn process(&mut self, sample: f32) -> f32 {
+        let level_db = 20.0 * sample.abs().max(MIN_LEVEL).log10();
+        let coeff = if level_db > self.envelope_db {
+            self.attack_coeff
+        } else {
+            self.release_coeff
+        };
+        self.envelope_db = coeff * self.envelope_db + (1.0 - coeff) * level_db;
+
+        let output = sample * Self::db_to_linear(self.gain_reduction_db()) * self.makeup_gain;
+        output.clamp(-self.ceiling, self.ceiling)
+    }
+
+    pub fn reset(&mut self) {
+        self.envelope_db = -120.0;
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::f32::consts::PI;
+
+    const SAMPLE_RATE: u32 = 48000;
+    const THRESHOLD_DB: f32 = -30.0;
+    const RATIO: f32 = 4.0;
+    // peak level of the sine bursts
+    const AMPLITUDE: f32 = 0.5;
+
+    fn compressor() -> Compressor {
+        Compressor::new(
+            SAMPLE_RATE,
+            THRESHOLD_DB,
+            RATIO,
+            Duration::from_millis(10),
+            Duration::from_millis(250),
+            0.0,
+            0.0,
+        )
+    }
+
+    fn millis(ms: usize) -> usize {
+        SAMPLE_RATE as usize * ms / 1000
+    }
+
+    /// feeds `samples` of a 1 kHz sine burst (or silence) and returns the gain reduction afterwards
+    fn feed(compressor: &mut Compressor, samples: usize, amplitude: f32) -> f32 {
+        let max_reduction = -(20.0 * AMPLITUDE.log10() - THRESHOLD_DB) * (1.0 - 1.0 / RATIO);
+        for i in 0..samples {
+            let sample = amplitude * (2.0 * PI * 1000.0 * i as f32 / SAMPLE_RATE as f32).sin();
+            let output = compressor.process(sample);
+            assert!(output.abs() <= sample.abs());
+            assert!(output.abs() <= 1.0);
+            assert!(compressor.gain_reduction_db() <= 0.0);
+            assert!(compressor.gain_reduction_db() >= max_reduction - 0.01);
+        }
+        compressor.gain_reduction_db()
+    }
+
+    #[test]
+    fn quiet_signals_are_not_compressed() {
+        let mut compressor = compressor();
+        // -40 dBFS peak
+        assert_eq!(feed(&mut compressor, millis(500), 0.01), 0.0);
+    }
+
+    #[test]
+    fn attack_and_release() {
+        let mut compressor = compressor();
+
+        // the envelope starts at -120 dB, so it takes a few attack times to cross the threshold
+        assert_eq!(feed(&mut compressor, millis(5), AMPLITUDE), 0.0);
+        let after_20ms = feed(&mut compressor, millis(15), AMPLITUDE);
+        let after_200ms = feed(&mut compressor, millis(180), AMPLITUDE);
+        assert!(after_20ms < 0.0);
+        assert!(after_200ms < after_20ms);
+        // the envelope of the sine settles a few dB below its peak
+        assert!(after_200ms < -12.0);
+
+        // the release is slower than the attack, so the gain recovers gradually
+        let released_10ms = feed(&mut compressor, millis(10), 0.0);
+        assert!(released_10ms > after_200ms);
+        assert!(released_10ms < -6.0);
+        assert_eq!(feed(&mut compressor, millis(190), 0.0), 0.0);
+    }
+
+    #[test]
+    fn makeup_gain_is_limited_by_the_ceiling() {
+        let mut compressor = Compressor::new(
+            SAMPLE_RATE,
+            0.0,
+            1.0,
+            Duration::from_millis(10),
+            Duration::from_millis(250),
+            12.0,
+            -1.0,
+        );
+        let ceiling = 10f32.powf(-1.0 / 20.0);
+        assert!((compressor.process(0.01) - 0.01 * 10f32.powf(12.0 / 20.0)).abs() < 1e-6);
+        assert_eq!(compressor.process(0.9), ceiling);
+        assert_eq!(compressor.process(-0.9), -ceiling);
+    }
+}
@@ -0,0 +1,89 @@
+use crate::audio::output_device_info::OutputDeviceInfo;
+use crate::audio::output_device_provider::{OutputConnection, OutputDeviceProvider};
+use crate::audio::utils;
+use cpal::traits::{DeviceTrait, HostTrait};
+use cpal::HostId;
+use rodio::{OutputStreamBuilder, Sink};
+use crate::audio::output_lost_signal::OutputLostSignal;
+use std::sync::Arc;
+
+pub struct CpalDeviceProvider {
+    // the default host comes first, so it wins if several hosts offer the same device name
+    host_ids: Vec<HostId>,
+}
+
+impl Default for CpalDeviceProvider {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CpalDeviceProvider {
+    pub fn new() -> Self {
+        let default_host_id = cpal::default_host().id();
+        let mut host_ids = vec![default_host_id];
+        host_ids.extend(utils::output_hosts().into_iter().filter(|id| *id != default_host_id));
+        Self { host_ids }
+    }
+
+    pub fn with_host(host_id: HostId) -> Self {
+        Self { host_ids: vec![host_id] }
+    }
+
+    fn find_device(&self, device_name: &str) -> Option<cpal::Device> {
+        self.host_ids.iter().find_map(|host_id| {
+            cpal::host_from_id(*host_id)
+                .ok()?
+                .output_devices()
+                .ok()?
+                .find(|d| d.name().is_ok_and(|name| name == device_name))
+        })
+    }
+}
+
+impl OutputDeviceProvider for CpalDeviceProvider {
+    fn output_devices(&self) -> Vec<OutputDeviceInfo> {
+        if self.host_ids.len() == 1 {
+            return utils::output_devices(self.host_ids[0]);
+        }
+        utils::all_output_devices()
+    }
+
+    fn available_devices(&self) -> Vec<String> {
+        // polled regularly, so the configs are not queried here
+        self.host_ids
+            .iter()
+            .filter_map(|host_id| cpal::host_from_id(*host_id).ok())
+            .filter_map(|host| host.output_devices().ok())
+            .flat_map(|devices| devices.filter_map(|d| d.name().ok()))
+            .collect()
+    }
+
+    fn connect(&self, device_name: &str) -> Option<(Sink, OutputConnection)> {
+        let device = self.find_device(device_name)?;
+
+        let lost = Arc::new(OutputLostSignal::default());
+        let lost_clone = lost.clone();
+        let builder = OutputStreamBuilder::from_device(device)
+            .ok()?
+            .with_error_callback(move |e| {
+                println!("audio output error: {}", e);
+                if matches!(e, cpal::StreamError::DeviceNotAvailable) {
+                    lost_clone.set();
+                }
+            });
+
+        let mut stream = match builder.open_stream_or_fallback() {
+            Ok(stream) => stream,
+            Err(e) => {
+                println!("could not open audio output {}: {}", device_name, e);
+                return None;
+            }
+        };
+        // switching devices is expected, no need to log it
+        stream.log_on_drop(false);
+
+        let sink = Sink::connect_new(stream.mixer());
+        Some((sink, OutputConnection::new(device_name.to_string(), lost, Box::new(stream))))
+    }
+}
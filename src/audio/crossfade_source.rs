@@ -0,0 +1,215 @@
+use crate::audio::item_transition::{BoxedSource, ItemTransition};
+use rodio::source::SeekError;
+use rodio::{ChannelCount, SampleRate, Source};
+use std::sync::Arc;
+use std::time::Duration;
+
+const MIX_CHECK_FRAMES: u64 = 512;
+
+/// Outermost stage of an item, mixes the beginning of the next item into its
+/// end when a cross fade is configured. Without a cross fade the next item is
+/// left untouched for the [`HandoffSource`], which plays it gapless.
+///
+/// [`HandoffSource`]: crate::audio::handoff_source::HandoffSource
+pub struct CrossfadeSource<S: Source> {
+    input: S,
+    transition: Arc<ItemTransition>,
+    // duration of the item as indexed, used if the decoder does not know it (e.g. VBR mp3)
+    item_duration: Duration,
+    samples_played: u64,
+    // next item while cross fading
+    mix: Option<BoxedSource>,
+    mix_position: u64,
+    mix_len: u64,
+}
+
+impl<S: Source> CrossfadeSource<S> {
+    /// `item_duration` is zero if unknown
+    pub fn new(input: S, transition: Arc<ItemTransition>, item_duration: Duration) -> Self {
+        Self {
+            input,
+            transition,
+            item_duration,
+            samples_played: 0,
+            mix: None,
+            mix_position: 0,
+            mix_len: 0,
+        }
+    }
+
+    fn samples_per_second(&self) -> u64 {
+        self.input.sample_rate() as u64 * self.input.channels().max(1) as u64
+    }
+
+    fn remaining_samples(&self) -> Option<u64> {
+        let total = self
+            .input
+            .total_duration()
+            .or_else(|| (!self.item_duration.is_zero()).then_some(self.item_duration))?;
+        let total_samples = (total.as_secs_f64() * self.samples_per_second() as f64) as u64;
+        Some(total_samples.saturating_sub(self.samples_played))
+    }
+
+    /// Starts mixing at a frame boundary once the end is within the cross fade,
+    /// checked every few hundred frames only to keep the lock out of the hot path
+    fn start_mix_if_due(&mut self) {
+        let check_interval = MIX_CHECK_FRAMES * self.input.channels().max(1) as u64;
+        if self.mix.is_some() || !self.samples_played.is_multiple_of(check_interval) {
+            return;
+        }
+        let crossfade = self.transition.crossfade();
+        if crossfade.is_zero() {
+            return;
+        }
+        let Some(remaining) = self.remaining_samples() else {
+            return;
+        };
+        let crossfade_samples = (crossfade.as_secs_f64() * self.samples_per_second() as f64) as u64;
+        if remaining > crossfade_samples || remaining == 0 {
+            return;
+        }
+        // mixing different formats would need resampling, these items are played gapless instead
+        if self.transition.next_format() != Some((self.input.channels(), self.input.sample_rate())) {
+            return;
+        }
+        self.mix = self.transition.take_next();
+        self.mix_position = 0;
+        self.mix_len = remaining;
+    }
+
+    fn mix_duration(&self) -> Duration {
+        Duration::from_secs_f64(self.mix_position as f64 / self.samples_per_second().max(1) as f64)
+    }
+}
+
+impl<S: Source> Iterator for CrossfadeSource<S> {
+    type Item = f32;
+
+    fn next(&mut self) -> Option<f32> {
+        self.start_mix_if_due();
+        let Some(sample) = self.input.next() else {
+            // the handoff continues with the rest of the next item
+            if let Some(mix) = self.mix.take() {
+                let overlap = self.mix_duration();
+                self.transition.put_back(mix, overlap);
+            }
+            return None;
+        };
+        self.samples_played += 1;
+
+        let Some(mix) = self.mix.as_mut() else {
+            return Some(sample);
+        };
+        let next_sample = mix.next().unwrap_or(0.0);
+        let fade_in = (self.mix_position as f32 / self.mix_len.max(1) as f32).min(1.0);
+        self.mix_position += 1;
+        Some(sample * (1.0 - fade_in) + next_sample * fade_in)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.input.size_hint()
+    }
+}
+
+impl<S: Source> Source for CrossfadeSource<S> {
+    fn current_span_len(&self) -> Option<usize> {
+        self.input.current_span_len()
+    }
+
+    fn channels(&self) -> ChannelCount {
+        self.input.channels()
+    }
+
+    fn sample_rate(&self) -> SampleRate {
+        self.input.sample_rate()
+    }
+
+    fn total_duration(&self) -> Option<Duration> {
+        self.input.total_duration()
+    }
+
+    fn try_seek(&mut self, pos: Duration) -> Result<(), SeekError> {
+        // seeking away from the end restarts the next item
+        if let Some(mut mix) = self.mix.take() {
+            let _ = mix.try_seek(Duration::ZERO);
+            self.transition.put_back(mix, Duration::ZERO);
+        }
+        self.input.try_seek(pos)?;
+        self.samples_played = (pos.as_secs_f64() * self.samples_per_second() as f64) as u64;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rodio::buffer::SamplesBuffer;
+
+    const SAMPLE_RATE: SampleRate = 48000;
+    const CROSSFADE: Duration = Duration::from_millis(200);
+
+    /// Like a VBR mp3 without header, the decoder does not know the total duration
+    struct UnknownDuration(SamplesBuffer);
+
+    impl Iterator for UnknownDuration {
+        type Item = f32;
+
+        fn next(&mut self) -> Option<f32> {
+            self.0.next()
+        }
+    }
+
+    impl Source for UnknownDuration {
+        fn current_span_len(&self) -> Option<usize> {
+            None
+        }
+
+        fn channels(&self) -> ChannelCount {
+            self.0.channels()
+        }
+
+        fn sample_rate(&self) -> SampleRate {
+            self.0.sample_rate()
+        }
+
+        fn total_duration(&self) -> Option<Duration> {
+            None
+        }
+
+        fn try_seek(&mut self, pos: Duration) -> Result<(), SeekError> {
+            self.0.try_seek(pos)
+        }
+    }
+
+    /// Plays one second of silence with a next item whose samples are their index,
+    /// returns the output and the transition afterwards
+    fn play(item_duration: Duration) -> (Vec<f32>, Arc<ItemTransition>) {
+        let transition = Arc::new(ItemTransition::default());
+        let next: Vec<f32> = (0..SAMPLE_RATE).map(|i| i as f32).collect();
+        transition.set_next(Box::new(SamplesBuffer::new(1, SAMPLE_RATE, next)), CROSSFADE);
+        let input = UnknownDuration(SamplesBuffer::new(1, SAMPLE_RATE, vec![0.0; SAMPLE_RATE as usize]));
+        let output = CrossfadeSource::new(input, transition.clone(), item_duration).collect();
+        (output, transition)
+    }
+
+    #[test]
+    fn item_duration_is_used_without_total_duration() {
+        let (output, transition) = play(Duration::from_secs(1));
+        let crossfade_start = SAMPLE_RATE as usize - (CROSSFADE.as_secs_f64() * SAMPLE_RATE as f64) as usize;
+        assert!(output[..=crossfade_start].iter().all(|s| *s == 0.0));
+        assert!(output[crossfade_start + 1..].iter().all(|s| *s > 0.0));
+
+        assert_eq!(transition.overlap(), CROSSFADE);
+        // the handoff continues the next item right after the overlap
+        let mut next = transition.take_next().unwrap();
+        assert_eq!(next.next(), Some((SAMPLE_RATE as usize - crossfade_start) as f32));
+    }
+
+    #[test]
+    fn unknown_duration_plays_gapless() {
+        let (output, transition) = play(Duration::ZERO);
+        assert!(output.iter().all(|s| *s == 0.0));
+        assert_eq!(transition.overlap(), Duration::ZERO);
+        assert_eq!(transition.take_next().unwrap().next(), Some(0.0));
+    }
+}
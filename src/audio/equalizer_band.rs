@@ -0,0 +1,40 @@
+use crate::audio::biquad::Biquad;
+use crate::audio::equalizer_band_type::EqualizerBandType;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, PartialEq)]
+#[derive(Serialize, Deserialize)]
+pub struct EqualizerBand {
+    pub band_type: EqualizerBandType,
+    // center / corner frequency in Hz
+    pub frequency: f32,
+    pub gain_db: f32,
+    pub q: f32,
+}
+
+impl EqualizerBand {
+    pub fn new(band_type: EqualizerBandType, frequency: f32, gain_db: f32, q: f32) -> Self {
+        Self {
+            band_type,
+            frequency,
+            gain_db,
+            q,
+        }
+    }
+
+    pub fn is_neutral(&self) -> bool {
+        self.gain_db.abs() < 0.01
+    }
+
+    pub fn to_biquad(&self, sample_rate: u32) -> Biquad {
+        let rate = sample_rate as f64;
+        let frequency = self.frequency as f64;
+        let q = (self.q as f64).max(0.1);
+        let gain = self.gain_db as f64;
+        match self.band_type {
+            EqualizerBandType::LowShelf => Biquad::low_shelf(rate, frequency, q, gain),
+            EqualizerBandType::Peaking => Biquad::peaking(rate, frequency, q, gain),
+            EqualizerBandType::HighShelf => Biquad::high_shelf(rate, frequency, q, gain),
+        }
+    }
+}
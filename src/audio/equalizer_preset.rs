@@ -0,0 +1,61 @@
+use crate::audio::equalizer_band::EqualizerBand;
+use crate::audio::equalizer_band_type::EqualizerBandType::{HighShelf, LowShelf, Peaking};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[derive(Serialize, Deserialize)]
+pub enum EqualizerPreset {
+    #[default]
+    Flat,
+    BassBoost,
+    TrebleBoost,
+    Speech,
+    // uses the custom bands of the settings
+    Custom,
+}
+
+impl EqualizerPreset {
+    pub fn all() -> Vec<EqualizerPreset> {
+        vec![
+            EqualizerPreset::Flat,
+            EqualizerPreset::BassBoost,
+            EqualizerPreset::TrebleBoost,
+            EqualizerPreset::Speech,
+            EqualizerPreset::Custom,
+        ]
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            EqualizerPreset::Flat => "Flat",
+            EqualizerPreset::BassBoost => "Bass boost",
+            EqualizerPreset::TrebleBoost => "Treble boost",
+            EqualizerPreset::Speech => "Speech",
+            EqualizerPreset::Custom => "Custom",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<EqualizerPreset> {
+        Self::all().into_iter().find(|p| p.name() == name)
+    }
+
+    /// Bands of the built-in presets, empty for flat and custom
+    pub fn bands(&self) -> Vec<EqualizerBand> {
+        match self {
+            EqualizerPreset::Flat | EqualizerPreset::Custom => vec![],
+            EqualizerPreset::BassBoost => vec![
+                EqualizerBand::new(LowShelf, 100.0, 6.0, 0.707),
+                EqualizerBand::new(Peaking, 250.0, 2.0, 1.0),
+            ],
+            EqualizerPreset::TrebleBoost => vec![
+                EqualizerBand::new(Peaking, 4000.0, 2.0, 1.0),
+                EqualizerBand::new(HighShelf, 8000.0, 6.0, 0.707),
+            ],
+            EqualizerPreset::Speech => vec![
+                EqualizerBand::new(LowShelf, 150.0, -6.0, 0.707),
+                EqualizerBand::new(Peaking, 2500.0, 4.0, 1.0),
+                EqualizerBand::new(HighShelf, 10000.0, -3.0, 0.707),
+            ],
+        }
+    }
+}
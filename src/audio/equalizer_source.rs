@@ -0,0 +1,176 @@
+use crate::audio::biquad::Biquad;
+use crate::audio::equalizer_band::EqualizerBand;
+use rodio::source::SeekError;
+use rodio::{ChannelCount, SampleRate, Source};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+// old and new filters are cross faded when the bands change to avoid clicks
+const EQUALIZER_CROSSFADE: Duration = Duration::from_millis(30);
+
+/// Shared between the player and the audio thread, the version tells the
+/// source to rebuild its filters
+#[derive(Default)]
+pub struct EqualizerControl {
+    bands: Mutex<Vec<EqualizerBand>>,
+    version: AtomicU64,
+}
+
+impl EqualizerControl {
+    pub fn set_bands(&self, bands: Vec<EqualizerBand>) {
+        *self.bands.lock().unwrap() = bands.into_iter().filter(|b| !b.is_neutral()).collect();
+        self.version.fetch_add(1, Ordering::Release);
+    }
+
+    fn bands(&self) -> Vec<EqualizerBand> {
+        self.bands.lock().unwrap().clone()
+    }
+
+    fn version(&self) -> u64 {
+        self.version.load(Ordering::Acquire)
+    }
+}
+
+// all bands in series, one chain per channel
+type FilterChains = Vec<Vec<Biquad>>;
+
+/// Multi band equalizer, a no-op while all bands are neutral
+pub struct EqualizerSource<S: Source> {
+    input: S,
+    control: Arc<EqualizerControl>,
+    version: u64,
+    channels: ChannelCount,
+    sample_rate: SampleRate,
+    filters: FilterChains,
+    previous_filters: Option<FilterChains>,
+    // remaining and total samples of the cross fade
+    fade_remaining: usize,
+    fade_len: usize,
+    channel: usize,
+}
+
+impl<S: Source> EqualizerSource<S> {
+    pub fn new(input: S, control: Arc<EqualizerControl>) -> Self {
+        let channels = input.channels();
+        let sample_rate = input.sample_rate();
+        let version = control.version();
+        let filters = Self::build_filters(&control.bands(), channels, sample_rate);
+        Self {
+            input,
+            control,
+            version,
+            channels,
+            sample_rate,
+            filters,
+            previous_filters: None,
+            fade_remaining: 0,
+            fade_len: 0,
+            channel: 0,
+        }
+    }
+
+    fn build_filters(bands: &[EqualizerBand], channels: ChannelCount, sample_rate: SampleRate) -> FilterChains {
+        (0..channels.max(1))
+            .map(|_| bands.iter().map(|band| band.to_biquad(sample_rate)).collect())
+            .collect()
+    }
+
+    fn process_chain(chain: &mut [Biquad], sample: f64) -> f64 {
+        chain.iter_mut().fold(sample, |value, filter| filter.process(value))
+    }
+
+    /// only called at frame boundaries
+    fn update_filters(&mut self) {
+        let channels = self.input.channels();
+        let sample_rate = self.input.sample_rate();
+        let version = self.control.version();
+        let format_changed = channels != self.channels || sample_rate != self.sample_rate;
+        if version == self.version && !format_changed {
+            return;
+        }
+
+        let filters = Self::build_filters(&self.control.bands(), channels, sample_rate);
+        if format_changed {
+            // the old filter state is useless for a different format
+            self.previous_filters = None;
+            self.fade_remaining = 0;
+        } else {
+            self.previous_filters = Some(std::mem::replace(&mut self.filters, filters.clone()));
+            self.fade_len = (EQUALIZER_CROSSFADE.as_secs_f32() * sample_rate as f32 * channels.max(1) as f32) as usize;
+            self.fade_remaining = self.fade_len;
+        }
+        self.filters = filters;
+        self.version = version;
+        self.channels = channels;
+        self.sample_rate = sample_rate;
+    }
+
+    fn reset(&mut self) {
+        for chain in self.filters.iter_mut() {
+            for filter in chain.iter_mut() {
+                filter.reset();
+            }
+        }
+        self.previous_filters = None;
+        self.fade_remaining = 0;
+    }
+}
+
+impl<S: Source> Iterator for EqualizerSource<S> {
+    type Item = f32;
+
+    fn next(&mut self) -> Option<f32> {
+        if self.channel == 0 {
+            self.update_filters();
+        }
+        let sample = self.input.next()?;
+        let channel = self.channel;
+        self.channel = (self.channel + 1) % self.channels.max(1) as usize;
+
+        let output = Self::process_chain(&mut self.filters[channel], sample as f64);
+        if self.fade_remaining == 0 {
+            return Some(output as f32);
+        }
+
+        let Some(previous_filters) = self.previous_filters.as_mut() else {
+            self.fade_remaining = 0;
+            return Some(output as f32);
+        };
+        let previous_output = Self::process_chain(&mut previous_filters[channel], sample as f64);
+        let new_weight = 1.0 - self.fade_remaining as f64 / self.fade_len.max(1) as f64;
+        self.fade_remaining -= 1;
+        if self.fade_remaining == 0 {
+            self.previous_filters = None;
+        }
+        Some((previous_output * (1.0 - new_weight) + output * new_weight) as f32)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.input.size_hint()
+    }
+}
+
+impl<S: Source> Source for EqualizerSource<S> {
+    fn current_span_len(&self) -> Option<usize> {
+        self.input.current_span_len()
+    }
+
+    fn channels(&self) -> ChannelCount {
+        self.input.channels()
+    }
+
+    fn sample_rate(&self) -> SampleRate {
+        self.input.sample_rate()
+    }
+
+    fn total_duration(&self) -> Option<Duration> {
+        self.input.total_duration()
+    }
+
+    fn try_seek(&mut self, pos: Duration) -> Result<(), SeekError> {
+        self.reset();
+        self.channel = 0;
+        self.input.try_seek(pos)
+    }
+}
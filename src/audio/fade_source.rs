@@ -0,0 +1,152 @@
+use rodio::source::SeekError;
+use rodio::{ChannelCount, SampleRate, Source};
+use std::sync::atomic::{AtomicU32, AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Shared between the player and the audio thread. Each request gets a new
+/// generation, the sources report the generation whose ramp they finished.
+pub struct FadeControl {
+    generation: AtomicU64,
+    settled_generation: AtomicU64,
+    // f32 bits
+    target: AtomicU32,
+    ramp_micros: AtomicU64,
+}
+
+impl Default for FadeControl {
+    fn default() -> Self {
+        Self {
+            generation: AtomicU64::new(0),
+            settled_generation: AtomicU64::new(0),
+            target: AtomicU32::new(1f32.to_bits()),
+            ramp_micros: AtomicU64::new(0),
+        }
+    }
+}
+
+impl FadeControl {
+    /// Ramps linearly from the current gain to `target`
+    pub fn fade_to(&self, target: f32, duration: Duration) -> u64 {
+        self.target.store(target.to_bits(), Ordering::Relaxed);
+        self.ramp_micros.store(duration.as_micros() as u64, Ordering::Relaxed);
+        self.generation.fetch_add(1, Ordering::AcqRel) + 1
+    }
+
+    pub fn set_gain(&self, gain: f32) -> u64 {
+        self.fade_to(gain, Duration::ZERO)
+    }
+
+    pub fn is_settled(&self, generation: u64) -> bool {
+        self.settled_generation.load(Ordering::Acquire) >= generation
+    }
+
+    fn generation(&self) -> u64 {
+        self.generation.load(Ordering::Acquire)
+    }
+
+    fn target(&self) -> f32 {
+        f32::from_bits(self.target.load(Ordering::Relaxed))
+    }
+
+    fn ramp(&self) -> Duration {
+        Duration::from_micros(self.ramp_micros.load(Ordering::Relaxed))
+    }
+}
+
+/// Applies the volume ramps requested through the [`FadeControl`] sample by
+/// sample. No samples are dropped or inserted, so the sink position is not affected.
+pub struct FadeSource<S: Source> {
+    input: S,
+    control: Arc<FadeControl>,
+    generation: u64,
+    gain: f32,
+    target: f32,
+    // gain change per frame while ramping
+    step: f32,
+    channel: usize,
+}
+
+impl<S: Source> FadeSource<S> {
+    pub fn new(input: S, control: Arc<FadeControl>) -> Self {
+        let generation = control.generation();
+        let target = control.target();
+        Self {
+            input,
+            control,
+            generation,
+            gain: target,
+            target,
+            step: 0.0,
+            channel: 0,
+        }
+    }
+
+    /// only called at frame boundaries, so all channels get the same gain
+    fn update_gain(&mut self) {
+        let generation = self.control.generation();
+        if generation != self.generation {
+            self.generation = generation;
+            self.target = self.control.target();
+            let ramp_frames = self.control.ramp().as_secs_f32() * self.input.sample_rate() as f32;
+            if ramp_frames < 1.0 {
+                self.gain = self.target;
+                self.step = 0.0;
+            } else {
+                self.step = (self.target - self.gain) / ramp_frames;
+            }
+        }
+
+        if self.step != 0.0 {
+            self.gain += self.step;
+            let reached = (self.step > 0.0 && self.gain >= self.target) || (self.step < 0.0 && self.gain <= self.target);
+            if reached {
+                self.gain = self.target;
+                self.step = 0.0;
+            }
+        }
+        if self.step == 0.0 {
+            self.control.settled_generation.fetch_max(self.generation, Ordering::AcqRel);
+        }
+    }
+}
+
+impl<S: Source> Iterator for FadeSource<S> {
+    type Item = f32;
+
+    fn next(&mut self) -> Option<f32> {
+        if self.channel == 0 {
+            self.update_gain();
+        }
+        let sample = self.input.next()?;
+        self.channel = (self.channel + 1) % self.input.channels().max(1) as usize;
+        Some(sample * self.gain)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.input.size_hint()
+    }
+}
+
+impl<S: Source> Source for FadeSource<S> {
+    fn current_span_len(&self) -> Option<usize> {
+        self.input.current_span_len()
+    }
+
+    fn channels(&self) -> ChannelCount {
+        self.input.channels()
+    }
+
+    fn sample_rate(&self) -> SampleRate {
+        self.input.sample_rate()
+    }
+
+    fn total_duration(&self) -> Option<Duration> {
+        self.input.total_duration()
+    }
+
+    fn try_seek(&mut self, pos: Duration) -> Result<(), SeekError> {
+        self.channel = 0;
+        self.input.try_seek(pos)
+    }
+}
@@ -0,0 +1,103 @@
+use crate::audio::output_device_info::OutputDeviceInfo;
+use crate::audio::output_device_provider::{OutputConnection, OutputDeviceProvider};
+use crate::audio::output_lost_signal::OutputLostSignal;
+use rodio::{Sink, Source};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+const FAKE_OUTPUT_CHUNK_DURATION: Duration = Duration::from_millis(10);
+
+// device name and lost signal of every opened connection
+type FakeConnections = Vec<(String, Arc<OutputLostSignal>)>;
+
+/// Output devices that can be plugged and unplugged at will, samples are
+/// consumed in real time but never played
+#[derive(Clone, Default)]
+pub struct FakeDeviceProvider {
+    devices: Arc<Mutex<Vec<String>>>,
+    connections: Arc<Mutex<FakeConnections>>,
+}
+
+impl FakeDeviceProvider {
+    pub fn new(devices: Vec<String>) -> Self {
+        Self {
+            devices: Arc::new(Mutex::new(devices)),
+            connections: Arc::new(Mutex::new(vec![])),
+        }
+    }
+
+    pub fn plug(&self, device_name: &str) {
+        let mut devices = self.devices.lock().unwrap();
+        if !devices.iter().any(|d| d == device_name) {
+            devices.push(device_name.to_string());
+        }
+    }
+
+    /// Removes the device and marks all of its connections as lost
+    pub fn unplug(&self, device_name: &str) {
+        self.devices.lock().unwrap().retain(|d| d != device_name);
+        let mut connections = self.connections.lock().unwrap();
+        for (name, lost) in connections.iter() {
+            if name == device_name {
+                lost.set();
+            }
+        }
+        connections.retain(|(name, _)| name != device_name);
+    }
+}
+
+struct FakeOutput {
+    stopped: Arc<AtomicBool>,
+}
+
+impl Drop for FakeOutput {
+    fn drop(&mut self) {
+        self.stopped.store(true, Ordering::SeqCst);
+    }
+}
+
+impl OutputDeviceProvider for FakeDeviceProvider {
+    fn output_devices(&self) -> Vec<OutputDeviceInfo> {
+        self.devices
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|name| OutputDeviceInfo::new("fake".to_string(), name.clone()))
+            .collect()
+    }
+
+    fn connect(&self, device_name: &str) -> Option<(Sink, OutputConnection)> {
+        if !self.available_devices().iter().any(|d| d == device_name) {
+            return None;
+        }
+
+        let (sink, mut queue_output) = Sink::new();
+        let lost = Arc::new(OutputLostSignal::default());
+        let stopped = Arc::new(AtomicBool::new(false));
+
+        // the sink only makes progress (and answers seeks) while its output is consumed
+        let thread_lost = lost.clone();
+        let thread_stopped = stopped.clone();
+        thread::spawn(move || {
+            while !thread_lost.is_set() && !thread_stopped.load(Ordering::SeqCst) {
+                let samples_per_chunk = queue_output.sample_rate() as u128
+                    * queue_output.channels() as u128
+                    * FAKE_OUTPUT_CHUNK_DURATION.as_millis()
+                    / 1000;
+                for _ in 0..samples_per_chunk {
+                    queue_output.next();
+                }
+                thread::sleep(FAKE_OUTPUT_CHUNK_DURATION);
+            }
+        });
+
+        self.connections
+            .lock()
+            .unwrap()
+            .push((device_name.to_string(), lost.clone()));
+        let fake_output = FakeOutput { stopped };
+        Some((sink, OutputConnection::new(device_name.to_string(), lost, Box::new(fake_output))))
+    }
+}
@@ -0,0 +1,75 @@
+use crate::audio::item_transition::{BoxedSource, ItemTransition};
+use rodio::source::SeekError;
+use rodio::{ChannelCount, SampleRate, Source};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Queued right behind the current item, plays the next item from where the
+/// cross fade left it (or from the start). Plays nothing if the next item was
+/// cleared in the meantime.
+pub struct HandoffSource {
+    transition: Arc<ItemTransition>,
+    inner: Option<BoxedSource>,
+    started: bool,
+    channels: ChannelCount,
+    sample_rate: SampleRate,
+}
+
+impl HandoffSource {
+    pub fn new(transition: Arc<ItemTransition>) -> Self {
+        let (channels, sample_rate) = transition.next_format().unwrap_or((2, 44100));
+        Self {
+            transition,
+            inner: None,
+            started: false,
+            channels,
+            sample_rate,
+        }
+    }
+
+    fn ensure_started(&mut self) {
+        if !self.started {
+            self.started = true;
+            self.inner = self.transition.take_next();
+        }
+    }
+}
+
+impl Iterator for HandoffSource {
+    type Item = f32;
+
+    fn next(&mut self) -> Option<f32> {
+        self.ensure_started();
+        self.inner.as_mut()?.next()
+    }
+}
+
+impl Source for HandoffSource {
+    fn current_span_len(&self) -> Option<usize> {
+        match &self.inner {
+            Some(inner) => inner.current_span_len(),
+            None if self.started => Some(0),
+            None => self.transition.next_span_len(),
+        }
+    }
+
+    fn channels(&self) -> ChannelCount {
+        self.inner.as_ref().map(|inner| inner.channels()).unwrap_or(self.channels)
+    }
+
+    fn sample_rate(&self) -> SampleRate {
+        self.inner.as_ref().map(|inner| inner.sample_rate()).unwrap_or(self.sample_rate)
+    }
+
+    fn total_duration(&self) -> Option<Duration> {
+        self.inner.as_ref().and_then(|inner| inner.total_duration())
+    }
+
+    fn try_seek(&mut self, pos: Duration) -> Result<(), SeekError> {
+        self.ensure_started();
+        match self.inner.as_mut() {
+            Some(inner) => inner.try_seek(pos),
+            None => Ok(()),
+        }
+    }
+}
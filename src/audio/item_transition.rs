@@ -0,0 +1,68 @@
+use rodio::Source;
+use std::sync::Mutex;
+use std::time::Duration;
+
+pub type BoxedSource = Box<dyn Source + Send>;
+
+#[derive(Default)]
+struct ItemTransitionState {
+    next: Option<BoxedSource>,
+    crossfade: Duration,
+    // how much of the next item was already played while cross fading
+    overlap: Duration,
+}
+
+/// Hands the pre-decoded next item from the outgoing [`CrossfadeSource`] to the
+/// [`HandoffSource`] queued behind it, so both share the same decoder.
+///
+/// [`CrossfadeSource`]: crate::audio::crossfade_source::CrossfadeSource
+/// [`HandoffSource`]: crate::audio::handoff_source::HandoffSource
+#[derive(Default)]
+pub struct ItemTransition {
+    state: Mutex<ItemTransitionState>,
+}
+
+impl ItemTransition {
+    pub fn set_next(&self, next: BoxedSource, crossfade: Duration) {
+        let mut state = self.state.lock().unwrap();
+        state.next = Some(next);
+        state.crossfade = crossfade;
+        state.overlap = Duration::ZERO;
+    }
+
+    /// The queued handoff plays nothing afterwards
+    pub fn clear_next(&self) {
+        let mut state = self.state.lock().unwrap();
+        state.next = None;
+        state.overlap = Duration::ZERO;
+    }
+
+    pub fn crossfade(&self) -> Duration {
+        self.state.lock().unwrap().crossfade
+    }
+
+    pub fn overlap(&self) -> Duration {
+        self.state.lock().unwrap().overlap
+    }
+
+    pub(crate) fn take_next(&self) -> Option<BoxedSource> {
+        self.state.lock().unwrap().next.take()
+    }
+
+    pub(crate) fn put_back(&self, next: BoxedSource, overlap: Duration) {
+        let mut state = self.state.lock().unwrap();
+        state.next = Some(next);
+        state.overlap = overlap;
+    }
+
+    /// Channels and sample rate of the next item, if any
+    pub(crate) fn next_format(&self) -> Option<(rodio::ChannelCount, rodio::SampleRate)> {
+        let state = self.state.lock().unwrap();
+        state.next.as_ref().map(|next| (next.channels(), next.sample_rate()))
+    }
+
+    pub(crate) fn next_span_len(&self) -> Option<usize> {
+        let state = self.state.lock().unwrap();
+        state.next.as_ref().and_then(|next| next.current_span_len())
+    }
+}
@@ -1 +1,22 @@
-mod utils;
\ No newline at end of file
+pub mod utils;
+pub mod biquad;
+pub mod compressor;
+pub mod voice_boost_source;
+pub mod equalizer_band_type;
+pub mod equalizer_band;
+pub mod equalizer_preset;
+pub mod equalizer_source;
+pub mod item_transition;
+pub mod crossfade_source;
+pub mod handoff_source;
+pub mod silence_skip_source;
+pub mod fade_source;
+pub mod output_device_config;
+pub mod output_device_info;
+pub mod output_lost_signal;
+pub mod output_device_provider;
+pub mod cpal_device_provider;
+pub mod fake_device_provider;
+pub mod render_clock;
+pub mod wav_writer;
+pub mod rendering_device_provider;
@@ -0,0 +1,8 @@
+/// One supported configuration range of an output device
+#[derive(Clone, Debug)]
+pub struct OutputDeviceConfig {
+    pub channels: u16,
+    pub min_sample_rate: u32,
+    pub max_sample_rate: u32,
+    pub sample_format: String,
+}
@@ -0,0 +1,36 @@
+use crate::audio::output_device_config::OutputDeviceConfig;
+
+#[derive(Clone, Debug)]
+pub struct OutputDeviceInfo {
+    pub host: String,
+    pub name: String,
+    pub configs: Vec<OutputDeviceConfig>,
+}
+
+impl OutputDeviceInfo {
+    pub fn new(host: String, name: String) -> Self {
+        Self {
+            host,
+            name,
+            configs: vec![],
+        }
+    }
+
+    /// short human readable summary, e.g. "2ch 44100-48000Hz f32"
+    pub fn configs_summary(&self) -> String {
+        self.configs
+            .iter()
+            .map(|c| {
+                if c.min_sample_rate == c.max_sample_rate {
+                    format!("{}ch {}Hz {}", c.channels, c.min_sample_rate, c.sample_format)
+                } else {
+                    format!(
+                        "{}ch {}-{}Hz {}",
+                        c.channels, c.min_sample_rate, c.max_sample_rate, c.sample_format
+                    )
+                }
+            })
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+}
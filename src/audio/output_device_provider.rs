@@ -0,0 +1,43 @@
+use crate::audio::output_device_info::OutputDeviceInfo;
+use rodio::Sink;
+use std::any::Any;
+use crate::audio::output_lost_signal::OutputLostSignal;
+use std::sync::Arc;
+
+/// Keeps an opened output alive, audio stops as soon as this is dropped
+pub struct OutputConnection {
+    pub device_name: String,
+    lost: Arc<OutputLostSignal>,
+    _keep_alive: Box<dyn Any>,
+}
+
+impl OutputConnection {
+    pub fn new(device_name: String, lost: Arc<OutputLostSignal>, keep_alive: Box<dyn Any>) -> Self {
+        Self {
+            device_name,
+            lost,
+            _keep_alive: keep_alive,
+        }
+    }
+
+    /// Set by the output (e.g. the cpal error callback) when the device disappeared
+    pub fn is_lost(&self) -> bool {
+        self.lost.is_set()
+    }
+
+    pub fn lost_signal(&self) -> Arc<OutputLostSignal> {
+        self.lost.clone()
+    }
+}
+
+/// Abstraction over the available audio outputs, so that device loss and
+/// reconnection can be simulated without real hardware
+pub trait OutputDeviceProvider {
+    fn output_devices(&self) -> Vec<OutputDeviceInfo>;
+
+    fn available_devices(&self) -> Vec<String> {
+        self.output_devices().into_iter().map(|d| d.name).collect()
+    }
+
+    fn connect(&self, device_name: &str) -> Option<(Sink, OutputConnection)>;
+}
@@ -0,0 +1,29 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use tokio::sync::Notify;
+
+/// Set from the audio side (e.g. the cpal error callback) when the device
+/// disappeared, the player waits for it instead of polling
+#[derive(Default)]
+pub struct OutputLostSignal {
+    lost: AtomicBool,
+    notify: Notify,
+}
+
+impl OutputLostSignal {
+    pub fn set(&self) {
+        self.lost.store(true, Ordering::SeqCst);
+        // stores a permit if nobody waits right now
+        self.notify.notify_one();
+    }
+
+    pub fn is_set(&self) -> bool {
+        self.lost.load(Ordering::SeqCst)
+    }
+
+    pub async fn wait(&self) {
+        if self.is_set() {
+            return;
+        }
+        self.notify.notified().await;
+    }
+}
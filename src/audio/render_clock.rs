@@ -0,0 +1,86 @@
+use std::sync::{Arc, Condvar, Mutex};
+use std::thread;
+use std::time::Duration;
+
+const RENDER_CLOCK_WAIT_TIMEOUT: Duration = Duration::from_millis(50);
+
+#[derive(Default)]
+struct RenderClockState {
+    // how much audio the renderers are allowed to consume
+    target: Duration,
+    rendered: Duration,
+    renderers: usize,
+}
+
+/// Deterministic clock for outputs without hardware: audio is only consumed
+/// when the clock is advanced, so positions do not depend on the wall clock
+#[derive(Clone, Default)]
+pub struct RenderClock {
+    inner: Arc<(Mutex<RenderClockState>, Condvar)>,
+}
+
+impl RenderClock {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn advance(&self, duration: Duration) {
+        let (state, condvar) = &*self.inner;
+        state.lock().unwrap().target += duration;
+        condvar.notify_all();
+    }
+
+    /// Advances the clock and blocks until the audio has been consumed (or no renderer is connected)
+    pub fn advance_and_wait(&self, duration: Duration) {
+        self.advance(duration);
+        let (state, condvar) = &*self.inner;
+        let mut guard = state.lock().unwrap();
+        while guard.renderers > 0 && guard.rendered < guard.target {
+            guard = condvar.wait_timeout(guard, RENDER_CLOCK_WAIT_TIMEOUT).unwrap().0;
+        }
+    }
+
+    pub fn rendered(&self) -> Duration {
+        self.inner.0.lock().unwrap().rendered
+    }
+
+    /// Advances the clock in real time, e.g. for a headless run without sound card
+    pub fn spawn_realtime(&self, step: Duration) {
+        let clock = self.clone();
+        thread::spawn(move || {
+            loop {
+                clock.advance(step);
+                thread::sleep(step);
+            }
+        });
+    }
+
+    pub(crate) fn register_renderer(&self) {
+        self.inner.0.lock().unwrap().renderers += 1;
+    }
+
+    pub(crate) fn unregister_renderer(&self) {
+        let (state, condvar) = &*self.inner;
+        state.lock().unwrap().renderers -= 1;
+        condvar.notify_all();
+    }
+
+    /// Blocks until the clock allows rendering the next chunk, returns false when `is_stopped` is true
+    pub(crate) fn wait_for_chunk(&self, chunk: Duration, is_stopped: impl Fn() -> bool) -> bool {
+        let (state, condvar) = &*self.inner;
+        let mut guard = state.lock().unwrap();
+        while guard.rendered + chunk > guard.target {
+            if is_stopped() {
+                return false;
+            }
+            guard = condvar.wait_timeout(guard, RENDER_CLOCK_WAIT_TIMEOUT).unwrap().0;
+        }
+        !is_stopped()
+    }
+
+    pub(crate) fn mark_rendered(&self, chunk: Duration) {
+        let (state, condvar) = &*self.inner;
+        state.lock().unwrap().rendered += chunk;
+        condvar.notify_all();
+    }
+}
@@ -0,0 +1,115 @@
+use crate::audio::output_device_info::OutputDeviceInfo;
+use crate::audio::output_device_provider::{OutputConnection, OutputDeviceProvider};
+use crate::audio::output_lost_signal::OutputLostSignal;
+use crate::audio::render_clock::RenderClock;
+use crate::audio::wav_writer::WavWriter;
+use rodio::source::UniformSourceIterator;
+use rodio::Sink;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+pub const NULL_DEVICE_NAME: &str = "null";
+pub const WAV_DEVICE_NAME: &str = "wav";
+
+const RENDER_CHANNELS: u16 = 2;
+const RENDER_SAMPLE_RATE: u32 = 44100;
+const RENDER_CHUNK_DURATION: Duration = Duration::from_millis(10);
+
+/// Output without sound card that consumes audio along a `RenderClock`,
+/// either discarding the samples or writing them into a wav file
+pub struct RenderingDeviceProvider {
+    clock: RenderClock,
+    wav_path: Option<PathBuf>,
+}
+
+impl RenderingDeviceProvider {
+    pub fn null(clock: RenderClock) -> Self {
+        Self { clock, wav_path: None }
+    }
+
+    pub fn wav(clock: RenderClock, wav_path: PathBuf) -> Self {
+        Self {
+            clock,
+            wav_path: Some(wav_path),
+        }
+    }
+
+    fn device_name(&self) -> &'static str {
+        if self.wav_path.is_some() {
+            WAV_DEVICE_NAME
+        } else {
+            NULL_DEVICE_NAME
+        }
+    }
+}
+
+struct RenderingOutput {
+    stopped: Arc<AtomicBool>,
+}
+
+impl Drop for RenderingOutput {
+    fn drop(&mut self) {
+        self.stopped.store(true, Ordering::SeqCst);
+    }
+}
+
+impl OutputDeviceProvider for RenderingDeviceProvider {
+    fn output_devices(&self) -> Vec<OutputDeviceInfo> {
+        vec![OutputDeviceInfo::new("render".to_string(), self.device_name().to_string())]
+    }
+
+    fn connect(&self, device_name: &str) -> Option<(Sink, OutputConnection)> {
+        if device_name != self.device_name() {
+            return None;
+        }
+
+        let mut wav_writer = match &self.wav_path {
+            Some(path) => match WavWriter::create(path, RENDER_CHANNELS, RENDER_SAMPLE_RATE) {
+                Ok(writer) => Some(writer),
+                Err(e) => {
+                    println!("could not create wav output {}: {}", path.display(), e);
+                    return None;
+                }
+            },
+            None => None,
+        };
+
+        let (sink, queue_output) = Sink::new();
+        let mut samples = UniformSourceIterator::new(queue_output, RENDER_CHANNELS, RENDER_SAMPLE_RATE);
+        let samples_per_chunk =
+            (RENDER_SAMPLE_RATE as u128 * RENDER_CHANNELS as u128 * RENDER_CHUNK_DURATION.as_millis() / 1000) as usize;
+
+        let stopped = Arc::new(AtomicBool::new(false));
+        let thread_stopped = stopped.clone();
+        let clock = self.clock.clone();
+        clock.register_renderer();
+        thread::spawn(move || {
+            while clock.wait_for_chunk(RENDER_CHUNK_DURATION, || thread_stopped.load(Ordering::SeqCst)) {
+                for _ in 0..samples_per_chunk {
+                    let sample = samples.next().unwrap_or(0.0);
+                    if let Some(writer) = wav_writer.as_mut()
+                        && let Err(e) = writer.write_sample(sample)
+                    {
+                        println!("could not write wav output: {}", e);
+                        wav_writer = None;
+                    }
+                }
+                clock.mark_rendered(RENDER_CHUNK_DURATION);
+            }
+            if let Some(writer) = wav_writer
+                && let Err(e) = writer.finish()
+            {
+                println!("could not finish wav output: {}", e);
+            }
+            clock.unregister_renderer();
+        });
+
+        // a rendering output can not get lost
+        let lost = Arc::new(OutputLostSignal::default());
+        let rendering_output = RenderingOutput { stopped };
+        Some((sink, OutputConnection::new(device_name.to_string(), lost, Box::new(rendering_output))))
+    }
+}
@@ -0,0 +1,187 @@
+use rodio::source::SeekError;
+use rodio::{ChannelCount, SampleRate, Source};
+use std::sync::atomic::{AtomicBool, AtomicU32, AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Shared between the player and the audio thread, collects the time saved by all items
+pub struct SilenceSkipControl {
+    enabled: AtomicBool,
+    // linear amplitude, stored as f32 bits
+    threshold: AtomicU32,
+    min_silence_millis: AtomicU64,
+    saved_nanos: AtomicU64,
+}
+
+impl Default for SilenceSkipControl {
+    fn default() -> Self {
+        let control = Self {
+            enabled: AtomicBool::new(false),
+            threshold: AtomicU32::new(0),
+            min_silence_millis: AtomicU64::new(0),
+            saved_nanos: AtomicU64::new(0),
+        };
+        control.configure(-45.0, Duration::from_millis(500));
+        control
+    }
+}
+
+impl SilenceSkipControl {
+    pub fn set_enabled(&self, enabled: bool) {
+        self.enabled.store(enabled, Ordering::Relaxed);
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled.load(Ordering::Relaxed)
+    }
+
+    /// Silence below `threshold_db` is shortened to `min_silence`
+    pub fn configure(&self, threshold_db: f32, min_silence: Duration) {
+        let threshold = 10f32.powf(threshold_db / 20.0);
+        self.threshold.store(threshold.to_bits(), Ordering::Relaxed);
+        self.min_silence_millis.store(min_silence.as_millis() as u64, Ordering::Relaxed);
+    }
+
+    /// Time saved since the last call
+    pub fn take_saved(&self) -> Duration {
+        Duration::from_nanos(self.saved_nanos.swap(0, Ordering::Relaxed))
+    }
+
+    fn threshold(&self) -> f32 {
+        f32::from_bits(self.threshold.load(Ordering::Relaxed))
+    }
+
+    fn min_silence(&self) -> Duration {
+        Duration::from_millis(self.min_silence_millis.load(Ordering::Relaxed))
+    }
+}
+
+/// Time skipped in one item since it started or was seeked, maps the sink
+/// position back to the timeline of the file
+#[derive(Default)]
+pub struct SkippedTime {
+    nanos: AtomicU64,
+}
+
+impl SkippedTime {
+    pub fn get(&self) -> Duration {
+        Duration::from_nanos(self.nanos.load(Ordering::Relaxed))
+    }
+
+    fn set(&self, skipped: Duration) {
+        self.nanos.store(skipped.as_nanos() as u64, Ordering::Relaxed);
+    }
+}
+
+/// Drops silent frames once a silence lasted longer than the configured minimum,
+/// so long pauses are shortened to that minimum
+pub struct SilenceSkipSource<S: Source> {
+    input: S,
+    control: Arc<SilenceSkipControl>,
+    skipped: Arc<SkippedTime>,
+    frame: Vec<f32>,
+    frame_position: usize,
+    silent_frames: u64,
+    skipped_frames: u64,
+}
+
+impl<S: Source> SilenceSkipSource<S> {
+    pub fn new(input: S, control: Arc<SilenceSkipControl>, skipped: Arc<SkippedTime>) -> Self {
+        Self {
+            input,
+            control,
+            skipped,
+            frame: Vec::new(),
+            frame_position: 0,
+            silent_frames: 0,
+            skipped_frames: 0,
+        }
+    }
+
+    fn read_frame(&mut self) -> bool {
+        self.frame.clear();
+        self.frame_position = 0;
+        for _ in 0..self.input.channels().max(1) {
+            match self.input.next() {
+                Some(sample) => self.frame.push(sample),
+                None => break,
+            }
+        }
+        !self.frame.is_empty()
+    }
+
+    /// Whether the frame just read is dropped
+    fn should_skip_frame(&mut self) -> bool {
+        if !self.control.is_enabled() {
+            self.silent_frames = 0;
+            return false;
+        }
+        let threshold = self.control.threshold();
+        if self.frame.iter().any(|sample| sample.abs() >= threshold) {
+            self.silent_frames = 0;
+            return false;
+        }
+        self.silent_frames += 1;
+        let sample_rate = self.input.sample_rate().max(1) as u64;
+        let min_frames = self.control.min_silence().as_millis() as u64 * sample_rate / 1000;
+        if self.silent_frames <= min_frames {
+            return false;
+        }
+
+        self.skipped_frames += 1;
+        let frame_nanos = 1_000_000_000 / sample_rate;
+        self.control.saved_nanos.fetch_add(frame_nanos, Ordering::Relaxed);
+        self.skipped.set(Duration::from_nanos(self.skipped_frames * 1_000_000_000 / sample_rate));
+        true
+    }
+}
+
+impl<S: Source> Iterator for SilenceSkipSource<S> {
+    type Item = f32;
+
+    fn next(&mut self) -> Option<f32> {
+        if self.frame_position < self.frame.len() {
+            self.frame_position += 1;
+            return Some(self.frame[self.frame_position - 1]);
+        }
+        loop {
+            if !self.read_frame() {
+                return None;
+            }
+            if !self.should_skip_frame() {
+                break;
+            }
+        }
+        self.frame_position = 1;
+        Some(self.frame[0])
+    }
+}
+
+impl<S: Source> Source for SilenceSkipSource<S> {
+    // dropped frames make the span length of the input meaningless
+    fn current_span_len(&self) -> Option<usize> {
+        None
+    }
+
+    fn channels(&self) -> ChannelCount {
+        self.input.channels()
+    }
+
+    fn sample_rate(&self) -> SampleRate {
+        self.input.sample_rate()
+    }
+
+    fn total_duration(&self) -> Option<Duration> {
+        self.input.total_duration()
+    }
+
+    fn try_seek(&mut self, pos: Duration) -> Result<(), SeekError> {
+        self.input.try_seek(pos)?;
+        self.frame.clear();
+        self.frame_position = 0;
+        self.silent_frames = 0;
+        self.skipped_frames = 0;
+        self.skipped.set(Duration::ZERO);
+        Ok(())
+    }
+}
@@ -1,27 +1,41 @@
-/*
-        for i in 1..5 {
-            let hosts_ids = cpal::available_hosts();
+use crate::audio::output_device_config::OutputDeviceConfig;
+use crate::audio::output_device_info::OutputDeviceInfo;
+use cpal::traits::{DeviceTrait, HostTrait};
+use cpal::HostId;
 
-            for host_id in hosts_ids {
-                println!("==== HOST {:?}", host_id);
+pub fn output_hosts() -> Vec<HostId> {
+    cpal::available_hosts()
+}
 
-                let host_result = cpal::host_from_id(host_id);
+/// Output devices of a single host including their supported configs
+pub fn output_devices(host_id: HostId) -> Vec<OutputDeviceInfo> {
+    let Ok(host) = cpal::host_from_id(host_id) else {
+        return vec![];
+    };
+    let Ok(devices) = host.output_devices() else {
+        return vec![];
+    };
 
-                if let Ok(host) = host_result {
-                    let devices_result = host.devices();
-                    if let Ok(devices) = devices_result {
-
-                        for device in devices {
-                            let devicename_result = device.name();
-                            if let Ok(devicename) = devicename_result {
-                                println!("   ----{:?}", devicename);
-                            }
-                        }
-                    }
-                }
+    devices
+        .filter_map(|device| {
+            let name = device.name().ok()?;
+            let mut info = OutputDeviceInfo::new(host_id.name().to_string(), name);
+            if let Ok(configs) = device.supported_output_configs() {
+                info.configs = configs
+                    .map(|c| OutputDeviceConfig {
+                        channels: c.channels(),
+                        min_sample_rate: c.min_sample_rate().0,
+                        max_sample_rate: c.max_sample_rate().0,
+                        sample_format: c.sample_format().to_string(),
+                    })
+                    .collect();
             }
-            tokio::time::sleep(Duration::from_secs(5)).await;
-        }
+            Some(info)
+        })
+        .collect()
+}
 
-        return Ok(());
-    */
\ No newline at end of file
+/// Output devices of all available hosts
+pub fn all_output_devices() -> Vec<OutputDeviceInfo> {
+    output_hosts().into_iter().flat_map(output_devices).collect()
+}
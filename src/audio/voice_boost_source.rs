@@ -0,0 +1,204 @@
+use crate::audio::biquad::Biquad;
+use crate::audio::compressor::Compressor;
+use rodio::source::SeekError;
+use rodio::{ChannelCount, SampleRate, Source};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Shared between the player and the audio thread, so voice boost can be toggled without re-appending the source
+#[derive(Default)]
+pub struct VoiceBoostControl {
+    enabled: AtomicBool,
+}
+
+impl VoiceBoostControl {
+    pub fn set_enabled(&self, enabled: bool) {
+        self.enabled.store(enabled, Ordering::Relaxed);
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled.load(Ordering::Relaxed)
+    }
+}
+
+/// Speech band EQ (rumble filter and presence boost) followed by a compressor /
+/// limiter that lifts quiet narrators
+pub struct VoiceBoostSource<S: Source> {
+    input: S,
+    control: Arc<VoiceBoostControl>,
+    channels: ChannelCount,
+    sample_rate: SampleRate,
+    // high pass and presence filter per channel
+    filters: Vec<(Biquad, Biquad)>,
+    compressor: Compressor,
+    channel: usize,
+    was_enabled: bool,
+}
+
+impl<S: Source> VoiceBoostSource<S> {
+    pub fn new(input: S, control: Arc<VoiceBoostControl>) -> Self {
+        let channels = input.channels();
+        let sample_rate = input.sample_rate();
+        Self {
+            filters: Self::speech_filters(channels, sample_rate),
+            compressor: Self::voice_compressor(channels, sample_rate),
+            input,
+            control,
+            channels,
+            sample_rate,
+            channel: 0,
+            was_enabled: false,
+        }
+    }
+
+    fn speech_filters(channels: ChannelCount, sample_rate: SampleRate) -> Vec<(Biquad, Biquad)> {
+        let rate = sample_rate as f64;
+        (0..channels.max(1))
+            .map(|_| (Biquad::high_pass(rate, 100.0, 0.707), Biquad::peaking(rate, 3000.0, 1.0, 4.0)))
+            .collect()
+    }
+
+    fn voice_compressor(channels: ChannelCount, sample_rate: SampleRate) -> Compressor {
+        Compressor::new(
+            sample_rate * channels.max(1) as u32,
+            -30.0,
+            4.0,
+            Duration::from_millis(10),
+            Duration::from_millis(250),
+            12.0,
+            -1.0,
+        )
+    }
+
+    /// the format may change at span boundaries
+    fn update_format(&mut self) {
+        let channels = self.input.channels();
+        let sample_rate = self.input.sample_rate();
+        if channels != self.channels || sample_rate != self.sample_rate {
+            self.channels = channels;
+            self.sample_rate = sample_rate;
+            self.filters = Self::speech_filters(channels, sample_rate);
+            self.compressor = Self::voice_compressor(channels, sample_rate);
+        }
+    }
+
+    fn reset(&mut self) {
+        for (high_pass, presence) in self.filters.iter_mut() {
+            high_pass.reset();
+            presence.reset();
+        }
+        self.compressor.reset();
+    }
+}
+
+impl<S: Source> Iterator for VoiceBoostSource<S> {
+    type Item = f32;
+
+    fn next(&mut self) -> Option<f32> {
+        if self.channel == 0 {
+            self.update_format();
+        }
+        let sample = self.input.next()?;
+        let channel = self.channel;
+        self.channel = (self.channel + 1) % self.channels.max(1) as usize;
+
+        let enabled = self.control.is_enabled();
+        if enabled && !self.was_enabled {
+            self.reset();
+        }
+        self.was_enabled = enabled;
+        if !enabled {
+            return Some(sample);
+        }
+
+        let (high_pass, presence) = &mut self.filters[channel];
+        let filtered = presence.process(high_pass.process(sample as f64)) as f32;
+        Some(self.compressor.process(filtered))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.input.size_hint()
+    }
+}
+
+impl<S: Source> Source for VoiceBoostSource<S> {
+    fn current_span_len(&self) -> Option<usize> {
+        self.input.current_span_len()
+    }
+
+    fn channels(&self) -> ChannelCount {
+        self.input.channels()
+    }
+
+    fn sample_rate(&self) -> SampleRate {
+        self.input.sample_rate()
+    }
+
+    fn total_duration(&self) -> Option<Duration> {
+        self.input.total_duration()
+    }
+
+    fn try_seek(&mut self, pos: Duration) -> Result<(), SeekError> {
+        self.reset();
+        self.channel = 0;
+        self.input.try_seek(pos)
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rodio::buffer::SamplesBuffer;
+    use std::f64::consts::PI;
+
+    const SAMPLE_RATE: SampleRate = 48000;
+
+    fn sine(frequency: f64, amplitude: f32, samples: usize) -> Vec<f32> {
+        (0..samples)
+            .map(|i| amplitude * (2.0 * PI * frequency * i as f64 / SAMPLE_RATE as f64).sin() as f32)
+            .collect()
+    }
+
+    fn presence_gain_db(frequency: f64) -> f64 {
+        let (_, mut presence) = VoiceBoostSource::<SamplesBuffer>::speech_filters(1, SAMPLE_RATE)[0];
+        let input = sine(frequency, 1.0, SAMPLE_RATE as usize);
+        let settled = input.len() / 2;
+        let output: Vec<f64> = input.iter().map(|s| presence.process(*s as f64)).collect();
+        let input_energy: f64 = input[settled..].iter().map(|s| (*s as f64).powi(2)).sum();
+        let output_energy: f64 = output[settled..].iter().map(|s| s.powi(2)).sum();
+        10.0 * (output_energy / input_energy).log10()
+    }
+
+    #[test]
+    fn presence_filter_boosts_the_speech_band() {
+        let centre = presence_gain_db(3000.0);
+        let far_off = presence_gain_db(200.0);
+        assert!((centre - 4.0).abs() < 0.1);
+        assert!(far_off.abs() < 0.2);
+    }
+
+    #[test]
+    fn bypass_is_bit_identical() {
+        let input: Vec<f32> = sine(440.0, 0.8, 4800).into_iter().chain(sine(60.0, 0.05, 4800)).collect();
+        let control = Arc::new(VoiceBoostControl::default());
+        let source = VoiceBoostSource::new(SamplesBuffer::new(2, SAMPLE_RATE, input.clone()), control);
+        let output: Vec<f32> = source.collect();
+        assert_eq!(output.len(), input.len());
+        assert!(output.iter().zip(&input).all(|(o, i)| o.to_bits() == i.to_bits()));
+    }
+
+    #[test]
+    fn enabled_voice_boost_lifts_quiet_speech() {
+        let input = sine(3000.0, 0.01, SAMPLE_RATE as usize);
+        let control = Arc::new(VoiceBoostControl::default());
+        control.set_enabled(true);
+        let source = VoiceBoostSource::new(SamplesBuffer::new(1, SAMPLE_RATE, input.clone()), control);
+        let output: Vec<f32> = source.collect();
+        let peak = |samples: &[f32]| samples.iter().fold(0f32, |max, s| max.max(s.abs()));
+        let settled = input.len() / 2;
+        assert!(peak(&output[settled..]) > 2.0 * peak(&input[settled..]));
+        assert!(peak(&output) <= 10f32.powf(-1.0 / 20.0));
+    }
+}
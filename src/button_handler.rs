@@ -1,6 +1,4 @@
 use std::sync::{Arc, Mutex};
-use std::thread;
-use std::time::{Duration, Instant};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum ButtonAction {
@@ -10,8 +8,6 @@ pub enum ButtonAction {
 
 
 
-const DEBOUNCE_DELAY: Duration = Duration::from_millis(400);
-
 #[derive(Clone)]
 pub struct ButtonHandler {
     state: Arc<Mutex<State>>,
@@ -23,6 +19,12 @@ struct State {
     clicks: u32,
 }
 
+impl Default for ButtonHandler {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl ButtonHandler {
     pub fn new() -> Self {
         Self {
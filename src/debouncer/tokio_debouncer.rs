@@ -14,11 +14,11 @@ use std::time::Duration;
 #[cfg(not(feature = "parking_lot"))]
 pub trait MutexExt<T> {
     /// Lock the mutex, panicking if poisoned.
-    fn risky_lock(&self) -> MutexGuard<T>;
+    fn risky_lock(&self) -> MutexGuard<'_, T>;
 }
 #[cfg(not(feature = "parking_lot"))]
 impl<T> MutexExt<T> for Mutex<T> {
-    fn risky_lock(&self) -> MutexGuard<T> {
+    fn risky_lock(&self) -> MutexGuard<'_, T> {
         self.lock().expect("Mutex poisoned")
     }
 }
@@ -104,11 +104,7 @@ impl Debouncer {
             notifier: Notify::new(),
             cooldown,
             state: Mutex::new(DebouncerState {
-                has_run: if matches!(mode, DebounceMode::Leading) {
-                    false
-                } else {
-                    true
-                },
+                has_run: !matches!(mode, DebounceMode::Leading),
                 last_run: tokio::time::Instant::now(),
                 triggered: false,
             }),
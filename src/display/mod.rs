@@ -1 +1 @@
-pub(crate) mod utils;
\ No newline at end of file
+pub mod utils;
\ No newline at end of file
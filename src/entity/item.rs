@@ -10,6 +10,8 @@ pub enum MediaType {
     Audiobook,
     #[sea_orm(num_value = 4)]
     Music,
+    #[sea_orm(num_value = 8)]
+    Podcast,
 }
 
 #[sea_orm::model]
@@ -25,8 +27,21 @@ pub struct Model {
 
     pub cover_hash: String,
     
+    // path of the library root, location is relative to it
+    pub library_root: String,
+
     pub location: String,
 
+    // set while the library root (e.g. a removable USB / SD card) is not available
+    pub is_offline: bool,
+
+    // set while the file is not found by the scan of its library root, e.g. because
+    // it has been deleted or matches an exclusion rule
+    pub is_missing: bool,
+
+    // total playback duration in milliseconds
+    pub duration: i64,
+
     // this key is randomly generated on every "full scan" and each item gets updated
     // all items that do not have this updated key get
     pub last_scan_random_key: String,
@@ -44,6 +59,15 @@ pub struct Model {
     #[sea_orm(has_many)]
     pub progress_history: HasMany<super::items_progress_history::Entity>,
 
+    #[sea_orm(has_many)]
+    pub bookmarks: HasMany<super::items_bookmarks::Entity>,
+
+    #[sea_orm(has_many)]
+    pub listening_sessions: HasMany<super::items_listening_sessions::Entity>,
+
+    #[sea_orm(has_many)]
+    pub listening_states: HasMany<super::items_listening_states::Entity>,
+
     /*
     // properties needed for listing
     pub cover: String, // empty for no cover, rel_path for cover
@@ -0,0 +1,31 @@
+use chrono::Utc;
+use sea_orm::entity::prelude::*;
+
+
+#[sea_orm::model]
+#[derive(DeriveEntityModel, Clone, Debug, PartialEq)]
+#[sea_orm(table_name = "items_bookmarks")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i32,
+
+    // Foreign key to items.id
+    pub item_id: i32,
+
+    // playback position in milliseconds
+    pub position: i64,
+
+    pub chapter_index: Option<i32>,
+
+    pub chapter_name: Option<String>,
+
+    pub note: Option<String>,
+
+    pub date_created: chrono::DateTime<Utc>,
+
+    #[sea_orm(belongs_to, from = "item_id", to = "id")]
+    pub item: HasOne<super::item::Entity>,
+
+}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -9,6 +9,8 @@ pub enum JsonTagField {
     Chapters,
     #[sea_orm(num_value = 1)]
     Lyrics,
+    #[sea_orm(num_value = 2)]
+    ReplayGain,
 }
 
 #[sea_orm::model]
@@ -0,0 +1,37 @@
+use chrono::Utc;
+use sea_orm::entity::prelude::*;
+
+/// Continuous playback of one item, from play until pause, stop or the next item
+#[sea_orm::model]
+#[derive(DeriveEntityModel, Clone, Debug, PartialEq)]
+#[sea_orm(table_name = "items_listening_sessions")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i32,
+
+    // Foreign key to items.id
+    pub item_id: i32,
+
+    pub session_key: String,
+
+    pub date_start: chrono::DateTime<Utc>,
+
+    pub date_end: chrono::DateTime<Utc>,
+
+    // playback positions in milliseconds
+    pub start_position: i64,
+
+    pub end_position: i64,
+
+    // time spent listening in milliseconds
+    pub listened: i64,
+
+    // time jumped over by seeking forward or skipping silence in milliseconds
+    pub skipped: i64,
+
+    #[sea_orm(belongs_to, from = "item_id", to = "id")]
+    pub item: HasOne<super::item::Entity>,
+
+}
+
+impl ActiveModelBehavior for ActiveModel {}
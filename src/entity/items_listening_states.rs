@@ -0,0 +1,41 @@
+use chrono::Utc;
+use sea_orm::entity::prelude::*;
+
+#[derive(Debug, Clone, PartialEq, Eq, EnumIter, DeriveActiveEnum)]
+#[sea_orm(rs_type = "i32", db_type = "Integer")]
+pub enum ListeningState {
+    #[sea_orm(num_value = 0)]
+    New,
+    #[sea_orm(num_value = 1)]
+    InProgress,
+    #[sea_orm(num_value = 2)]
+    Finished,
+}
+
+#[sea_orm::model]
+#[derive(DeriveEntityModel, Clone, Debug, PartialEq)]
+#[sea_orm(table_name = "items_listening_states")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i32,
+
+    // Foreign key to items.id, at most one state per item
+    #[sea_orm(unique)]
+    pub item_id: i32,
+
+    pub state: ListeningState,
+
+    // last listened position in milliseconds
+    pub position: i64,
+
+    // set by the user, automatic updates keep a manual Finished state
+    pub is_manual: bool,
+
+    pub date_modified: chrono::DateTime<Utc>,
+
+    #[sea_orm(belongs_to, from = "item_id", to = "id")]
+    pub item: HasOne<super::item::Entity>,
+
+}
+
+impl ActiveModelBehavior for ActiveModel {}
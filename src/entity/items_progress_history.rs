@@ -1,6 +1,14 @@
 use chrono::Utc;
 use sea_orm::entity::prelude::*;
 
+#[derive(Debug, Clone, PartialEq, Eq, EnumIter, DeriveActiveEnum)]
+#[sea_orm(rs_type = "i32", db_type = "Integer")]
+pub enum ProgressReason {
+    #[sea_orm(num_value = 0)]
+    Position,
+    #[sea_orm(num_value = 2)]
+    AutoRewind,
+}
 
 #[sea_orm::model]
 #[derive(DeriveEntityModel, Clone, Debug, PartialEq)]
@@ -14,8 +22,13 @@ pub struct Model {
 
     pub session_key: String,
 
-    // playback position in seconds, percentage, etc.
-    pub position: Time,
+    pub reason: ProgressReason,
+
+    // playback position in milliseconds
+    pub position: i64,
+
+    // position before e.g. a rewind in milliseconds, same as position otherwise
+    pub previous_position: i64,
 
     pub date_modified: chrono::DateTime<Utc>,
 
@@ -3,3 +3,7 @@ pub mod items_json_metadata;
 pub mod items_metadata;
 
 pub mod items_progress_history;
+pub mod items_bookmarks;
+pub mod items_listening_sessions;
+pub mod items_listening_states;
+pub mod settings;
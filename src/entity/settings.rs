@@ -0,0 +1,20 @@
+use chrono::Utc;
+use sea_orm::entity::prelude::*;
+
+// generic key value store, values are stored as json
+#[sea_orm::model]
+#[derive(DeriveEntityModel, Clone, Debug, PartialEq)]
+#[sea_orm(table_name = "settings")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i32,
+
+    #[sea_orm(unique)]
+    pub key: String,
+
+    pub value: String,
+
+    pub date_modified: chrono::DateTime<Utc>,
+}
+
+impl ActiveModelBehavior for ActiveModel {}
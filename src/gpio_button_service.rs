@@ -7,5 +7,6 @@ pub enum GpioButtonEvent {
 }
 
 pub struct GpioButtonService {
+    #[allow(dead_code)] // events are not read yet
     device: Device
 }
\ No newline at end of file
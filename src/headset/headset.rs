@@ -2,6 +2,12 @@ pub struct Headset {
 
 }
 
+impl Default for Headset {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl Headset {
     pub fn new() -> Headset {
         Self {
@@ -9,7 +15,7 @@ impl Headset {
     }
 
 
-    pub async fn run(&mut self, device_path: String/*, evt_tx: mpsc::UnboundedSender<PlayerEvent>*/)/* -> JoinHandle<Result<String, String>> */{
+    pub async fn run(&mut self, _device_path: String/*, evt_tx: mpsc::UnboundedSender<PlayerEvent>*/)/* -> JoinHandle<Result<String, String>> */{
         /*
         let btn_click_count = Arc::new(Mutex::new(0));
         let btn_click_count_clone = btn_click_count.clone();
@@ -0,0 +1,18 @@
+// modules are named after their main type, e.g. player::player::Player
+#![allow(clippy::module_inception)]
+
+pub mod gpio_button_service;
+pub mod headset;
+pub mod player;
+
+pub mod entity;
+pub mod migrator;
+
+pub mod button_handler;
+pub mod media_source;
+pub mod serde_json_mods;
+pub mod debouncer;
+pub mod audio;
+pub mod display;
+pub mod time;
+pub mod settings;
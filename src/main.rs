@@ -1,22 +1,10 @@
 use clap::Parser;
 use tokio::sync::mpsc;
 
-mod gpio_button_service;
-mod headset;
-mod player;
-
-mod entity;
-mod migrator;
-
-mod button_handler;
-mod media_source;
-pub mod serde_json_mods;
-mod debouncer;
-mod audio;
-mod display;
-mod time;
 mod slint_helpers;
 
+use rust_slint_riscv64_musl_demo::{audio, debouncer, display, media_source, migrator, player, settings, time};
+
 const MAGIC_HEADSET_REMOTE_DEBOUNCER_DELAY: u64 = 250;
 const MAGIC_REPETITIVE_ACTION_DELAY: u64 = 850;
 
@@ -24,23 +12,55 @@ const MAGIC_REPETITIVE_ACTION_DELAY: u64 = 850;
 #[derive(Parser, Debug)]
 #[command(version, about, long_about = None)]
 struct Args {
+    /// Default for the data directory and the library, if they are not specified
     #[arg(short, long, default_value = "./")]
     base_directory: String,
+
+    /// Location of player.db (defaults to base directory)
+    #[arg(long)]
+    data_directory: Option<String>,
+
+    /// Location of the cover cache (defaults to <data directory>/cache)
+    #[arg(long)]
+    cache_directory: Option<String>,
+
+    /// Library root, can be used multiple times (defaults to base directory)
+    #[arg(short, long)]
+    library: Vec<String>,
+
+    /// Library root on removable media (USB / SD), can be used multiple times
+    #[arg(long)]
+    removable_library: Vec<String>,
+
+    /// Discard the audio instead of using a sound card (headless runs)
+    #[arg(long)]
+    null_output: bool,
+
+    /// Render the audio into this wav file instead of using a sound card
+    #[arg(long)]
+    wav_output: Option<String>,
 }
 
+use crate::audio::equalizer_preset::EqualizerPreset;
+use crate::audio::cpal_device_provider::CpalDeviceProvider;
+use crate::audio::output_device_provider::OutputDeviceProvider;
+use crate::audio::render_clock::RenderClock;
+use crate::audio::rendering_device_provider::RenderingDeviceProvider;
 use crate::debouncer::tokio_debouncer::{DebounceMode, Debouncer};
-use crate::entity::{item, items_json_metadata, items_metadata, items_progress_history};
 use crate::media_source::file_media_source::FileMediaSource;
+use crate::media_source::library_root::LibraryRoot;
 use crate::media_source::media_source::{
     MediaSource, MediaSourceCommand, MediaSourceEvent
-    ,
+    , MediaSourceItem, MediaSourceListeningState, MediaType,
 };
 use crate::migrator::Migrator;
-use crate::player::player::Player;
+use crate::settings::settings_manager::SettingsManager;
+use crate::player::player::{Player, SETTINGS_KEY_OUTPUT_DEVICE};
 use crate::player::player_command::PlayerCommand;
 use crate::player::player_event::PlayerEvent;
+use crate::player::player_state::PlayerState;
 use crate::player::trigger_action::TriggerAction;
-use crate::time::format_duration;
+use crate::time::{format_duration, format_duration_short};
 use chrono::{DateTime, Utc};
 use evdev::{Device, EventSummary, KeyCode};
 use sea_orm::{Database, DatabaseConnection, DbErr};
@@ -49,28 +69,17 @@ use slint::{
     ComponentHandle, Model, ModelRc, SharedString, ToSharedString,
     VecModel,
 };
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::sync::{Arc, Mutex};
 use std::time::{Duration, SystemTime};
 use std::{iter, thread};
 use tokio::select;
-use tokio::task::JoinHandle;
 
 slint::include_modules!();
 
-async fn connect_db(db_url: &str, first_run: bool) -> Result<DatabaseConnection, DbErr> {
+/// Creates the schema on the first run and brings databases of older versions up to date
+async fn connect_db(db_url: &str) -> Result<DatabaseConnection, DbErr> {
     let db = Database::connect(db_url).await?;
-    // todo: dirty hack to prevent startup failure if db exists
-    // this has to be solved with migrations or at least better than this
-    if first_run {
-        db.get_schema_builder()
-            .register(item::Entity)
-            .register(items_metadata::Entity)
-            .register(items_json_metadata::Entity)
-            .register(items_progress_history::Entity)
-            .apply(&db)
-            .await?;
-    }
     Migrator::up(&db, None).await?;
     Ok(db)
 }
@@ -86,33 +95,48 @@ async fn main() -> Result<(), slint::PlatformError> {
 
     let args = Args::parse();
     let base_dir = args.base_directory.clone();
-    println!("base directory is: {}", base_dir.clone());
-    let base_path = Path::new(&base_dir);
-    if !Path::exists(base_path) {
+    let data_dir = args.data_directory.clone().unwrap_or(base_dir.clone());
+    println!("data directory is: {}", data_dir.clone());
+    let data_path = Path::new(&data_dir);
+    if !Path::exists(data_path) {
         match std::env::current_dir() {
             Ok(cwd) => {
                 println!(
-                    "base directory does not exist: {:?}, current dir is: {:?}",
-                    base_path, cwd
+                    "data directory does not exist: {:?}, current dir is: {:?}",
+                    data_path, cwd
                 );
             }
             Err(_) => {
-                println!("base directory does not exist: {}", args.base_directory);
+                println!("data directory does not exist: {}", data_dir);
             }
         }
 
         return Err(slint::PlatformError::Other(format!(
-            "Base directory does not exist: {}",
-            args.base_directory
+            "Data directory does not exist: {}",
+            data_dir
         )));
     }
 
+    let cache_dir = args.cache_directory.clone().unwrap_or(format!(
+        "{}/{}",
+        data_dir.clone().trim_end_matches("/"),
+        String::from("cache")
+    ));
+
+    let mut library_roots: Vec<LibraryRoot> = args.library
+        .iter()
+        .map(|path| LibraryRoot::new(path.clone(), false))
+        .chain(args.removable_library.iter().map(|path| LibraryRoot::new(path.clone(), true)))
+        .collect();
+    if args.library.is_empty() {
+        library_roots.insert(0, LibraryRoot::new(base_dir.clone(), false));
+    }
+
     let db_path = format!(
         "{}/{}",
-        base_dir.clone().trim_end_matches("/"),
+        data_dir.clone().trim_end_matches("/"),
         String::from("player.db")
     );
-    let first_run = !Path::new(&db_path).exists();
     let db_url = format!("sqlite://{}?mode=rwc", db_path);
 
 
@@ -128,7 +152,7 @@ async fn main() -> Result<(), slint::PlatformError> {
 
 
     slint::spawn_local(async move {
-        let connect_result = connect_db(&db_url, first_run).await;
+        let connect_result = connect_db(&db_url).await;
         if connect_result.is_err() {
             /*
             return Err(slint::PlatformError::Other(format!(
@@ -142,24 +166,43 @@ async fn main() -> Result<(), slint::PlatformError> {
         let db = connect_result.unwrap();
 
 
-        // let settings_manager = SettingsManager::new(db.clone());
+        let settings_manager = SettingsManager::new(db.clone());
 
-        let display_brightness = 1000; // settings_manager.get("display.brightness", 1000).await;
-        let dark_mode = true; // settings_manager.get("appearance.dark_mode", true);
-        let file_source = FileMediaSource::new(db.clone(), args.base_directory);
+        let _display_brightness = 1000; // settings_manager.get("display.brightness", 1000).await;
+        let _dark_mode = true; // settings_manager.get("appearance.dark_mode", true);
+        let file_source = FileMediaSource::new(db.clone(), settings_manager.clone(), library_roots, cache_dir);
 
         let fs_clone1 = file_source.clone();
         slint::spawn_local(async move {
             fs_clone1.scan_media().await;
+            let fs_loudness = fs_clone1.clone();
+            slint::spawn_local(async move {
+                fs_loudness.analyze_missing_loudness().await;
+            }).unwrap();
             fs_clone1.run(source_cmd_rx, source_evt_tx).await;
         }).unwrap();
 
 
 
+        let preferred_output_device = settings_manager
+            .get(SETTINGS_KEY_OUTPUT_DEVICE, "USB-C to 3.5mm Headphone Jack A".to_string())
+            .await;
+        let device_provider: Box<dyn OutputDeviceProvider> = if args.null_output || args.wav_output.is_some() {
+            let clock = RenderClock::new();
+            clock.spawn_realtime(Duration::from_millis(10));
+            match &args.wav_output {
+                Some(path) => Box::new(RenderingDeviceProvider::wav(clock, PathBuf::from(path))),
+                None => Box::new(RenderingDeviceProvider::null(clock)),
+            }
+        } else {
+            Box::new(CpalDeviceProvider::new())
+        };
         let fs_clone2 = file_source.clone();
         let mut player = Player::new(
             Arc::new(fs_clone2),
-            "USB-C to 3.5mm Headphone Jack A".to_string(),
+            settings_manager.clone(),
+            device_provider,
+            preferred_output_device,
             "pipewire".to_string(),
         );
         slint::spawn_local(async move {
@@ -181,7 +224,7 @@ async fn main() -> Result<(), slint::PlatformError> {
     let debouncer = Debouncer::new(Duration::from_millis(MAGIC_HEADSET_REMOTE_DEBOUNCER_DELAY), DebounceMode::Trailing);
     let debouncer_clone = debouncer.clone();
 
-    let handle = thread::spawn(move || {
+    let _handle = thread::spawn(move || {
         loop {
             let device_paths = vec!["/dev/input/event1", "/dev/input/event13"];
 
@@ -213,7 +256,7 @@ async fn main() -> Result<(), slint::PlatformError> {
 
             for event in device.fetch_events().unwrap() {
                 match event.destructure() {
-                    EventSummary::Key(ev, KeyCode::KEY_PLAYPAUSE, 1) => {
+                    EventSummary::Key(_, KeyCode::KEY_PLAYPAUSE, 1) => {
                         let dt: DateTime<Utc> = event.timestamp().into();
                         let now: DateTime<Utc> = SystemTime::now().into();
                         let iso = dt.format("%Y-%m-%dT%H:%M:%S%.3fZ").to_string();
@@ -234,7 +277,7 @@ async fn main() -> Result<(), slint::PlatformError> {
                         debouncer_clone.trigger();
                         // println!("debouncer.trigger()");
                     }
-                    EventSummary::Key(ev, KeyCode::KEY_PLAYPAUSE, 0) => {
+                    EventSummary::Key(_, KeyCode::KEY_PLAYPAUSE, 0) => {
                         let dt: DateTime<Utc> = event.timestamp().into();
                         let now: DateTime<Utc> = SystemTime::now().into();
                         let iso = dt.format("%Y-%m-%dT%H:%M:%S%.3fZ").to_string();
@@ -250,7 +293,7 @@ async fn main() -> Result<(), slint::PlatformError> {
                             *hold_guard = false;
 
                             let mut clicks_guard = btn_click_count.lock().unwrap();
-                            *clicks_guard = *clicks_guard + 1;
+                            *clicks_guard += 1;
                             drop(clicks_guard);
                         }
 
@@ -311,6 +354,8 @@ async fn main() -> Result<(), slint::PlatformError> {
                                 1 => Some(TriggerAction::Toggle),
                                 2 => Some(TriggerAction::Next),
                                 3 => Some(TriggerAction::Previous),
+                                4 => Some(TriggerAction::AddBookmark),
+                                5 => Some(TriggerAction::ClearLoop),
                                 _ => None
                             }
                         };
@@ -318,15 +363,15 @@ async fn main() -> Result<(), slint::PlatformError> {
 
                         // idea: If hold, periodically send events in an extra thread
                         // until the next event comes in
-                        if trigger_action_opt.is_some() {
-                            let loop_event = if *hold_guard {true} else {false};
+                        if let Some(trigger_action) = trigger_action_opt {
+                            let loop_event = *hold_guard;
                             let tx = player_evt_tx_clone2.clone();
 
                             if loop_event {
 
                                 let handle = slint::spawn_local(async move {
                                     loop {
-                                        let _ = tx.send(PlayerEvent::ExternalTrigger(trigger_action_opt.unwrap()));
+                                        let _ = tx.send(PlayerEvent::ExternalTrigger(trigger_action));
                                         tokio::time::sleep(Duration::from_millis(MAGIC_REPETITIVE_ACTION_DELAY)).await;
                                         // thread::sleep(Duration::from_millis(MAGIC_REPETITIVE_ACTION_DELAY));
                                     }
@@ -334,7 +379,7 @@ async fn main() -> Result<(), slint::PlatformError> {
 
                                 ongoing_player_operation = Some(handle);
                             } else {
-                                let _ = tx.send(PlayerEvent::ExternalTrigger(trigger_action_opt.unwrap()));
+                                let _ = tx.send(PlayerEvent::ExternalTrigger(trigger_action));
                             }
 
                         }
@@ -404,6 +449,13 @@ async fn main() -> Result<(), slint::PlatformError> {
         }
     });
 
+    slint_audio_player.on_toggle({
+        let tx = player_cmd_tx.clone();
+        move || {
+            tx.send(PlayerCommand::Toggle()).unwrap();
+        }
+    });
+
     slint_audio_player.on_next({
         let tx = player_cmd_tx.clone();
         move || {
@@ -425,6 +477,171 @@ async fn main() -> Result<(), slint::PlatformError> {
         }
     });
 
+    slint_audio_player.on_play_media_at({
+        let tx = player_cmd_tx.clone();
+        move |id: SharedString, millis_i64: i64| {
+            tx.send(PlayerCommand::PlayMediaAt(
+                id.to_string(),
+                Duration::from_millis(millis_i64.max(0) as u64),
+            ))
+            .unwrap();
+        }
+    });
+
+    slint_audio_player.on_play_queue({
+        let tx = player_cmd_tx.clone();
+        move |ids: ModelRc<SharedString>, start_index: i32| {
+            let item_ids: Vec<String> = ids.iter().map(|id| id.to_string()).collect();
+            tx.send(PlayerCommand::PlayQueue(item_ids, start_index.max(0) as usize))
+                .unwrap();
+        }
+    });
+
+    slint_audio_player.on_cycle_repeat_mode({
+        let tx = player_cmd_tx.clone();
+        let ui = slint_app_window.as_weak();
+        move || {
+            if let Some(ui) = ui.upgrade() {
+                let current = slint_helpers::utils::slint_to_repeat_mode(ui.global::<SlintAudioPlayer>().get_repeat_mode());
+                tx.send(PlayerCommand::SetRepeatMode(current.cycle())).unwrap();
+            }
+        }
+    });
+
+    slint_audio_player.on_toggle_shuffle({
+        let tx = player_cmd_tx.clone();
+        let ui = slint_app_window.as_weak();
+        move || {
+            if let Some(ui) = ui.upgrade() {
+                let shuffle = ui.global::<SlintAudioPlayer>().get_shuffle();
+                tx.send(PlayerCommand::SetShuffle(!shuffle)).unwrap();
+            }
+        }
+    });
+
+    slint_audio_player.on_toggle_voice_boost({
+        let tx = player_cmd_tx.clone();
+        let ui = slint_app_window.as_weak();
+        move || {
+            if let Some(ui) = ui.upgrade() {
+                let enabled = ui.global::<SlintAudioPlayer>().get_voice_boost();
+                tx.send(PlayerCommand::SetVoiceBoost(!enabled)).unwrap();
+            }
+        }
+    });
+
+    slint_audio_player.on_toggle_series_autoplay({
+        let tx = player_cmd_tx.clone();
+        let ui = slint_app_window.as_weak();
+        move || {
+            if let Some(ui) = ui.upgrade() {
+                let enabled = ui.global::<SlintAudioPlayer>().get_series_autoplay();
+                tx.send(PlayerCommand::SetSeriesAutoplay(!enabled)).unwrap();
+            }
+        }
+    });
+
+    slint_audio_player.on_set_series_autoplay_default({
+        let tx = player_cmd_tx.clone();
+        move |enabled| {
+            tx.send(PlayerCommand::SetSeriesAutoplayDefault(enabled)).unwrap();
+        }
+    });
+
+    slint_audio_player.set_equalizer_presets(ModelRc::new(VecModel::from(
+        EqualizerPreset::all()
+            .iter()
+            .map(|preset| preset.name().to_shared_string())
+            .collect::<Vec<_>>(),
+    )));
+
+    slint_audio_player.on_select_equalizer_preset({
+        let tx = player_cmd_tx.clone();
+        move |name: SharedString| {
+            if let Some(preset) = EqualizerPreset::from_name(name.as_str()) {
+                tx.send(PlayerCommand::SelectEqualizerPreset(preset)).unwrap();
+            }
+        }
+    });
+
+    slint_audio_player.on_set_equalizer_gain({
+        let tx = player_cmd_tx.clone();
+        move |index: i32, gain: f32| {
+            tx.send(PlayerCommand::SetEqualizerGain(index as usize, gain)).unwrap();
+        }
+    });
+
+    slint_audio_player.on_set_crossfade({
+        let tx = player_cmd_tx.clone();
+        move |seconds: f32| {
+            tx.send(PlayerCommand::SetCrossfade(Duration::from_secs_f32(seconds.max(0.0)))).unwrap();
+        }
+    });
+
+    slint_audio_player.on_toggle_skip_silence({
+        let tx = player_cmd_tx.clone();
+        let ui = slint_app_window.as_weak();
+        move || {
+            if let Some(ui) = ui.upgrade() {
+                let enabled = ui.global::<SlintAudioPlayer>().get_skip_silence();
+                tx.send(PlayerCommand::SetSilenceSkip(!enabled)).unwrap();
+            }
+        }
+    });
+
+    slint_audio_player.on_set_loop({
+        let tx = player_cmd_tx.clone();
+        move |start: i64, end: i64| {
+            let start = Duration::from_millis(start.max(0) as u64);
+            let end = Duration::from_millis(end.max(0) as u64);
+            tx.send(PlayerCommand::SetLoop(start.min(end), start.max(end))).unwrap();
+        }
+    });
+
+    slint_audio_player.on_repeat_chapter({
+        let tx = player_cmd_tx.clone();
+        move || {
+            tx.send(PlayerCommand::RepeatChapter()).unwrap();
+        }
+    });
+
+    slint_audio_player.on_clear_loop({
+        let tx = player_cmd_tx.clone();
+        move || {
+            tx.send(PlayerCommand::ClearLoop()).unwrap();
+        }
+    });
+
+    slint_audio_player.on_add_bookmark({
+        let tx = player_cmd_tx.clone();
+        move |note: SharedString| {
+            let note = if note.is_empty() { None } else { Some(note.to_string()) };
+            tx.send(PlayerCommand::AddBookmark(note)).unwrap();
+        }
+    });
+
+    slint_audio_player.on_list_output_devices({
+        let tx = player_cmd_tx.clone();
+        move || {
+            tx.send(PlayerCommand::ListOutputDevices()).unwrap();
+        }
+    });
+
+    slint_audio_player.on_select_output({
+        let tx = player_cmd_tx.clone();
+        move |device_name: SharedString| {
+            tx.send(PlayerCommand::SelectOutput(device_name.to_string()))
+                .unwrap();
+        }
+    });
+
+    slint_audio_player.on_set_ui_visible({
+        let tx = player_cmd_tx.clone();
+        move |visible: bool| {
+            tx.send(PlayerCommand::SetUiVisible(visible)).unwrap();
+        }
+    });
+
     slint_audio_player.on_seek_to({
         let tx = player_cmd_tx.clone();
         move |millis_i64: i64| {
@@ -532,6 +749,92 @@ async fn main() -> Result<(), slint::PlatformError> {
         }
     });
 
+    slint_media_source.on_load_bookmarks({
+        let tx = source_cmd_tx.clone();
+        move |item_id| {
+            tx.send(MediaSourceCommand::Bookmarks(item_id.to_string()))
+                .unwrap();
+        }
+    });
+
+    slint_media_source.on_remove_bookmark({
+        let tx = source_cmd_tx.clone();
+        move |item_id, bookmark_id| {
+            tx.send(MediaSourceCommand::RemoveBookmark(item_id.to_string(), bookmark_id.to_string()))
+                .unwrap();
+        }
+    });
+
+    let bookmarks_export_path = format!("{}/bookmarks.json", data_dir.trim_end_matches("/"));
+    slint_media_source.on_export_bookmarks({
+        let tx = source_cmd_tx.clone();
+        move || {
+            tx.send(MediaSourceCommand::ExportBookmarks(bookmarks_export_path.clone()))
+                .unwrap();
+        }
+    });
+
+    slint_media_source.on_load_continue_listening({
+        let tx = source_cmd_tx.clone();
+        move || {
+            tx.send(MediaSourceCommand::ContinueListening).unwrap();
+        }
+    });
+
+    slint_media_source.on_set_listening_state({
+        let tx = source_cmd_tx.clone();
+        move |item_id, state| {
+            let state = slint_helpers::utils::slint_to_listening_state(state);
+            tx.send(MediaSourceCommand::SetListeningState(item_id.to_string(), state))
+                .unwrap();
+        }
+    });
+
+    slint_media_source.on_update_metadata({
+        let tx = source_cmd_tx.clone();
+        let ui_handle = slint_media_source_ui.as_weak();
+        move |item, title, artist, series, part, genre| {
+            let Some(ui) = ui_handle.upgrade() else {
+                return;
+            };
+            let inner = ui.global::<SlintMediaSource>();
+            inner.set_metadata_error(SharedString::default());
+            let update = slint_helpers::utils::slint_metadata_update(
+                &item, title, artist, series, part, genre, inner.get_metadata_chapter_names(),
+            );
+            tx.send(MediaSourceCommand::UpdateMetadata(item.id.to_string(), update))
+                .unwrap();
+        }
+    });
+
+    slint_media_source.on_begin_metadata_edit({
+        let ui_handle = slint_media_source_ui.as_weak();
+        move |item| {
+            if let Some(ui) = ui_handle.upgrade() {
+                // a copy, so that cancelled edits do not change the item
+                let names: Vec<SharedString> = item.chapters.iter().map(|c| c.name).collect();
+                ui.global::<SlintMediaSource>().set_metadata_chapter_names(ModelRc::new(VecModel::from(names)));
+            }
+        }
+    });
+
+    slint_media_source.on_load_listening_stats({
+        let tx = source_cmd_tx.clone();
+        move || {
+            tx.send(MediaSourceCommand::ListeningStats).unwrap();
+        }
+    });
+
+    let stats_csv_export_path = format!("{}/listening_sessions.csv", data_dir.trim_end_matches("/"));
+    let stats_json_export_path = format!("{}/listening_stats.json", data_dir.trim_end_matches("/"));
+    slint_media_source.on_export_listening_stats({
+        let tx = source_cmd_tx.clone();
+        move || {
+            tx.send(MediaSourceCommand::ExportListeningStats(stats_csv_export_path.clone(), stats_json_export_path.clone()))
+                .unwrap();
+        }
+    });
+
     let ui_handle = slint_media_source_ui.as_weak();
     slint::spawn_local(async move {
         // now owned in this async block
@@ -542,6 +845,17 @@ async fn main() -> Result<(), slint::PlatformError> {
 
                 match event {
                     MediaSourceEvent::FilterResults(items) => {
+                        let hide_finished = inner.get_hide_finished();
+                        let items: Vec<MediaSourceItem> = items
+                            .into_iter()
+                            .filter(|i| {
+                                !(hide_finished
+                                    && i.media_type == MediaType::Audiobook
+                                    && i.progress.state == MediaSourceListeningState::Finished)
+                            })
+                            .collect();
+                        let ids: Vec<SharedString> = items.iter().map(|i| i.id.to_shared_string()).collect();
+                        inner.set_filter_result_ids(ModelRc::new(VecModel::from(ids)));
                         inner.set_filter_results(slint_helpers::utils::rust_items_to_slint_model(items, false));
                     }
                     MediaSourceEvent::FindResult(opt_item) => {
@@ -552,6 +866,58 @@ async fn main() -> Result<(), slint::PlatformError> {
                             inner.set_find_results(slint::ModelRc::default());
                         }
                     }
+                    MediaSourceEvent::MetadataUpdated(item) => {
+                        let is_displayed = inner.get_find_results().iter().any(|i| i.id == item.id.as_str());
+                        if is_displayed {
+                            inner.set_find_results(slint_helpers::utils::rust_items_to_slint_model(vec![item], true));
+                        }
+                    }
+                    MediaSourceEvent::ContinueListeningResults(items) => {
+                        inner.set_continue_listening(slint_helpers::utils::rust_items_to_slint_model(items, false));
+                    }
+                    MediaSourceEvent::ListeningStateChanged(item) => {
+                        let is_displayed = inner.get_find_results().iter().any(|i| i.id == item.id.as_str());
+                        if is_displayed {
+                            inner.set_find_results(slint_helpers::utils::rust_items_to_slint_model(vec![item], true));
+                        }
+                    }
+                    MediaSourceEvent::MetadataUpdateFailed(id, message) => {
+                        println!("could not update metadata of item {}: {}", id, message);
+                        inner.set_metadata_error(message.into());
+                    }
+                    MediaSourceEvent::Reclassified(changed) => {
+                        println!("reclassified {} items", changed);
+                    }
+                    MediaSourceEvent::LibraryRootChanged(path, is_available) => {
+                        println!("library root {} is available: {}", path, is_available);
+                    }
+                    MediaSourceEvent::LibraryRootScanned(path) => {
+                        println!("library root {} has been scanned", path);
+                    }
+                    MediaSourceEvent::BookmarksResult(item_id, bookmarks) => {
+                        if inner.get_bookmarks_item_id() == item_id.as_str() {
+                            inner.set_bookmarks(slint_helpers::utils::rust_bookmarks_to_slint_model(bookmarks));
+                        }
+                    }
+                    MediaSourceEvent::BookmarksExported(path, count) => {
+                        println!("exported {} bookmarks to {}", count, path);
+                    }
+                    MediaSourceEvent::ListeningStatsResult(stats) => {
+                        let days = stats.days.iter().map(|(day, listened)| (day.format("%a").to_string(), *listened)).collect();
+                        let weeks = stats.weeks.iter().map(|(monday, listened)| (monday.format("%b %d").to_string(), *listened)).collect();
+                        inner.set_stats_days(slint_helpers::utils::stats_bars_to_slint_model(days));
+                        inner.set_stats_weeks(slint_helpers::utils::stats_bars_to_slint_model(weeks));
+                        inner.set_stats_top_authors(slint_helpers::utils::stats_bars_to_slint_model(stats.top_authors));
+                        inner.set_stats_total_listened(format_duration(stats.total_listened).to_shared_string());
+                        inner.set_stats_total_skipped(format_duration(stats.total_skipped).to_shared_string());
+                        inner.set_stats_current_streak(stats.current_streak as i32);
+                        inner.set_stats_longest_streak(stats.longest_streak as i32);
+                        let finished_books: Vec<SharedString> = stats.finished_books.iter().map(|t| t.to_shared_string()).collect();
+                        inner.set_stats_finished_books(ModelRc::new(VecModel::from(finished_books)));
+                    }
+                    MediaSourceEvent::ListeningStatsExported(count) => {
+                        println!("exported {} listening sessions", count);
+                    }
                 }
             } else {
                 // UI was dropped; stop listening
@@ -562,6 +928,7 @@ async fn main() -> Result<(), slint::PlatformError> {
     .unwrap();
 
     let ui_handle_player = slint_media_source_ui.as_weak();
+    let bookmarks_tx = source_cmd_tx.clone();
 
     slint::spawn_local(async move {
 
@@ -573,32 +940,116 @@ async fn main() -> Result<(), slint::PlatformError> {
                 let inner = ui.global::<SlintAudioPlayer>();
 
                 match event {
-                    PlayerEvent::Status(item_id, status) => {
+                    PlayerEvent::State(item_id, state) => {
                         inner.set_current_item_id(item_id.to_shared_string());
-                        inner.set_status(status.to_shared_string());
+                        inner.set_state(slint_helpers::utils::player_state_to_slint(state));
+                        if state == PlayerState::Playing {
+                            inner.set_error_message(SharedString::new());
+                        }
+                    }
+
+                    PlayerEvent::Error(error) => {
+                        inner.set_error_message(error.to_string().to_shared_string());
                     }
 
                     PlayerEvent::Stopped => {}
 
+                    PlayerEvent::OutputDeviceChanged(device_name) => {
+                        inner.set_output_device(device_name.unwrap_or_default().to_shared_string());
+                    }
+
+                    PlayerEvent::PlayModeChanged(repeat_mode, shuffle) => {
+                        inner.set_repeat_mode(slint_helpers::utils::repeat_mode_to_slint(repeat_mode));
+                        inner.set_shuffle(shuffle);
+                    }
+
+                    PlayerEvent::VoiceBoostChanged(enabled) => {
+                        inner.set_voice_boost(enabled);
+                    }
+
+                    PlayerEvent::SeriesAutoplayChanged(default_enabled, enabled) => {
+                        inner.set_series_autoplay_default(default_enabled);
+                        inner.set_series_autoplay(enabled);
+                    }
+
+                    PlayerEvent::EqualizerChanged(equalizer) => {
+                        inner.set_equalizer_preset(equalizer.preset.name().to_shared_string());
+                        inner.set_equalizer_bands(slint_helpers::utils::equalizer_bands_to_slint_model(&equalizer));
+                    }
+
+                    PlayerEvent::CrossfadeChanged(duration) => {
+                        inner.set_crossfade(duration.as_secs_f32());
+                    }
+
+                    PlayerEvent::SilenceSkipChanged(enabled) => {
+                        inner.set_skip_silence(enabled);
+                    }
+
+                    PlayerEvent::SilenceTimeSaved(time_saved) => {
+                        inner.set_silence_time_saved(format_duration(time_saved).to_shared_string());
+                    }
+
+                    PlayerEvent::LoopChanged(section_loop) => {
+                        inner.set_loop_active(section_loop.is_some());
+                        if let Some(section_loop) = section_loop {
+                            inner.set_loop_start(section_loop.start.as_millis() as i64);
+                            inner.set_loop_end(section_loop.end.as_millis() as i64);
+                            inner.set_loop_repetitions(section_loop.repetitions as i32);
+                        }
+                    }
+
+                    PlayerEvent::BookmarkAdded(bookmark) => {
+                        let media_source = ui.global::<SlintMediaSource>();
+                        if media_source.get_bookmarks_item_id() == bookmark.item_id.as_str() {
+                            let _ = bookmarks_tx.send(MediaSourceCommand::Bookmarks(bookmark.item_id));
+                        }
+                    }
+
+                    PlayerEvent::OutputDevices(devices) => {
+                        let slint_devices: Vec<SlintOutputDevice> = devices
+                            .iter()
+                            .map(|d| SlintOutputDevice {
+                                host: d.host.to_shared_string(),
+                                name: d.name.to_shared_string(),
+                                configs: d.configs_summary().to_shared_string(),
+                            })
+                            .collect();
+                        inner.set_output_devices(ModelRc::new(VecModel::from(slint_devices)));
+                    }
+
                     PlayerEvent::Position(item_id, position) => {
 
                         inner.set_current_item_id(item_id.to_shared_string());
-                        inner.set_position_formatted(format_duration(position).to_shared_string());
+                        inner.set_position(position.position.as_millis() as i64);
+                        inner.set_position_formatted(format_duration(position.position).to_shared_string());
+                        inner.set_duration(position.duration.as_millis() as i64);
+                        inner.set_remaining(position.remaining.as_millis() as i64);
+                        inner.set_remaining_formatted(format_duration(position.remaining).to_shared_string());
+                        inner.set_chapter_index(position.chapter_index.map(|i| i as i32).unwrap_or(-1));
+                        inner.set_chapter_position(position.chapter_position.as_millis() as i64);
+                        inner.set_chapter_duration(position.chapter_duration.as_millis() as i64);
+                        inner.set_chapter_position_formatted(format_duration_short(position.chapter_position).to_shared_string());
+                        inner.set_chapter_duration_formatted(format_duration_short(position.chapter_duration).to_shared_string());
+                        inner.set_chapter_remaining_formatted(format_duration_short(position.chapter_remaining).to_shared_string());
+                    }
+                    PlayerEvent::ChapterChanged(item_id, index, name) => {
+                        inner.set_current_item_id(item_id.to_shared_string());
+                        inner.set_chapter_index(index.map(|i| i as i32).unwrap_or(-1));
+                        inner.set_chapter_name(name.unwrap_or_default().to_shared_string());
                     }
                     PlayerEvent::ExternalTrigger(trigger_action) => {
                         // println!("trigger action: {:?}", trigger_action);
 
                         match trigger_action {
-                            TriggerAction::Toggle => if inner.get_status().to_string() == "playing" {
-                                inner.invoke_pause();
-                            } else {
-                                inner.invoke_play();
-                            }
+                            // the player decides based on its own state
+                            TriggerAction::Toggle => {inner.invoke_toggle();}
                             TriggerAction::Next => {inner.invoke_next();}
                             TriggerAction::Previous => {inner.invoke_previous();}
                             TriggerAction::StepBack => {inner.invoke_seek_relative(-15000);}
                             TriggerAction::StepForward => {inner.invoke_seek_relative(15000);}
-                            TriggerAction::StopOngoing => if inner.get_status().to_string() == "playing" {
+                            TriggerAction::AddBookmark => {inner.invoke_add_bookmark(SharedString::new());}
+                            TriggerAction::ClearLoop => {inner.invoke_clear_loop();}
+                            TriggerAction::StopOngoing => if inner.get_state() == SlintPlayerState::Playing {
                                 inner.invoke_play();
                             } else {
                                 inner.invoke_pause();
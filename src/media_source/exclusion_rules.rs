@@ -0,0 +1,51 @@
+use globset::{Glob, GlobSet, GlobSetBuilder};
+use serde::{Deserialize, Serialize};
+
+/// gitignore-style files that exclude paths from scanning, valid for their
+/// directory and all subdirectories
+pub const PLAYER_IGNORE_FILENAME: &str = ".playerignore";
+
+/// Global exclusions, applied to every library root in addition to `.playerignore` files
+#[derive(Debug, Clone)]
+#[derive(Serialize, Deserialize)]
+pub struct ExclusionRules {
+    // globs relative to the library root, matching directories skips their contents
+    #[serde(default)]
+    pub exclude_globs: Vec<String>,
+    // hidden files and directories (starting with a dot, e.g. macOS ._ resource forks)
+    #[serde(default)]
+    pub include_hidden: bool,
+}
+
+impl ExclusionRules {
+    pub fn defaults() -> Self {
+        Self {
+            exclude_globs: vec![
+                "**/@eaDir".to_string(),
+                "**/#recycle".to_string(),
+                "**/#snapshot".to_string(),
+                "**/$RECYCLE.BIN".to_string(),
+                "**/System Volume Information".to_string(),
+                "**/lost+found".to_string(),
+                "**/.Trash*".to_string(),
+                "**/._*".to_string(),
+            ],
+            include_hidden: false,
+        }
+    }
+
+    pub fn build_glob_set(&self) -> GlobSet {
+        let mut builder = GlobSetBuilder::new();
+        for exclude_glob in &self.exclude_globs {
+            match Glob::new(exclude_glob) {
+                Ok(glob) => {
+                    builder.add(glob);
+                }
+                Err(e) => {
+                    println!("invalid exclude glob {}: {}", exclude_glob, e);
+                }
+            }
+        }
+        builder.build().unwrap_or_else(|_| GlobSet::empty())
+    }
+}
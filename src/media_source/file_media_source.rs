@@ -1,61 +1,94 @@
-use crate::item;
+use crate::entity::item;
 use async_trait::async_trait;
-use chrono::{DateTime, Local, Utc};
+use chrono::{DateTime, Local, NaiveDate, Utc};
 use image::imageops::FilterType;
-use image::{load_from_memory, DynamicImage, GenericImageView};
+use image::{load_from_memory, GenericImageView};
 use lofty::error::LoftyError;
-use lofty::file::{AudioFile, TaggedFileExt};
+use lofty::file::{AudioFile, FileType, TaggedFileExt};
 use lofty::picture::MimeType;
 use lofty::probe::Probe;
 use lofty::tag::TagType::Mp4Ilst;
-use lofty::tag::{Accessor, Tag};
+use lofty::tag::{Accessor, ItemKey, Tag, TagExt};
+use lofty::config::{ParseOptions, WriteOptions};
+use lofty::id3::v2::{Frame, FrameFlags, FrameId, SynchronizedTextFrame, TimestampFormat};
+use lofty::mpeg::{Layer, MpegFile, MpegProperties, MpegVersion};
 use std::{
+    collections::hash_map::Entry,
+    collections::{HashMap, HashSet},
     fs,
-    io::Read,
+    io,
     path::{Path, PathBuf},
     sync::{Arc, Mutex},
     time::Duration
 };
-use tokio::sync::mpsc::{UnboundedReceiver, UnboundedSender};
-use walkdir::WalkDir;
+use tokio::sync::mpsc::{unbounded_channel, UnboundedReceiver, UnboundedSender};
+use ignore::WalkBuilder;
 
 use crate::entity::item::{ActiveModel, ActiveModelEx};
-use crate::entity::items_json_metadata::JsonTagField::Chapters;
+use crate::entity::items_json_metadata::JsonTagField::{Chapters, Lyrics, ReplayGain};
 use crate::entity::items_metadata::TagField::*;
 use crate::entity::items_metadata::{Entity, TagField};
-use crate::entity::{items_json_metadata, items_metadata};
+use crate::entity::{items_bookmarks, items_json_metadata, items_listening_sessions, items_listening_states, items_metadata, items_progress_history};
 use mp4ameta::FreeformIdent;
 use sea_orm::prelude::HasMany;
-use sea_orm::{ColumnTrait, DatabaseConnection, EntityTrait, HasManyModel, QueryFilter};
+use sea_orm::{ActiveValue::Set, ColumnTrait, DatabaseConnection, DatabaseTransaction, DbErr, EntityTrait, HasManyModel, QueryFilter, QueryOrder, QuerySelect, TransactionTrait};
 use xxhash_rust::xxh3::xxh3_64;
-use crate::media_source::media_source::MediaSource;
+use crate::media_source::media_source::{MediaSource, MediaSourceBookmark, MediaSourceError, MediaSourceListeningSession, MediaSourceMetadataUpdate, MediaSourceProgressReason};
+use crate::media_source::media_source_listening_stats::MediaSourceListeningStats;
+use crate::media_source::media_source_listening_state::MediaSourceListeningState;
+use crate::media_source::media_source_listening_progress::MediaSourceListeningProgress;
 use crate::media_source::media_source_chapter::MediaSourceChapter;
+use crate::media_source::media_source_lyrics::MediaSourceLyrics;
+use crate::media_source::media_source_lyrics_line::MediaSourceLyricsLine;
+use crate::media_source::media_source_replay_gain::MediaSourceReplayGain;
+use crate::media_source::loudness_analyzer::LoudnessAnalyzer;
 use crate::media_source::media_source_command::MediaSourceCommand;
 use crate::media_source::media_source_event::MediaSourceEvent;
 use crate::media_source::media_source_image_codec::MediaSourceImageCodec;
 use crate::media_source::media_source_item::MediaSourceItem;
 use crate::media_source::media_source_metadata::MediaSourceMetadata;
 use crate::media_source::media_source_picture::MediaSourcePicture;
-use crate::media_source::media_type::MediaType;
+use crate::media_source::media_type_classifier::MediaTypeClassifier;
+use crate::media_source::media_type_rule::MediaTypeRule;
+use crate::media_source::library_root::LibraryRoot;
+use crate::media_source::exclusion_rules::{ExclusionRules, PLAYER_IGNORE_FILENAME};
+use crate::media_source::utils::{convert_db_listening_state, convert_db_media_type, convert_listening_state_to_db, convert_media_type_to_db, convert_progress_reason_to_db};
+use crate::settings::settings_manager::SettingsManager;
+use sea_orm::sea_query::{Expr, OnConflict};
+
+const SETTINGS_KEY_MEDIA_TYPE_RULES: &str = "media_source.media_type_rules";
+const SETTINGS_KEY_EXCLUSION_RULES: &str = "media_source.exclusion_rules";
+
+const LRC_EXTENSION: &str = "lrc";
+const ITUNES_NORMALIZATION_MP4_KEY: &str = "----:com.apple.iTunes:iTunNORM";
+const ITUNES_NORMALIZATION_KEY: &str = "iTunNORM";
+const LIBRARY_ROOT_POLL_INTERVAL: Duration = Duration::from_secs(5);
+const CONTINUE_LISTENING_LIMIT: u64 = 10;
+const MISSING_ITEMS_CHUNK_SIZE: usize = 500;
 
 #[derive(Clone)]
 pub struct FileMediaSource {
     pub db: DatabaseConnection,
-    pub base_path: String,
+    pub cache_dir: String,
+    settings: SettingsManager,
     state: Arc<Mutex<FileMediaSourceState>>,
 }
 
 struct FileMediaSourceState {
-    pub base_path: String,
+    pub library_roots: Vec<LibraryRoot>,
+    // paths of the library roots that were available on the last check
+    pub available_roots: Vec<String>,
 }
 
 impl FileMediaSource {
-    pub fn new(db: DatabaseConnection, base_path: String) -> Self {
+    pub fn new(db: DatabaseConnection, settings: SettingsManager, library_roots: Vec<LibraryRoot>, cache_dir: String) -> Self {
         Self {
             db,
-            base_path: base_path.clone(),
+            cache_dir,
+            settings,
             state: Arc::new(Mutex::new(FileMediaSourceState {
-                base_path
+                library_roots,
+                available_roots: vec![],
             })),
         }
     }
@@ -71,8 +104,222 @@ impl FileMediaSource {
             part: None,
             cover: None,
             chapters: vec![],
+            lyrics: None,
+            replay_gain: None,
+            duration: Duration::ZERO,
         }
     }
+    fn map_db_bookmark(bookmark: &items_bookmarks::Model) -> MediaSourceBookmark {
+        MediaSourceBookmark {
+            id: bookmark.id.to_string(),
+            item_id: bookmark.item_id.to_string(),
+            position: Duration::from_millis(bookmark.position.max(0) as u64),
+            chapter_index: bookmark.chapter_index.map(|i| i as usize),
+            chapter_name: bookmark.chapter_name.clone(),
+            note: bookmark.note.clone(),
+            date_created: bookmark.date_created,
+        }
+    }
+
+    /// Writes all bookmarks as JSON file, returns the number of exported bookmarks
+    pub async fn export_bookmarks(&self, path: &str) -> Result<usize, MediaSourceError> {
+        let bookmarks = items_bookmarks::Entity::find()
+            .order_by_asc(items_bookmarks::Column::ItemId)
+            .order_by_asc(items_bookmarks::Column::Position)
+            .all(&self.db)
+            .await?;
+
+        let mut items: HashMap<i32, Option<MediaSourceItem>> = HashMap::new();
+        let mut entries = vec![];
+        for bookmark in &bookmarks {
+            if let Entry::Vacant(entry) = items.entry(bookmark.item_id) {
+                entry.insert(self.find(&bookmark.item_id.to_string()).await);
+            }
+            let item = items.get(&bookmark.item_id).and_then(|i| i.as_ref());
+            entries.push(serde_json::json!({
+                "item_id": bookmark.item_id.to_string(),
+                "item_title": item.map(|i| i.title.clone()),
+                "item_location": item.map(|i| i.location.clone()),
+                "position": bookmark.position,
+                "chapter_index": bookmark.chapter_index,
+                "chapter_name": bookmark.chapter_name,
+                "note": bookmark.note,
+                "date_created": bookmark.date_created.to_rfc3339(),
+            }));
+        }
+
+        let json = serde_json::to_string_pretty(&entries).map_err(io::Error::other)?;
+        fs::write(path, json)?;
+        Ok(entries.len())
+    }
+
+    fn map_db_listening_session(session: &items_listening_sessions::Model) -> MediaSourceListeningSession {
+        MediaSourceListeningSession {
+            item_id: session.item_id.to_string(),
+            session_key: session.session_key.clone(),
+            start: session.date_start,
+            end: session.date_end,
+            start_position: Duration::from_millis(session.start_position.max(0) as u64),
+            end_position: Duration::from_millis(session.end_position.max(0) as u64),
+            listened: Duration::from_millis(session.listened.max(0) as u64),
+            skipped: Duration::from_millis(session.skipped.max(0) as u64),
+        }
+    }
+
+    /// All listening sessions ordered by start and the items they belong to
+    async fn listening_sessions(&self) -> Result<(Vec<MediaSourceListeningSession>, HashMap<String, MediaSourceItem>), DbErr> {
+        let sessions: Vec<MediaSourceListeningSession> = items_listening_sessions::Entity::find()
+            .order_by_asc(items_listening_sessions::Column::DateStart)
+            .all(&self.db)
+            .await?
+            .iter()
+            .map(Self::map_db_listening_session)
+            .collect();
+
+        let mut items = HashMap::new();
+        for session in &sessions {
+            if items.contains_key(&session.item_id) {
+                continue;
+            }
+            if let Some(item) = self.find(&session.item_id).await {
+                items.insert(session.item_id.clone(), item);
+            }
+        }
+        Ok((sessions, items))
+    }
+
+    pub async fn listening_stats(&self) -> MediaSourceListeningStats {
+        match self.listening_sessions().await {
+            Ok((sessions, items)) => MediaSourceListeningStats::from_sessions(&sessions, &items, Local::now().date_naive()),
+            Err(e) => {
+                println!("could not load listening sessions: {}", e);
+                MediaSourceListeningStats::default()
+            }
+        }
+    }
+
+    /// Writes all sessions as CSV and the statistics as JSON file, returns the number of exported sessions
+    pub async fn export_listening_stats(&self, csv_path: &str, json_path: &str) -> Result<usize, MediaSourceError> {
+        let (sessions, items) = self.listening_sessions().await?;
+
+        let mut csv = String::from("start,end,item_id,item_title,author,start_position,end_position,listened,skipped\n");
+        for session in &sessions {
+            let item = items.get(&session.item_id);
+            let fields = [
+                session.start.to_rfc3339(),
+                session.end.to_rfc3339(),
+                session.item_id.clone(),
+                item.map(|i| i.title.clone()).unwrap_or_default(),
+                item.and_then(|i| i.metadata.artist.clone()).unwrap_or_default(),
+                session.start_position.as_millis().to_string(),
+                session.end_position.as_millis().to_string(),
+                session.listened.as_millis().to_string(),
+                session.skipped.as_millis().to_string(),
+            ];
+            let escaped: Vec<String> = fields.iter().map(|f| escape_csv_field(f)).collect();
+            csv.push_str(&escaped.join(","));
+            csv.push('\n');
+        }
+        fs::write(csv_path, csv)?;
+
+        let stats = MediaSourceListeningStats::from_sessions(&sessions, &items, Local::now().date_naive());
+        let durations = |entries: &Vec<(NaiveDate, Duration)>| -> Vec<serde_json::Value> {
+            entries
+                .iter()
+                .map(|(date, listened)| serde_json::json!({ "date": date.to_string(), "listened": listened.as_millis() as u64 }))
+                .collect()
+        };
+        let json = serde_json::json!({
+            "days": durations(&stats.days),
+            "weeks": durations(&stats.weeks),
+            "total_listened": stats.total_listened.as_millis() as u64,
+            "total_skipped": stats.total_skipped.as_millis() as u64,
+            "current_streak": stats.current_streak,
+            "longest_streak": stats.longest_streak,
+            "finished_books": stats.finished_books,
+            "top_authors": stats.top_authors
+                .iter()
+                .map(|(author, listened)| serde_json::json!({ "author": author, "listened": listened.as_millis() as u64 }))
+                .collect::<Vec<_>>(),
+            "sessions": sessions.len(),
+        });
+        let json = serde_json::to_string_pretty(&json).map_err(io::Error::other)?;
+        fs::write(json_path, json)?;
+        Ok(sessions.len())
+    }
+
+    /// Items in progress, most recently played first
+    pub async fn continue_listening(&self) -> Vec<MediaSourceItem> {
+        // items that cannot be played right now are left out before the limit applies
+        let result = items_listening_states::Entity::find()
+            .inner_join(item::Entity)
+            .filter(items_listening_states::Column::State.eq(items_listening_states::ListeningState::InProgress))
+            .filter(item::Column::IsOffline.eq(false))
+            .filter(item::Column::IsMissing.eq(false))
+            .order_by_desc(items_listening_states::Column::DateModified)
+            .limit(CONTINUE_LISTENING_LIMIT)
+            .all(&self.db)
+            .await;
+        let states = match result {
+            Ok(states) => states,
+            Err(e) => {
+                println!("could not load items in progress: {}", e);
+                return vec![];
+            }
+        };
+        let mut items = vec![];
+        for state in states {
+            if let Some(item) = self.find(&state.item_id.to_string()).await {
+                items.push(item);
+            }
+        }
+        items
+    }
+
+    /// Marks an item manually, e.g. as finished without listening to the end
+    pub async fn set_listening_state(&self, id: &str, state: MediaSourceListeningState) -> Result<MediaSourceItem, MediaSourceError> {
+        let item = self.find(id).await.ok_or(MediaSourceError::NotFound(id.to_string()))?;
+        // starting over as new item
+        let position = match state {
+            MediaSourceListeningState::New => Duration::ZERO,
+            _ => item.progress.position,
+        };
+        self.store_listening_progress(id, position, state, true).await?;
+        self.find(id).await.ok_or(MediaSourceError::NotFound(id.to_string()))
+    }
+
+    async fn store_listening_progress(
+        &self,
+        id: &str,
+        position: Duration,
+        state: MediaSourceListeningState,
+        is_manual: bool,
+    ) -> Result<(), MediaSourceError> {
+        let item_id: i32 = id.parse().map_err(|_| MediaSourceError::NotFound(id.to_string()))?;
+        let model = items_listening_states::ActiveModel {
+            item_id: Set(item_id),
+            state: Set(convert_listening_state_to_db(&state)),
+            position: Set(position.as_millis() as i64),
+            is_manual: Set(is_manual),
+            date_modified: Set(Utc::now()),
+            ..Default::default()
+        };
+        items_listening_states::Entity::insert(model)
+            .on_conflict(
+                OnConflict::column(items_listening_states::Column::ItemId)
+                    .update_columns([
+                        items_listening_states::Column::State,
+                        items_listening_states::Column::Position,
+                        items_listening_states::Column::IsManual,
+                        items_listening_states::Column::DateModified,
+                    ])
+                    .to_owned(),
+            )
+            .exec(&self.db)
+            .await?;
+        Ok(())
+    }
+
     pub fn map_db_model_to_media_item(&self, i: &item::ModelEx, metadata: &HasMany<items_metadata::Entity>, json: &HasMany<items_json_metadata::Entity>) -> MediaSourceItem {
         let mut title : String = String::from("");
         let mut genre : Option<String> = None;
@@ -86,12 +333,10 @@ impl FileMediaSource {
             hash: i.cover_hash.clone(),
             codec: MediaSourceImageCodec::Jpeg
         });
-        let filename = i.location.split('/').last();
-        if filename.is_some() {
-            let filename_no_ext = filename.unwrap().split('.').next();
-            if filename_no_ext.is_some() {
-                title = filename_no_ext.unwrap().to_string();
-            }
+        if let Some(filename) = i.location.split('/').next_back()
+            && let Some(filename_no_ext) = filename.split('.').next()
+        {
+            title = filename_no_ext.to_string();
         }
 
         for tag in metadata {
@@ -107,6 +352,8 @@ impl FileMediaSource {
         }
 
         let mut chapters: Vec<MediaSourceChapter> = Vec::new();
+        let mut lyrics: Option<MediaSourceLyrics> = None;
+        let mut replay_gain: Option<MediaSourceReplayGain> = None;
 
         for json_tag in json {
             match json_tag.tag_field {
@@ -115,15 +362,33 @@ impl FileMediaSource {
                         chapters = chaps;
                     }
                 },
-                _ => {}
+                Lyrics => {
+                    if let Ok(lyr) = serde_json::from_str(&json_tag.value) {
+                        lyrics = Some(lyr);
+                    }
+                }
+                ReplayGain => {
+                    if let Ok(gain) = serde_json::from_str(&json_tag.value) {
+                        replay_gain = Some(gain);
+                    }
+                }
             }
         }
 
+        let mut progress = MediaSourceListeningProgress::default();
+        for listening_state in &i.listening_states {
+            progress = MediaSourceListeningProgress {
+                state: convert_db_listening_state(&listening_state.state),
+                position: Duration::from_millis(listening_state.position.max(0) as u64),
+                date_modified: Some(listening_state.date_modified),
+            };
+        }
+
         MediaSourceItem {
             id: i.id.to_string(),
-            location: format!("{}/{}", self.base_path.clone().trim_end_matches('/'), i.location.trim_start_matches('/').to_string()),
+            location: format!("{}/{}", i.library_root.trim_end_matches('/'), i.location.trim_start_matches('/')),
             title: title.clone(),
-            media_type: MediaType::Unspecified,
+            media_type: convert_db_media_type(&i.media_type),
             metadata: MediaSourceMetadata {
                 title: Some(title.clone()),
                 artist,
@@ -133,24 +398,25 @@ impl FileMediaSource {
                 series,
                 part,
                 cover,
-                chapters
+                chapters,
+                lyrics,
+                replay_gain,
+                duration: Duration::from_millis(i.duration.max(0) as u64),
             },
+            progress,
         }
     }
 
 
-    async fn upsert_item(&self, id: i32, file_id: String, media_type: item::MediaType, location: String, meta: &MediaSourceMetadata) -> ActiveModelEx {
+    async fn upsert_item(&self, id: i32, file_id: String, media_type: item::MediaType, library_root: String, location: String, meta: &MediaSourceMetadata) -> Result<ActiveModelEx, DbErr> {
         // todo: improve this
         // see https://www.sea-ql.org/blog/2025-11-25-sea-orm-2.0/
         let db = self.db.clone();
         let now = Utc::now();
         let cover = meta.cover.clone();
 
-        let cover_hash = if cover.is_some() {
-            cover.unwrap().hash
-        } else {
-            String::from("")
-        };
+        let cover_hash = cover.map(|c| c.hash).unwrap_or_default();
+        let duration = meta.duration.as_millis() as i64;
 
 
 
@@ -162,8 +428,12 @@ impl FileMediaSource {
             ActiveModel::builder()
                 .set_file_id(file_id)
                 .set_media_type(media_type)
+                .set_library_root(library_root.clone())
                 .set_location(location.trim_start_matches('/'))
+                .set_is_offline(false)
+                .set_is_missing(false)
                 .set_cover_hash(cover_hash)
+                .set_duration(duration)
                 .set_last_scan_random_key("")
                 .set_date_modified(now)
                 //.add_metadatum(metadata_items)
@@ -173,21 +443,37 @@ impl FileMediaSource {
                 .set_id(id)
                 .set_file_id(file_id)
                 .set_media_type(media_type)
+                .set_library_root(library_root.clone())
                 .set_location(location.trim_start_matches('/'))
+                .set_is_offline(false)
+                .set_is_missing(false)
                 .set_cover_hash(cover_hash)
+                .set_duration(duration)
                 .set_last_scan_random_key("")
                 .set_date_modified(now)
 
         };
 
 
+        // a re-scan replaces the tag rows instead of appending to them
+        let txn = db.begin().await?;
+        if id != 0 {
+            items_metadata::Entity::delete_many()
+                .filter(items_metadata::Column::ItemId.eq(id))
+                .exec(&txn)
+                .await?;
+            items_json_metadata::Entity::delete_many()
+                .filter(items_json_metadata::Column::ItemId.eq(id))
+                .exec(&txn)
+                .await?;
+        }
+
         let mut result = builder
             // .add_metadatum()
             // .add_picture()
             // .add_progress_history()
-            .save(&db)
-            .await
-            .expect("todo");
+            .save(&txn)
+            .await?;
 
 
         // now sync the metadata
@@ -212,80 +498,193 @@ impl FileMediaSource {
 
         }
 
-        let res = result.save(&db).await;
+        if let Some(lyrics) = &meta.lyrics
+            && let Ok(lyrics_json) = serde_json::to_string(lyrics)
+        {
+            let lyrics_model = items_json_metadata::ActiveModel::builder()
+                .set_tag_field(Lyrics)
+                .set_value(lyrics_json)
+                .set_date_modified(now);
+            result.json.push(lyrics_model);
+        }
+
+        if let Some(replay_gain) = &meta.replay_gain
+            && let Ok(replay_gain_json) = serde_json::to_string(replay_gain)
+        {
+            let replay_gain_model = items_json_metadata::ActiveModel::builder()
+                .set_tag_field(ReplayGain)
+                .set_value(replay_gain_json)
+                .set_date_modified(now);
+            result.json.push(replay_gain_model);
+        }
 
-        res.unwrap()
+        let result = result.save(&txn).await?;
+        txn.commit().await?;
+        Ok(result)
     }
 
     fn add_metadata(&self, metadata: &mut HasManyModel<Entity>, tag_field: TagField, value: Option<String>, date_modified: DateTime<Utc>) {
-        if value.is_some() {
+        if let Some(value) = value {
             metadata.push(items_metadata::ActiveModel::builder()
                 .set_tag_field(tag_field)
-                .set_value(value.unwrap())
+                .set_value(value)
                 .set_date_modified(date_modified));
         }
     }
 
 
     fn cache_path(&self) -> String {
-        let inner = self.state.lock().unwrap();
-        let cache_path = format!("{}/{}", inner.base_path.trim_end_matches('/').to_string(), self.rel_cache_path());
-        drop(inner);
-        cache_path
+        format!("{}/", self.cache_dir.trim_end_matches('/'))
     }
 
-    fn rel_cache_path(&self) -> String {
-        String::from("cache/")
+    /// Scans all library roots that are currently available and marks the items
+    /// of missing roots as offline
+    pub async fn scan_media(&self) {
+        let library_roots = self.state.lock().unwrap().library_roots.clone();
+
+        let mut available_roots: Vec<String> = Vec::new();
+        for library_root in library_roots {
+            let is_available = library_root.is_available();
+            self.set_library_root_offline(&library_root, !is_available).await;
+            if !is_available {
+                println!("library root is not available: {}", library_root.path);
+                continue;
+            }
+            self.scan_library_root(&library_root).await;
+            available_roots.push(library_root.path.clone());
+        }
+
+        let mut inner = self.state.lock().unwrap();
+        inner.available_roots = available_roots;
     }
 
-    pub async fn scan_media(&self) {
-        let audio_extensions = vec!("mp3", "m4b");
-        let inner = self.state.lock().unwrap();
-        let base_path = inner.base_path.clone();
+    /// Checks which library roots appeared or vanished since the last check and updates
+    /// the offline flag of their items (they are never deleted). Appeared roots still
+    /// have to be scanned, see `spawn_library_root_scan`
+    pub async fn update_library_roots(&self) -> Vec<(LibraryRoot, bool)> {
+        let (library_roots, previously_available) = {
+            let inner = self.state.lock().unwrap();
+            (inner.library_roots.clone(), inner.available_roots.clone())
+        };
+
+        let mut changes: Vec<(LibraryRoot, bool)> = Vec::new();
+        let mut available_roots: Vec<String> = Vec::new();
+        for library_root in library_roots {
+            let is_available = library_root.is_available();
+            let was_available = previously_available.contains(&library_root.path);
+            if is_available {
+                available_roots.push(library_root.path.clone());
+            }
+            if is_available == was_available {
+                continue;
+            }
+
+            self.set_library_root_offline(&library_root, !is_available).await;
+            changes.push((library_root, is_available));
+        }
+
+        let mut inner = self.state.lock().unwrap();
+        inner.available_roots = available_roots;
+        changes
+    }
+
+    /// Scans the library root on the runtime, so that a large root does not block the
+    /// command loop. The path is sent to `done_tx` when the scan is finished
+    fn spawn_library_root_scan(&self, library_root: LibraryRoot, done_tx: UnboundedSender<String>) {
+        let source = self.clone();
+        tokio::spawn(async move {
+            source.scan_library_root(&library_root).await;
+            let _ = done_tx.send(library_root.path);
+        });
+    }
+
+    async fn set_library_root_offline(&self, library_root: &LibraryRoot, is_offline: bool) {
+        let result = item::Entity::update_many()
+            .col_expr(item::Column::IsOffline, Expr::value(is_offline))
+            .filter(item::Column::LibraryRoot.eq(library_root.path.clone()))
+            .exec(&self.db)
+            .await;
+        if let Err(e) = result {
+            println!("could not update library root {}: {}", library_root.path, e);
+        }
+    }
+
+    async fn scan_library_root(&self, library_root: &LibraryRoot) {
+        let audio_extensions = ["mp3", "m4b"];
+        let base_path = library_root.path.clone();
         let db = self.db.clone();
-        drop(inner);
 
+        let classifier = MediaTypeClassifier::new(self.media_type_rules().await);
 
 
-        let audio_files = WalkDir::new(base_path.clone())
-            .into_iter()
+        let exclusion_rules = self.exclusion_rules().await;
+        let exclude_glob_set = exclusion_rules.build_glob_set();
+        let root_path = PathBuf::from(base_path.clone());
+
+        let mut walk_builder = WalkBuilder::new(base_path.clone());
+        walk_builder
+            .standard_filters(false)
+            .hidden(!exclusion_rules.include_hidden)
+            .add_custom_ignore_filename(PLAYER_IGNORE_FILENAME)
+            .filter_entry(move |e| {
+                let rel_path = e.path().strip_prefix(&root_path).unwrap_or(e.path());
+                !exclude_glob_set.is_match(rel_path)
+            });
+
+        let audio_files: Vec<PathBuf> = walk_builder.build()
             .filter_map(|e| e.ok())
             .filter(|e| {
-                let e_clone = e.clone();
-                let metadata = e_clone.metadata().unwrap();
-                if !metadata.is_file() {
-                    false;
+                if !e.file_type().is_some_and(|t| t.is_file()) {
+                    return false;
                 }
-                let path = e_clone.into_path();
-                return match path.extension() {
+                match e.path().extension() {
                     Some(ext) => {
-                        audio_extensions.contains(&ext.to_str().unwrap())
+                        audio_extensions.contains(&ext.to_str().unwrap_or(""))
                     }
                     None => false,
                 }
+            })
+            .map(|e| e.into_path())
+            .collect();
 
-            });
-
+        // locations as stored in the db, everything else on this root is hidden
+        let mut found_locations: HashSet<String> = HashSet::new();
         for audio_file in audio_files {
-            let full_path = audio_file.path().to_str().unwrap().to_string();
+            let Some(full_path) = audio_file.to_str().map(|p| p.to_string()) else {
+                println!("skipping file with non utf-8 path: {:?}", audio_file);
+                continue;
+            };
             let start_index = base_path.len();
             let rel_path = full_path[start_index..].to_string();
-            let media_type = if rel_path.starts_with("/music/") {
-                item::MediaType::Music
-            } else if rel_path.starts_with("/audiobooks/") {
-                item::MediaType::Audiobook
-            } else {
-                item::MediaType::Unspecified
-            };
+            found_locations.insert(rel_path.trim_start_matches('/').to_string());
 
             // update file modification time
             // let file = File::create("Foo.txt").unwrap();
             // file.set_modified(SystemTime::now()).unwrap();
 
 
-            let file_id = file_id::get_file_id(full_path.clone()).unwrap();
+            let file_id = match file_id::get_file_id(full_path.clone()) {
+                Ok(file_id) => file_id,
+                Err(e) => {
+                    println!("skipping {}, could not get file id: {}", full_path, e);
+                    continue;
+                }
+            };
             let file_id_str = format!("{:?}", file_id);
-            let file_date_modified = audio_file.path().metadata().unwrap().modified().unwrap();
+            let mut file_date_modified = match audio_file.metadata().and_then(|m| m.modified()) {
+                Ok(date_modified) => date_modified,
+                Err(e) => {
+                    println!("skipping {}, could not read modification time: {}", full_path, e);
+                    continue;
+                }
+            };
+            // changed sidecar lyrics have to trigger an update, too
+            if let Ok(lrc_metadata) = audio_file.with_extension(LRC_EXTENSION).metadata()
+                && let Ok(lrc_date_modified) = lrc_metadata.modified()
+                && lrc_date_modified > file_date_modified
+            {
+                file_date_modified = lrc_date_modified;
+            }
             let file_date_mod_compare: DateTime<Local> = DateTime::from(file_date_modified);
 
             let item_result = item::Entity::find()
@@ -299,7 +698,8 @@ impl FileMediaSource {
             let item_option = item_result.unwrap();
 
             let (item_is_modified, id) = if let Some(item) = item_option {
-                (item.date_modified < file_date_mod_compare, item.id)
+                // items indexed before library roots existed lack the root and the duration
+                (item.date_modified < file_date_mod_compare || item.library_root != base_path, item.id)
             } else {
                 (true, 0)
             };
@@ -317,16 +717,124 @@ impl FileMediaSource {
                     self.empty_metadata()
                 };
 
-                let _ = self.upsert_item(id, file_id_str.clone(), media_type.clone(), rel_path.clone(), &item_meta).await;
+                let media_type = classifier.classify(&rel_path, item_meta.genre.as_deref(), item_meta.duration);
+                if let Err(e) = self.upsert_item(id, file_id_str.clone(), convert_media_type_to_db(&media_type), base_path.clone(), rel_path.clone(), &item_meta).await {
+                    println!("could not index {}: {}", full_path, e);
+                }
             } else {
                 // item has not been modified
             }
         }
+
+        self.update_missing_items(&base_path, &found_locations).await;
+    }
+
+    /// Hides the items of a library root that were not found by its last scan and
+    /// shows them again once they reappear (e.g. after the exclusion rules changed)
+    async fn update_missing_items(&self, library_root: &str, found_locations: &HashSet<String>) {
+        let items = item::Entity::find()
+            .filter(item::Column::LibraryRoot.eq(library_root))
+            .all(&self.db)
+            .await;
+        let items = match items {
+            Ok(items) => items,
+            Err(e) => {
+                println!("could not load items of library root {}: {}", library_root, e);
+                return;
+            }
+        };
+
+        let (missing, found): (Vec<item::Model>, Vec<item::Model>) = items
+            .into_iter()
+            .partition(|i| !found_locations.contains(&i.location));
+        for (is_missing, items) in [(true, missing), (false, found)] {
+            let ids: Vec<i32> = items
+                .iter()
+                .filter(|i| i.is_missing != is_missing)
+                .map(|i| i.id)
+                .collect();
+            // stay below the sqlite limit of bound variables
+            for chunk in ids.chunks(MISSING_ITEMS_CHUNK_SIZE) {
+                let result = item::Entity::update_many()
+                    .col_expr(item::Column::IsMissing, Expr::value(is_missing))
+                    .filter(item::Column::Id.is_in(chunk.to_vec()))
+                    .exec(&self.db)
+                    .await;
+                if let Err(e) = result {
+                    println!("could not update missing items of library root {}: {}", library_root, e);
+                }
+            }
+        }
     }
 
 
 
 
+    pub async fn media_type_rules(&self) -> Vec<MediaTypeRule> {
+        self.settings.get(SETTINGS_KEY_MEDIA_TYPE_RULES, MediaTypeRule::defaults()).await
+    }
+
+    pub async fn exclusion_rules(&self) -> ExclusionRules {
+        self.settings.get(SETTINGS_KEY_EXCLUSION_RULES, ExclusionRules::defaults()).await
+    }
+
+    pub async fn update_exclusion_rules(&self, rules: ExclusionRules) -> Result<(), DbErr> {
+        self.settings.set(SETTINGS_KEY_EXCLUSION_RULES, &rules).await
+    }
+
+    pub async fn update_media_type_rules(&self, rules: Vec<MediaTypeRule>) -> Result<(), DbErr> {
+        self.settings.set(SETTINGS_KEY_MEDIA_TYPE_RULES, &rules).await
+    }
+
+    /// Applies the current media type rules to all indexed items using the stored
+    /// metadata, so that changing the rules does not require a full rescan
+    pub async fn reclassify(&self) -> usize {
+        self.reclassify_items(None).await
+    }
+
+    /// Applies the media type rules to a single item or, without id, to all items
+    async fn reclassify_items(&self, id: Option<i32>) -> usize {
+        let db = self.db.clone();
+        let classifier = MediaTypeClassifier::new(self.media_type_rules().await);
+
+        let mut query = item::Entity::load();
+        if let Some(id) = id {
+            query = query.filter(item::Column::Id.eq(id));
+        }
+        let items = query
+            .with(items_metadata::Entity)
+            .all(&db)
+            .await;
+        if items.is_err() {
+            return 0;
+        }
+
+        let mut changed = 0;
+        for i in items.unwrap() {
+            let mut genre: Option<String> = None;
+            for tag in &i.metadata {
+                if tag.tag_field == Genre {
+                    genre = Some(tag.value.clone());
+                }
+            }
+            let duration = Duration::from_millis(i.duration.max(0) as u64);
+            let media_type = convert_media_type_to_db(&classifier.classify(&i.location, genre.as_deref(), duration));
+            if media_type == i.media_type {
+                continue;
+            }
+
+            let update_result = item::Entity::update_many()
+                .col_expr(item::Column::MediaType, Expr::value(media_type))
+                .filter(item::Column::Id.eq(i.id))
+                .exec(&db)
+                .await;
+            if update_result.is_ok() {
+                changed += 1;
+            }
+        }
+        changed
+    }
+
     async fn extract_metadata(&self, path: String) -> Result<MediaSourceMetadata, LoftyError> {
 
         /*
@@ -342,6 +850,8 @@ let mut tag = Tag::read_with_path("music.m4a", &read_cfg).unwrap();
  */
 
         let tagged_file = Probe::open(path.clone())?.guess_file_type()?.read()?;
+        // SYLT frames only exist in the id3v2 tag of mpeg files
+        let is_mpeg = tagged_file.file_type() == FileType::Mpeg;
         let tag_result = match tagged_file.primary_tag() {
             Some(primary_tag) => Some(primary_tag),
             // If the "primary" tag doesn't exist, we just grab the
@@ -354,7 +864,10 @@ let mut tag = Tag::read_with_path("music.m4a", &read_cfg).unwrap();
         let duration = properties.duration();
 
         if tag_result.is_none() {
-            return Ok(self.empty_metadata());
+            let mut empty_metadata = self.empty_metadata();
+            empty_metadata.duration = duration;
+            empty_metadata.lyrics = self.extract_sidecar_lyrics(&path);
+            return Ok(empty_metadata);
         }
         let tag = tag_result.unwrap();
         let mut media_source_metadata = MediaSourceMetadata::new(
@@ -364,29 +877,159 @@ let mut tag = Tag::read_with_path("music.m4a", &read_cfg).unwrap();
             None, // composer
             None, // series
             None, // part
-            None, // genre
+            tag.genre().map(|s| s.to_string()),
             None, // cover
             vec![], // chapters
         );
+        media_source_metadata.duration = duration;
+        // written by update_metadata for non mp4 files, mp4 uses the tone freeform atoms
+        media_source_metadata.series = tag.get_string(&ItemKey::Movement).map(|s| s.to_string());
+        media_source_metadata.part = tag.get_string(&ItemKey::MovementNumber).map(|s| s.to_string());
         let pictures = self.extract_pictures(tag).await?;
-        if pictures.len() > 0 {
+        if !pictures.is_empty() {
             media_source_metadata.cover = Some(pictures[0].clone());
         }
 
-        if tag.tag_type() == Mp4Ilst {
-            self.extract_mp4_metadata(&mut media_source_metadata, path.clone(), duration);
+        if tag.tag_type() == Mp4Ilst
+            && let Err(e) = self.extract_mp4_metadata(&mut media_source_metadata, path.clone(), duration)
+        {
+            println!("skipping mp4 tags of {}: {}", path, e);
         }
 
+        // sidecar files win over synced (SYLT) lyrics, which win over plain text (USLT, ©lyr)
+        media_source_metadata.lyrics = self.extract_sidecar_lyrics(&path)
+            .or_else(|| if is_mpeg { self.extract_id3v2_synced_lyrics(&path) } else { None })
+            .or_else(|| tag.get_string(&ItemKey::Lyrics).and_then(MediaSourceLyrics::from_plain_text));
+
+        media_source_metadata.replay_gain = self.extract_replay_gain(tag);
+
         Ok(media_source_metadata)
     }
 
-    fn extract_mp4_metadata(&self, meta: &mut MediaSourceMetadata, path: String, duration: Duration) {
+    /// ReplayGain tags (ID3 TXXX, Vorbis comments and iTunes freeform atoms are
+    /// all mapped to the same keys), iTunNORM as fallback
+    fn extract_replay_gain(&self, tag: &Tag) -> Option<MediaSourceReplayGain> {
+        let replay_gain = MediaSourceReplayGain {
+            track_gain: tag.get_string(&ItemKey::ReplayGainTrackGain).and_then(MediaSourceReplayGain::parse_gain),
+            track_peak: tag.get_string(&ItemKey::ReplayGainTrackPeak).and_then(MediaSourceReplayGain::parse_peak),
+            album_gain: tag.get_string(&ItemKey::ReplayGainAlbumGain).and_then(MediaSourceReplayGain::parse_gain),
+            album_peak: tag.get_string(&ItemKey::ReplayGainAlbumPeak).and_then(MediaSourceReplayGain::parse_peak),
+            analyzed: false,
+        };
+        if !replay_gain.is_empty() {
+            return Some(replay_gain);
+        }
+
+        [ITUNES_NORMALIZATION_MP4_KEY, ITUNES_NORMALIZATION_KEY]
+            .iter()
+            .find_map(|key| tag.get_string(&ItemKey::Unknown(key.to_string())))
+            .and_then(MediaSourceReplayGain::from_itunes_normalization)
+    }
+
+    /// Computes the EBU R128 loudness of music items without ReplayGain tags, one file at a time
+    pub async fn analyze_missing_loudness(&self) {
+        let items = item::Entity::load()
+            .filter(item::Column::MediaType.eq(item::MediaType::Music))
+            .filter(item::Column::IsOffline.eq(false))
+            .filter(item::Column::IsMissing.eq(false))
+            .with(items_json_metadata::Entity)
+            .all(&self.db)
+            .await;
+        let Ok(items) = items else {
+            return;
+        };
+
+        for i in items {
+            let mut has_replay_gain = false;
+            for json_tag in &i.json {
+                has_replay_gain |= json_tag.tag_field == ReplayGain;
+            }
+            if has_replay_gain {
+                continue;
+            }
+            let path = PathBuf::from(format!("{}/{}", i.library_root.trim_end_matches('/'), i.location.trim_start_matches('/')));
+            let analyzed = tokio::task::spawn_blocking(move || LoudnessAnalyzer::analyze_file(&path)).await;
+            let Ok(Some(replay_gain)) = analyzed else {
+                continue;
+            };
+            let Ok(replay_gain_json) = serde_json::to_string(&replay_gain) else {
+                continue;
+            };
+            let result = items_json_metadata::ActiveModel::builder()
+                .set_item_id(i.id)
+                .set_tag_field(ReplayGain)
+                .set_value(replay_gain_json)
+                .set_date_modified(Utc::now())
+                .save(&self.db)
+                .await;
+            if let Err(e) = result {
+                println!("could not store loudness of item {}: {}", i.id, e);
+            }
+        }
+    }
+
+    fn extract_sidecar_lyrics(&self, path: &str) -> Option<MediaSourceLyrics> {
+        let lrc_path = Path::new(path).with_extension(LRC_EXTENSION);
+        let content = fs::read_to_string(lrc_path).ok()?;
+        MediaSourceLyrics::from_lrc(&content)
+    }
+
+    fn extract_id3v2_synced_lyrics(&self, path: &str) -> Option<MediaSourceLyrics> {
+        let mut file = fs::File::open(path).ok()?;
+        let mpeg_file = MpegFile::read_from(&mut file, ParseOptions::new()).ok()?;
+        let id3v2 = mpeg_file.id3v2()?;
+        let sylt_id = FrameId::new("SYLT").ok()?;
+
+        let Some(Frame::Binary(binary_frame)) = id3v2.get(&sylt_id) else {
+            return None;
+        };
+        let sylt = SynchronizedTextFrame::parse(&binary_frame.data, FrameFlags::default()).ok()?;
+        let frame_duration = match sylt.timestamp_format {
+            TimestampFormat::MS => None,
+            TimestampFormat::MPEG => match Self::mpeg_frame_duration(mpeg_file.properties()) {
+                Some(frame_duration) => Some(frame_duration),
+                None => {
+                    println!("ignoring SYLT lyrics of {}, the mpeg frame duration is unknown", path);
+                    return None;
+                }
+            },
+        };
+
+        let lines = sylt.content
+            .iter()
+            .map(|(timestamp, text)| {
+                let start = match frame_duration {
+                    Some(frame_duration) => frame_duration * *timestamp,
+                    None => Duration::from_millis(*timestamp as u64),
+                };
+                MediaSourceLyricsLine::new(start, text.trim().to_string())
+            })
+            .collect();
+        MediaSourceLyrics::from_synced(0, lines)
+    }
+
+    /// SYLT timestamps may be given in mpeg frames, whose duration depends on the layer and version
+    fn mpeg_frame_duration(properties: &MpegProperties) -> Option<Duration> {
+        if properties.sample_rate() == 0 {
+            return None;
+        }
+        let samples_per_frame: u64 = match (properties.layer(), properties.version()) {
+            (Layer::Layer1, _) => 384,
+            (Layer::Layer2, _) => 1152,
+            (Layer::Layer3, MpegVersion::V1) => 1152,
+            (Layer::Layer3, _) => 576,
+        };
+        Some(Duration::from_nanos(samples_per_frame * 1_000_000_000 / properties.sample_rate() as u64))
+    }
+
+    fn extract_mp4_metadata(&self, meta: &mut MediaSourceMetadata, path: String, duration: Duration) -> Result<(), mp4ameta::Error> {
         let mut chapters: Vec<MediaSourceChapter> = Vec::new();
-        let mp4tag = mp4ameta::Tag::read_from_path(path.clone()).unwrap();
+        let mp4tag = mp4ameta::Tag::read_from_path(path.clone())?;
         let tmp_chaps = mp4tag.chapters().iter().rev();
         let mut end = duration;
         for tmp_chap in tmp_chaps {
-            let duration = end - tmp_chap.start;
+            // chapters behind the end of the audio (broken files) get no duration
+            let duration = end.saturating_sub(tmp_chap.start);
             chapters.push(MediaSourceChapter::new(tmp_chap.title.clone(), tmp_chap.start, duration));
             end -= duration;
         }
@@ -423,6 +1066,149 @@ let mut tag = Tag::read_with_path("music.m4a", &read_cfg).unwrap();
         } else if movement_index.is_some() {
             meta.part = movement_index.map(|s| s.to_string());
         }
+        Ok(())
+    }
+
+    fn write_mp4_metadata(&self, path: &str, update: &MediaSourceMetadataUpdate) -> Result<(), MediaSourceError> {
+        let mut mp4tag = mp4ameta::Tag::read_from_path(path)
+            .map_err(|e| MediaSourceError::Tag(e.to_string()))?;
+
+        if let Some(title) = &update.title {
+            if title.is_empty() { mp4tag.remove_title(); } else { mp4tag.set_title(title.clone()); }
+        }
+        if let Some(artist) = &update.artist {
+            if artist.is_empty() { mp4tag.remove_artists(); } else { mp4tag.set_artist(artist.clone()); }
+        }
+        if let Some(genre) = &update.genre {
+            if genre.is_empty() { mp4tag.remove_genres(); } else { mp4tag.set_genre(genre.clone()); }
+        }
+
+        // same atoms as in extract_mp4_metadata
+        let series_indent = FreeformIdent::new_static("com.pilabor.tone", "SERIES");
+        if let Some(series) = &update.series {
+            mp4tag.remove_data_of(&series_indent);
+            if !series.is_empty() {
+                mp4tag.set_data(series_indent, mp4ameta::Data::Utf8(series.clone()));
+            }
+        }
+        let part_indent = FreeformIdent::new_static("com.pilabor.tone", "PART");
+        if let Some(part) = &update.part {
+            mp4tag.remove_data_of(&part_indent);
+            if !part.is_empty() {
+                mp4tag.set_data(part_indent, mp4ameta::Data::Utf8(part.clone()));
+            }
+        }
+
+        if let Some(chapters) = &update.chapters {
+            let mp4_chapters = chapters.iter()
+                .map(|c| mp4ameta::Chapter { start: c.start, title: c.name.clone() })
+                .collect::<Vec<_>>();
+            *mp4tag.chapter_list_mut() = mp4_chapters;
+        }
+
+        mp4tag.write_to_path(path).map_err(|e| MediaSourceError::Tag(e.to_string()))
+    }
+
+    /// Series and part are stored as movement name and number, which only ID3v2, Vorbis
+    /// comments and APE tags support. Other tags (e.g. ID3v1 only files) reject them
+    fn write_generic_metadata(&self, path: &str, update: &MediaSourceMetadataUpdate) -> Result<(), MediaSourceError> {
+        if update.chapters.is_some() {
+            return Err(MediaSourceError::Unsupported(format!("chapters can only be written to mp4 files: {}", path)));
+        }
+
+        let mut tagged_file = Probe::open(path)
+            .map_err(|e| MediaSourceError::Tag(e.to_string()))?
+            .guess_file_type()
+            .map_err(|e| MediaSourceError::Tag(e.to_string()))?
+            .read()
+            .map_err(|e| MediaSourceError::Tag(e.to_string()))?;
+        if tagged_file.primary_tag().is_none() {
+            let tag_type = tagged_file.primary_tag_type();
+            tagged_file.insert_tag(Tag::new(tag_type));
+        }
+        let Some(tag) = tagged_file.primary_tag_mut() else {
+            return Err(MediaSourceError::Tag(format!("no writable tag: {}", path)));
+        };
+
+        if let Some(title) = &update.title {
+            if title.is_empty() { tag.remove_title(); } else { tag.set_title(title.clone()); }
+        }
+        if let Some(artist) = &update.artist {
+            if artist.is_empty() { tag.remove_artist(); } else { tag.set_artist(artist.clone()); }
+        }
+        if let Some(genre) = &update.genre {
+            if genre.is_empty() { tag.remove_genre(); } else { tag.set_genre(genre.clone()); }
+        }
+        for (item_key, value) in [(ItemKey::Movement, &update.series), (ItemKey::MovementNumber, &update.part)] {
+            let Some(value) = value else {
+                continue;
+            };
+            tag.remove_key(&item_key);
+            if !value.is_empty() && !tag.insert_text(item_key, value.clone()) {
+                return Err(MediaSourceError::Unsupported(format!("series and part cannot be stored in {:?} tags: {}", tag.tag_type(), path)));
+            }
+        }
+
+        tag.save_to_path(path, WriteOptions::default())
+            .map_err(|e| MediaSourceError::Tag(e.to_string()))
+    }
+
+    /// Replaces the changed metadata rows of an item, the caller commits the transaction
+    async fn update_metadata_rows(&self, txn: &DatabaseTransaction, id: i32, update: &MediaSourceMetadataUpdate) -> Result<(), DbErr> {
+        let now = Utc::now();
+        let fields: Vec<(TagField, &Option<String>)> = vec![
+            (Title, &update.title),
+            (Artist, &update.artist),
+            (Series, &update.series),
+            (Part, &update.part),
+            (Genre, &update.genre),
+        ];
+
+        for (tag_field, value) in fields {
+            let Some(value) = value else {
+                continue;
+            };
+            items_metadata::Entity::delete_many()
+                .filter(items_metadata::Column::ItemId.eq(id))
+                .filter(items_metadata::Column::TagField.eq(tag_field.clone()))
+                .exec(txn)
+                .await?;
+            if !value.is_empty() {
+                items_metadata::ActiveModel::builder()
+                    .set_item_id(id)
+                    .set_tag_field(tag_field)
+                    .set_value(value.clone())
+                    .set_date_modified(now)
+                    .save(txn)
+                    .await?;
+            }
+        }
+
+        if let Some(chapters) = &update.chapters {
+            items_json_metadata::Entity::delete_many()
+                .filter(items_json_metadata::Column::ItemId.eq(id))
+                .filter(items_json_metadata::Column::TagField.eq(Chapters))
+                .exec(txn)
+                .await?;
+            if !chapters.is_empty() {
+                let chapters_json = serde_json::to_string(chapters).map_err(|e| DbErr::Custom(e.to_string()))?;
+                items_json_metadata::ActiveModel::builder()
+                    .set_item_id(id)
+                    .set_tag_field(Chapters)
+                    .set_value(chapters_json)
+                    .set_date_modified(now)
+                    .save(txn)
+                    .await?;
+            }
+        }
+
+        // the file has just been written, so the next scan must not treat it as modified
+        item::Entity::update_many()
+            .col_expr(item::Column::DateModified, Expr::value(now))
+            .filter(item::Column::Id.eq(id))
+            .exec(txn)
+            .await?;
+        Ok(())
     }
 
     async fn extract_pictures(&self, tag: &Tag) -> Result<Vec<MediaSourcePicture>, LoftyError> {
@@ -433,9 +1219,8 @@ let mut tag = Tag::read_with_path("music.m4a", &read_cfg).unwrap();
             // probably implement location() on MediaSourcePicture to return the full path
             // and tb_location for the thumbnail?
 
-            let hash_u64 = xxh3_64(&pic.data());
+            let hash_u64 = xxh3_64(pic.data());
             let hash = format!("{:016x}", hash_u64); // 16 chars, lowercase, zero-padded
-            let codec = mime_to_codec(pic.mime_type());
 
             let media_source_picture = MediaSourcePicture {
                 cache_dir: self.cache_path(),
@@ -450,13 +1235,16 @@ let mut tag = Tag::read_with_path("music.m4a", &read_cfg).unwrap();
             let tb_full_path = PathBuf::from(media_source_picture.tb_full_path( pic_ext.clone()));
             fs::create_dir_all(pic_path_str.clone())?;
 
-            let pic_full_path_exists = pic_full_path.exists();
-            if !pic_full_path_exists {
-                resize_image_bytes_to_file(&pic.data(), &pic_full_path, 368, 368);
+            if !pic_full_path.exists()
+                && let Err(e) = resize_image_bytes_to_file(pic.data(), &pic_full_path, 368, 368)
+            {
+                println!("could not store cover {}: {}", pic_full_path.display(), e);
             }
 
-            if !tb_full_path.exists() {
-                resize_image_bytes_to_file(&pic.data(), &tb_full_path, 192, 192);
+            if !tb_full_path.exists()
+                && let Err(e) = resize_image_bytes_to_file(pic.data(), &tb_full_path, 192, 192)
+            {
+                println!("could not store cover {}: {}", tb_full_path.display(), e);
             }
 
             pics.push(media_source_picture);
@@ -485,7 +1273,10 @@ let mut tag = Tag::read_with_path("music.m4a", &read_cfg).unwrap();
 impl MediaSource for FileMediaSource {
     fn id(&self) -> String {
         let inner = self.state.lock().unwrap();
-        let id = inner.base_path.clone();
+        let id = inner.library_roots.iter()
+            .map(|r| r.path.clone())
+            .collect::<Vec<String>>()
+            .join(";");
         drop(inner);
         id
     }
@@ -497,12 +1288,16 @@ impl MediaSource for FileMediaSource {
         let media_type = match query {
             "4" => item::MediaType::Music,
             "2" => item::MediaType::Audiobook,
+            "8" => item::MediaType::Podcast,
             _ => item::MediaType::Unspecified
         };
 
         let items = item::Entity::load()
                 .filter(item::Column::MediaType.eq(media_type))
+                .filter(item::Column::IsOffline.eq(false))
+                .filter(item::Column::IsMissing.eq(false))
                 .with(items_metadata::Entity)
+                .with(items_listening_states::Entity)
                 .all(&db)
                 .await;
         if items.is_err() {
@@ -523,6 +1318,7 @@ impl MediaSource for FileMediaSource {
             .filter(item::Column::Id.eq(id))
             .with(items_metadata::Entity)
             .with(items_json_metadata::Entity)
+            .with(items_listening_states::Entity)
             .one(&db)
             .await;
 
@@ -538,12 +1334,218 @@ impl MediaSource for FileMediaSource {
         None
     }
 
+    async fn series_items(&self, series: &str) -> Vec<MediaSourceItem> {
+        let result = items_metadata::Entity::find()
+            .filter(items_metadata::Column::TagField.eq(Series))
+            .filter(items_metadata::Column::Value.eq(series))
+            .all(&self.db)
+            .await;
+        let item_ids: Vec<i32> = match result {
+            Ok(rows) => rows.iter().map(|row| row.item_id).collect(),
+            Err(e) => {
+                println!("could not load series {}: {}", series, e);
+                return vec![];
+            }
+        };
+
+        let items = item::Entity::load()
+            .filter(item::Column::Id.is_in(item_ids))
+            .filter(item::Column::IsOffline.eq(false))
+            .filter(item::Column::IsMissing.eq(false))
+            .with(items_metadata::Entity)
+            .with(items_listening_states::Entity)
+            .all(&self.db)
+            .await;
+        match items {
+            Ok(items) => items
+                .iter()
+                .map(|i| self.map_db_model_to_media_item(i, &i.metadata, &i.json))
+                .collect(),
+            Err(e) => {
+                println!("could not load items of series {}: {}", series, e);
+                vec![]
+            }
+        }
+    }
+
+    async fn update_metadata(&self, id: &str, update: MediaSourceMetadataUpdate) -> Result<MediaSourceItem, MediaSourceError> {
+        let item_model = item::Entity::find()
+            .filter(item::Column::Id.eq(id))
+            .one(&self.db)
+            .await?
+            .ok_or(MediaSourceError::NotFound(id.to_string()))?;
+        if item_model.is_offline {
+            return Err(MediaSourceError::Offline(id.to_string()));
+        }
+        if update.is_empty() {
+            return self.find(id).await.ok_or(MediaSourceError::NotFound(id.to_string()));
+        }
+
+        let path = format!("{}/{}", item_model.library_root.trim_end_matches('/'), item_model.location.trim_start_matches('/'));
+        let is_mp4 = Path::new(&path)
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .is_some_and(|ext| ["m4b", "m4a", "mp4"].contains(&ext.to_lowercase().as_str()));
+
+        // the rows are written first, so a failing file write can roll them back
+        let txn = self.db.begin().await?;
+        self.update_metadata_rows(&txn, item_model.id, &update).await?;
+        let written = if is_mp4 {
+            self.write_mp4_metadata(&path, &update)
+        } else {
+            self.write_generic_metadata(&path, &update)
+        };
+        if let Err(e) = written {
+            txn.rollback().await?;
+            return Err(e);
+        }
+        // if the commit fails, the next scan picks up the changed file
+        txn.commit().await?;
+        if update.genre.is_some() {
+            self.reclassify_items(Some(item_model.id)).await;
+        }
+        self.find(id).await.ok_or(MediaSourceError::NotFound(id.to_string()))
+    }
+
+    async fn add_progress_history(
+        &self,
+        id: &str,
+        session_key: &str,
+        reason: MediaSourceProgressReason,
+        position: Duration,
+        previous_position: Duration,
+    ) -> Result<(), MediaSourceError> {
+        let item_id: i32 = id.parse().map_err(|_| MediaSourceError::NotFound(id.to_string()))?;
+        items_progress_history::ActiveModel::builder()
+            .set_item_id(item_id)
+            .set_session_key(session_key)
+            .set_reason(convert_progress_reason_to_db(&reason))
+            .set_position(position.as_millis() as i64)
+            .set_previous_position(previous_position.as_millis() as i64)
+            .set_date_modified(Utc::now())
+            .save(&self.db)
+            .await?;
+        Ok(())
+    }
+
+    async fn add_listening_session(&self, session: MediaSourceListeningSession) -> Result<(), MediaSourceError> {
+        let item_id: i32 = session
+            .item_id
+            .parse()
+            .map_err(|_| MediaSourceError::NotFound(session.item_id.clone()))?;
+        items_listening_sessions::ActiveModel::builder()
+            .set_item_id(item_id)
+            .set_session_key(session.session_key)
+            .set_date_start(session.start)
+            .set_date_end(session.end)
+            .set_start_position(session.start_position.as_millis() as i64)
+            .set_end_position(session.end_position.as_millis() as i64)
+            .set_listened(session.listened.as_millis() as i64)
+            .set_skipped(session.skipped.as_millis() as i64)
+            .save(&self.db)
+            .await?;
+        Ok(())
+    }
+
+    async fn update_listening_progress(
+        &self,
+        id: &str,
+        position: Duration,
+        state: MediaSourceListeningState,
+    ) -> Result<(), MediaSourceError> {
+        let item_id: i32 = id.parse().map_err(|_| MediaSourceError::NotFound(id.to_string()))?;
+        let current = items_listening_states::Entity::find()
+            .filter(items_listening_states::Column::ItemId.eq(item_id))
+            .one(&self.db)
+            .await?;
+        // e.g. listening to a single chapter again must not bring a book marked as finished back
+        if let Some(current) = current
+            && current.is_manual
+            && current.state == items_listening_states::ListeningState::Finished
+        {
+            return self.store_listening_progress(id, position, MediaSourceListeningState::Finished, true).await;
+        }
+        self.store_listening_progress(id, position, state, false).await
+    }
+
+    async fn add_bookmark(&self, bookmark: MediaSourceBookmark) -> Result<MediaSourceBookmark, MediaSourceError> {
+        let item_id: i32 = bookmark
+            .item_id
+            .parse()
+            .map_err(|_| MediaSourceError::NotFound(bookmark.item_id.clone()))?;
+        let model = items_bookmarks::ActiveModel::builder()
+            .set_item_id(item_id)
+            .set_position(bookmark.position.as_millis() as i64)
+            .set_chapter_index(bookmark.chapter_index.map(|i| i as i32))
+            .set_chapter_name(bookmark.chapter_name.clone())
+            .set_note(bookmark.note.clone())
+            .set_date_created(bookmark.date_created)
+            .save(&self.db)
+            .await?;
+        Ok(MediaSourceBookmark {
+            id: model.id.unwrap().to_string(),
+            ..bookmark
+        })
+    }
+
+    async fn bookmarks(&self, id: &str) -> Vec<MediaSourceBookmark> {
+        let result = items_bookmarks::Entity::find()
+            .filter(items_bookmarks::Column::ItemId.eq(id))
+            .order_by_asc(items_bookmarks::Column::Position)
+            .all(&self.db)
+            .await;
+        match result {
+            Ok(bookmarks) => bookmarks.iter().map(Self::map_db_bookmark).collect(),
+            Err(e) => {
+                println!("could not load bookmarks of item {}: {}", id, e);
+                vec![]
+            }
+        }
+    }
+
+    async fn remove_bookmark(&self, bookmark_id: &str) -> Result<(), MediaSourceError> {
+        items_bookmarks::Entity::delete_many()
+            .filter(items_bookmarks::Column::Id.eq(bookmark_id))
+            .exec(&self.db)
+            .await?;
+        Ok(())
+    }
+
     async fn run(
         mut self,
         mut cmd_rx: UnboundedReceiver<MediaSourceCommand>,
         evt_tx: UnboundedSender<MediaSourceEvent>,
     ) {
-        while let Some(cmd) = cmd_rx.recv().await {
+        let mut library_root_interval = tokio::time::interval(LIBRARY_ROOT_POLL_INTERVAL);
+        let (scan_done_tx, mut scan_done_rx) = unbounded_channel::<String>();
+        // paths of the library roots that are scanned in the background
+        let mut scanning_roots: Vec<String> = Vec::new();
+        loop {
+            let cmd = tokio::select! {
+                cmd_opt = cmd_rx.recv() => {
+                    match cmd_opt {
+                        Some(cmd) => cmd,
+                        None => break,
+                    }
+                }
+                _ = library_root_interval.tick() => {
+                    for (library_root, is_available) in self.update_library_roots().await {
+                        let path = library_root.path.clone();
+                        let _ = evt_tx.send(MediaSourceEvent::LibraryRootChanged(path.clone(), is_available));
+                        if is_available && !scanning_roots.contains(&path) {
+                            scanning_roots.push(path);
+                            self.spawn_library_root_scan(library_root, scan_done_tx.clone());
+                        }
+                    }
+                    continue;
+                }
+                Some(path) = scan_done_rx.recv() => {
+                    scanning_roots.retain(|p| *p != path);
+                    let _ = evt_tx.send(MediaSourceEvent::LibraryRootScanned(path));
+                    continue;
+                }
+            };
+
             match cmd {
                 MediaSourceCommand::Filter(query) => {
                     let results = self.filter(&query).await;
@@ -553,11 +1555,92 @@ impl MediaSource for FileMediaSource {
                     let result = self.find(&id).await;
                     let _ = evt_tx.send(MediaSourceEvent::FindResult(result));
                 }
+                MediaSourceCommand::UpdateMetadata(id, update) => {
+                    match self.update_metadata(&id, update).await {
+                        Ok(item) => {
+                            let _ = evt_tx.send(MediaSourceEvent::MetadataUpdated(item));
+                        }
+                        Err(e) => {
+                            let _ = evt_tx.send(MediaSourceEvent::MetadataUpdateFailed(id, e.to_string()));
+                        }
+                    }
+                }
+                MediaSourceCommand::Reclassify => {
+                    let changed = self.reclassify().await;
+                    let _ = evt_tx.send(MediaSourceEvent::Reclassified(changed));
+                }
+                MediaSourceCommand::UpdateMediaTypeRules(rules) => {
+                    if let Err(e) = self.update_media_type_rules(rules).await {
+                        println!("could not store media type rules: {}", e);
+                        continue;
+                    }
+                    let changed = self.reclassify().await;
+                    let _ = evt_tx.send(MediaSourceEvent::Reclassified(changed));
+                }
+                MediaSourceCommand::Bookmarks(id) => {
+                    let bookmarks = self.bookmarks(&id).await;
+                    let _ = evt_tx.send(MediaSourceEvent::BookmarksResult(id, bookmarks));
+                }
+                MediaSourceCommand::RemoveBookmark(item_id, bookmark_id) => {
+                    if let Err(e) = self.remove_bookmark(&bookmark_id).await {
+                        println!("could not remove bookmark {}: {}", bookmark_id, e);
+                    }
+                    let bookmarks = self.bookmarks(&item_id).await;
+                    let _ = evt_tx.send(MediaSourceEvent::BookmarksResult(item_id, bookmarks));
+                }
+                MediaSourceCommand::ExportBookmarks(path) => {
+                    match self.export_bookmarks(&path).await {
+                        Ok(count) => {
+                            let _ = evt_tx.send(MediaSourceEvent::BookmarksExported(path, count));
+                        }
+                        Err(e) => println!("could not export bookmarks to {}: {}", path, e),
+                    }
+                }
+                MediaSourceCommand::ContinueListening => {
+                    let items = self.continue_listening().await;
+                    let _ = evt_tx.send(MediaSourceEvent::ContinueListeningResults(items));
+                }
+                MediaSourceCommand::SetListeningState(id, state) => {
+                    match self.set_listening_state(&id, state).await {
+                        Ok(item) => {
+                            let _ = evt_tx.send(MediaSourceEvent::ListeningStateChanged(item));
+                        }
+                        Err(e) => println!("could not update listening state of item {}: {}", id, e),
+                    }
+                }
+                MediaSourceCommand::ListeningStats => {
+                    let stats = self.listening_stats().await;
+                    let _ = evt_tx.send(MediaSourceEvent::ListeningStatsResult(stats));
+                }
+                MediaSourceCommand::ExportListeningStats(csv_path, json_path) => {
+                    match self.export_listening_stats(&csv_path, &json_path).await {
+                        Ok(count) => {
+                            let _ = evt_tx.send(MediaSourceEvent::ListeningStatsExported(count));
+                        }
+                        Err(e) => println!("could not export listening statistics: {}", e),
+                    }
+                }
+                MediaSourceCommand::UpdateExclusionRules(rules) => {
+                    if let Err(e) = self.update_exclusion_rules(rules).await {
+                        println!("could not store exclusion rules: {}", e);
+                        continue;
+                    }
+                    self.scan_media().await;
+                }
             }
         }
     }
 }
 
+/// Quotes a CSV field if it contains separators, quotes or line breaks
+fn escape_csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
 fn resize_image_bytes_to_file(
     image_bytes: &[u8],
     output_path: &Path,
@@ -581,20 +1664,3 @@ fn resize_image_bytes_to_file(
 
     Ok(())
 }
-
-
-fn mime_to_codec( mime_type_opt: Option<&MimeType>) -> MediaSourceImageCodec {
-    let unknown_ext = String::from("dat");
-    if let Some(mime_type) = mime_type_opt {
-        let result = match mime_type {
-            MimeType::Png => MediaSourceImageCodec::Png,
-            MimeType::Jpeg => MediaSourceImageCodec::Jpeg,
-            MimeType::Tiff => MediaSourceImageCodec::Tiff,
-            MimeType::Bmp => MediaSourceImageCodec::Jpeg,
-            MimeType::Gif => MediaSourceImageCodec::Gif,
-            _ => MediaSourceImageCodec::Unknown
-        };
-        return result;
-    }
-    MediaSourceImageCodec::Unknown
-}
\ No newline at end of file
@@ -0,0 +1,33 @@
+use std::fs;
+use std::path::Path;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LibraryRoot {
+    pub path: String,
+    // removable media (USB / SD) may come and go at runtime
+    pub removable: bool,
+}
+
+impl LibraryRoot {
+    pub fn new(path: String, removable: bool) -> Self {
+        Self {
+            path: path.trim_end_matches('/').to_string(),
+            removable,
+        }
+    }
+
+    pub fn is_available(&self) -> bool {
+        let path = Path::new(&self.path);
+        if !path.is_dir() {
+            return false;
+        }
+        if !self.removable {
+            return true;
+        }
+        // an unmounted mount point usually still exists, but is empty
+        match fs::read_dir(path) {
+            Ok(mut entries) => entries.next().is_some(),
+            Err(_) => false,
+        }
+    }
+}
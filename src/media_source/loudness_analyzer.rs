@@ -0,0 +1,142 @@
+use crate::audio::biquad::Biquad;
+use crate::media_source::media_source_replay_gain::MediaSourceReplayGain;
+use rodio::Source;
+use std::f64::consts::PI;
+use std::fs::File;
+use std::path::Path;
+
+// ReplayGain 2.0 reference level
+const REFERENCE_LOUDNESS: f64 = -18.0;
+const ABSOLUTE_GATE: f64 = -70.0;
+const RELATIVE_GATE: f64 = -10.0;
+// 400ms gating blocks with 75% overlap are built from four 100ms sub blocks
+const SUB_BLOCKS_PER_BLOCK: usize = 4;
+
+/// Integrated loudness according to ITU-R BS.1770 / EBU R128
+pub struct LoudnessAnalyzer {
+    filters: Vec<(Biquad, Biquad)>,
+    channels: usize,
+    sub_block_len: usize,
+    sub_block_frames: usize,
+    sub_block_energy: f64,
+    sub_blocks: Vec<f64>,
+    peak: f32,
+}
+
+impl LoudnessAnalyzer {
+    pub fn new(channels: u16, sample_rate: u32) -> Self {
+        let channels = channels.max(1) as usize;
+        let filters = (0..channels).map(|_| Self::k_weighting(sample_rate as f64)).collect();
+        Self {
+            filters,
+            channels,
+            sub_block_len: (sample_rate as usize / 10).max(1),
+            sub_block_frames: 0,
+            sub_block_energy: 0.0,
+            sub_blocks: vec![],
+            peak: 0.0,
+        }
+    }
+
+    /// high shelf and high pass stage, valid for any sample rate
+    fn k_weighting(sample_rate: f64) -> (Biquad, Biquad) {
+        let f0 = 1681.974450955533;
+        let gain = 3.999843853973347;
+        let q = 0.7071752369554196;
+        let k = (PI * f0 / sample_rate).tan();
+        let vh = 10f64.powf(gain / 20.0);
+        let vb = vh.powf(0.4996667741545416);
+        let shelf = Biquad::new(
+            vh + vb * k / q + k * k,
+            2.0 * (k * k - vh),
+            vh - vb * k / q + k * k,
+            1.0 + k / q + k * k,
+            2.0 * (k * k - 1.0),
+            1.0 - k / q + k * k,
+        );
+
+        let f0 = 38.13547087602444;
+        let q = 0.5003270373238773;
+        let k = (PI * f0 / sample_rate).tan();
+        let high_pass = Biquad::new(
+            1.0,
+            -2.0,
+            1.0,
+            1.0 + k / q + k * k,
+            2.0 * (k * k - 1.0),
+            1.0 - k / q + k * k,
+        );
+        (shelf, high_pass)
+    }
+
+    /// interleaved samples, incomplete frames at the end are ignored
+    pub fn add_frame(&mut self, frame: &[f32]) {
+        for (channel, sample) in frame.iter().enumerate().take(self.channels) {
+            self.peak = self.peak.max(sample.abs());
+            let (shelf, high_pass) = &mut self.filters[channel];
+            let filtered = high_pass.process(shelf.process(*sample as f64));
+            self.sub_block_energy += filtered * filtered;
+        }
+        self.sub_block_frames += 1;
+        if self.sub_block_frames == self.sub_block_len {
+            self.sub_blocks.push(self.sub_block_energy / self.sub_block_len as f64);
+            self.sub_block_frames = 0;
+            self.sub_block_energy = 0.0;
+        }
+    }
+
+    fn loudness(energy: f64) -> f64 {
+        -0.691 + 10.0 * energy.log10()
+    }
+
+    /// None for silence or audio shorter than one gating block
+    pub fn integrated_loudness(&self) -> Option<f64> {
+        let blocks: Vec<f64> = self
+            .sub_blocks
+            .windows(SUB_BLOCKS_PER_BLOCK)
+            .map(|w| w.iter().sum::<f64>() / SUB_BLOCKS_PER_BLOCK as f64)
+            .filter(|energy| *energy > 0.0 && Self::loudness(*energy) > ABSOLUTE_GATE)
+            .collect();
+        if blocks.is_empty() {
+            return None;
+        }
+        let relative_gate = Self::loudness(blocks.iter().sum::<f64>() / blocks.len() as f64) + RELATIVE_GATE;
+        let gated: Vec<f64> = blocks
+            .into_iter()
+            .filter(|energy| Self::loudness(*energy) > relative_gate)
+            .collect();
+        if gated.is_empty() {
+            return None;
+        }
+        Some(Self::loudness(gated.iter().sum::<f64>() / gated.len() as f64))
+    }
+
+    pub fn replay_gain(&self) -> Option<MediaSourceReplayGain> {
+        let loudness = self.integrated_loudness()?;
+        Some(MediaSourceReplayGain {
+            track_gain: Some((REFERENCE_LOUDNESS - loudness) as f32),
+            track_peak: Some(self.peak),
+            album_gain: None,
+            album_peak: None,
+            analyzed: true,
+        })
+    }
+
+    /// Decodes the whole file, this is slow and should run in a background job
+    pub fn analyze_file(path: &Path) -> Option<MediaSourceReplayGain> {
+        let file = File::open(path).ok()?;
+        let decoder = rodio::Decoder::try_from(file).ok()?;
+        let channels = decoder.channels();
+        let mut analyzer = LoudnessAnalyzer::new(channels, decoder.sample_rate());
+
+        let mut frame: Vec<f32> = Vec::with_capacity(channels as usize);
+        for sample in decoder {
+            frame.push(sample);
+            if frame.len() == channels as usize {
+                analyzer.add_frame(&frame);
+                frame.clear();
+            }
+        }
+        analyzer.replay_gain()
+    }
+}
@@ -1,16 +1,51 @@
-pub(crate) use crate::media_source::media_source_item::MediaSourceItem;
-pub(crate) use crate::media_source::media_type::MediaType;
+pub use crate::media_source::media_source_item::MediaSourceItem;
+pub use crate::media_source::media_type::MediaType;
 use tokio::sync::mpsc::{UnboundedReceiver, UnboundedSender};
-pub(crate) use crate::media_source::media_source_command::MediaSourceCommand;
-pub(crate) use crate::media_source::media_source_event::MediaSourceEvent;
+pub use crate::media_source::media_source_command::MediaSourceCommand;
+pub use crate::media_source::media_source_event::MediaSourceEvent;
+pub use crate::media_source::media_source_error::MediaSourceError;
+pub use crate::media_source::media_source_metadata_update::MediaSourceMetadataUpdate;
+pub use crate::media_source::media_source_progress_reason::MediaSourceProgressReason;
+pub use crate::media_source::media_source_bookmark::MediaSourceBookmark;
+pub use crate::media_source::media_source_listening_session::MediaSourceListeningSession;
+pub use crate::media_source::media_source_listening_state::MediaSourceListeningState;
+use std::time::Duration;
 
 #[async_trait::async_trait]
 pub trait MediaSource: Send + Sync {
     fn id(&self) -> String;
     async fn filter(&self, query: &str) -> Vec<MediaSourceItem>;
     async fn find(&self, id: &str) -> Option<MediaSourceItem>;
+    /// Available items with the given series, in no particular order
+    async fn series_items(&self, series: &str) -> Vec<MediaSourceItem>;
 
+    /// Writes the changes back to the media file and updates the index
+    async fn update_metadata(&self, id: &str, update: MediaSourceMetadataUpdate) -> Result<MediaSourceItem, MediaSourceError>;
 
+
+    /// Records a playback position of a listening session
+    async fn add_progress_history(
+        &self,
+        id: &str,
+        session_key: &str,
+        reason: MediaSourceProgressReason,
+        position: Duration,
+        previous_position: Duration,
+    ) -> Result<(), MediaSourceError>;
+    /// Stores a finished listening session for the statistics
+    async fn add_listening_session(&self, session: MediaSourceListeningSession) -> Result<(), MediaSourceError>;
+    /// Remembers the last listened position and whether the item is finished
+    async fn update_listening_progress(
+        &self,
+        id: &str,
+        position: Duration,
+        state: MediaSourceListeningState,
+    ) -> Result<(), MediaSourceError>;
+    /// Stores a new bookmark, the returned bookmark contains the id
+    async fn add_bookmark(&self, bookmark: MediaSourceBookmark) -> Result<MediaSourceBookmark, MediaSourceError>;
+    /// Bookmarks of an item ordered by position
+    async fn bookmarks(&self, id: &str) -> Vec<MediaSourceBookmark>;
+    async fn remove_bookmark(&self, bookmark_id: &str) -> Result<(), MediaSourceError>;
     /// Async run loop - consumes self
     async fn run(
         self,
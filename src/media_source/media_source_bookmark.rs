@@ -0,0 +1,28 @@
+use chrono::{DateTime, Utc};
+use std::time::Duration;
+
+#[derive(Debug, Clone)]
+pub struct MediaSourceBookmark {
+    // empty until the bookmark is stored
+    pub id: String,
+    pub item_id: String,
+    pub position: Duration,
+    pub chapter_index: Option<usize>,
+    pub chapter_name: Option<String>,
+    pub note: Option<String>,
+    pub date_created: DateTime<Utc>,
+}
+
+impl MediaSourceBookmark {
+    pub fn new(item_id: String, position: Duration) -> Self {
+        Self {
+            id: String::new(),
+            item_id,
+            position,
+            chapter_index: None,
+            chapter_name: None,
+            note: None,
+            date_created: Utc::now(),
+        }
+    }
+}
@@ -1,7 +1,31 @@
+use crate::media_source::exclusion_rules::ExclusionRules;
+use crate::media_source::media_source_metadata_update::MediaSourceMetadataUpdate;
+use crate::media_source::media_source_listening_state::MediaSourceListeningState;
+use crate::media_source::media_type_rule::MediaTypeRule;
+
 #[derive(Debug)]
 pub enum MediaSourceCommand {
     Filter(String),
     Find(String),
+    UpdateMetadata(String, MediaSourceMetadataUpdate),
+    // re-apply the media type rules to all items without rescanning the files
+    Reclassify,
+    UpdateMediaTypeRules(Vec<MediaTypeRule>),
+    // stores the global exclusions and rescans all library roots
+    UpdateExclusionRules(ExclusionRules),
+    // item id
+    Bookmarks(String),
+    // item id and bookmark id
+    RemoveBookmark(String, String),
+    // target path of the JSON file
+    ExportBookmarks(String),
+    // items in progress, most recently played first
+    ContinueListening,
+    // item id and the manually chosen state
+    SetListeningState(String, MediaSourceListeningState),
+    ListeningStats,
+    // target paths of the CSV file with all sessions and the JSON file with the statistics
+    ExportListeningStats(String, String),
 }
 
 
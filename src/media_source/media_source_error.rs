@@ -0,0 +1,36 @@
+use std::fmt;
+
+#[derive(Debug)]
+pub enum MediaSourceError {
+    NotFound(String),
+    Offline(String),
+    Unsupported(String),
+    Tag(String),
+    Io(std::io::Error),
+    Db(sea_orm::DbErr),
+}
+
+impl fmt::Display for MediaSourceError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MediaSourceError::NotFound(id) => write!(f, "item not found: {}", id),
+            MediaSourceError::Offline(id) => write!(f, "item is offline: {}", id),
+            MediaSourceError::Unsupported(msg) => write!(f, "not supported: {}", msg),
+            MediaSourceError::Tag(msg) => write!(f, "could not write tags: {}", msg),
+            MediaSourceError::Io(e) => write!(f, "io error: {}", e),
+            MediaSourceError::Db(e) => write!(f, "database error: {}", e),
+        }
+    }
+}
+
+impl From<sea_orm::DbErr> for MediaSourceError {
+    fn from(e: sea_orm::DbErr) -> Self {
+        MediaSourceError::Db(e)
+    }
+}
+
+impl From<std::io::Error> for MediaSourceError {
+    fn from(e: std::io::Error) -> Self {
+        MediaSourceError::Io(e)
+    }
+}
@@ -1,7 +1,27 @@
+use crate::media_source::media_source_bookmark::MediaSourceBookmark;
 use crate::media_source::media_source_item::MediaSourceItem;
+use crate::media_source::media_source_listening_stats::MediaSourceListeningStats;
 
 #[derive(Debug)]
 pub enum MediaSourceEvent {
     FilterResults(Vec<MediaSourceItem>),
     FindResult(Option<MediaSourceItem>),
+    MetadataUpdated(MediaSourceItem),
+    // item id and error message
+    MetadataUpdateFailed(String, String),
+    // number of items that changed their media type
+    Reclassified(usize),
+    // library root path and whether it is available now
+    LibraryRootChanged(String, bool),
+    // library root path, sent when the scan of an appeared root is finished
+    LibraryRootScanned(String),
+    // item id and its bookmarks
+    BookmarksResult(String, Vec<MediaSourceBookmark>),
+    // path and number of exported bookmarks
+    BookmarksExported(String, usize),
+    ContinueListeningResults(Vec<MediaSourceItem>),
+    ListeningStateChanged(MediaSourceItem),
+    ListeningStatsResult(MediaSourceListeningStats),
+    // number of exported sessions
+    ListeningStatsExported(usize),
 }
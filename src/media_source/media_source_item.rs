@@ -1,5 +1,6 @@
 use crate::media_source::media_source_metadata::MediaSourceMetadata;
 use crate::media_source::media_type::MediaType;
+use crate::media_source::media_source_listening_progress::MediaSourceListeningProgress;
 
 #[derive(Debug, Clone)]
 pub struct MediaSourceItem {
@@ -7,5 +8,6 @@ pub struct MediaSourceItem {
     pub location: String,
     pub title: String,
     pub media_type: MediaType,
-    pub metadata: MediaSourceMetadata
+    pub metadata: MediaSourceMetadata,
+    pub progress: MediaSourceListeningProgress,
 }
\ No newline at end of file
@@ -0,0 +1,12 @@
+use crate::media_source::media_source_listening_state::MediaSourceListeningState;
+use chrono::{DateTime, Utc};
+use std::time::Duration;
+
+#[derive(Debug, Clone, Default)]
+pub struct MediaSourceListeningProgress {
+    pub state: MediaSourceListeningState,
+    // last listened position
+    pub position: Duration,
+    // None if the item has never been played
+    pub date_modified: Option<DateTime<Utc>>,
+}
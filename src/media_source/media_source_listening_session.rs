@@ -0,0 +1,33 @@
+use chrono::{DateTime, Utc};
+use std::time::Duration;
+
+/// Continuous playback of one item, from play until pause, stop or the next item
+#[derive(Debug, Clone)]
+pub struct MediaSourceListeningSession {
+    pub item_id: String,
+    pub session_key: String,
+    pub start: DateTime<Utc>,
+    pub end: DateTime<Utc>,
+    pub start_position: Duration,
+    pub end_position: Duration,
+    // time spent listening
+    pub listened: Duration,
+    // time jumped over by seeking forward or skipping silence
+    pub skipped: Duration,
+}
+
+impl MediaSourceListeningSession {
+    pub fn new(item_id: String, session_key: String, start_position: Duration) -> Self {
+        let now = Utc::now();
+        Self {
+            item_id,
+            session_key,
+            start: now,
+            end: now,
+            start_position,
+            end_position: start_position,
+            listened: Duration::ZERO,
+            skipped: Duration::ZERO,
+        }
+    }
+}
@@ -0,0 +1,30 @@
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+// an item counts as finished this close to its end, but at most 5% of its duration
+const FINISHED_REMAINING: Duration = Duration::from_secs(60);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[derive(Serialize, Deserialize)]
+pub enum MediaSourceListeningState {
+    #[default]
+    New = 0,
+    InProgress = 1,
+    Finished = 2,
+}
+
+impl MediaSourceListeningState {
+    /// State after listening up to `position`, finished near the end of the item
+    pub fn at(position: Duration, duration: Duration) -> Self {
+        if Self::is_finished_at(position, duration) {
+            MediaSourceListeningState::Finished
+        } else {
+            MediaSourceListeningState::InProgress
+        }
+    }
+
+    /// Whether listening up to `position` finishes an item, never for items without duration
+    pub fn is_finished_at(position: Duration, duration: Duration) -> bool {
+        !duration.is_zero() && duration.saturating_sub(position) <= FINISHED_REMAINING.min(duration / 20)
+    }
+}
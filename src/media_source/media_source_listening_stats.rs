@@ -0,0 +1,220 @@
+use crate::media_source::media_source_item::MediaSourceItem;
+use crate::media_source::media_source_listening_session::MediaSourceListeningSession;
+use crate::media_source::media_source_listening_state::MediaSourceListeningState;
+use crate::media_source::media_type::MediaType;
+use chrono::{DateTime, Days, Local, NaiveDate, Utc, Weekday};
+use std::collections::{BTreeMap, HashMap};
+use std::time::Duration;
+
+const STATS_DAYS: u64 = 7;
+const STATS_WEEKS: u64 = 8;
+const TOP_AUTHORS: usize = 5;
+
+/// Listening sessions aggregated for the statistics screen. Sessions count for
+/// the (local) day they started on.
+#[derive(Debug, Clone, Default)]
+pub struct MediaSourceListeningStats {
+    // listened time of the last days, oldest first
+    pub days: Vec<(NaiveDate, Duration)>,
+    // listened time of the last weeks by their first day (monday), oldest first
+    pub weeks: Vec<(NaiveDate, Duration)>,
+    pub total_listened: Duration,
+    pub total_skipped: Duration,
+    // days in a row with listening up to today, yesterday still counts so that
+    // the streak is not lost before listening today
+    pub current_streak: u32,
+    pub longest_streak: u32,
+    // titles of audiobooks listened to the end, most recently finished first
+    pub finished_books: Vec<String>,
+    // authors with their listened time, most listened first
+    pub top_authors: Vec<(String, Duration)>,
+}
+
+impl MediaSourceListeningStats {
+    /// `sessions` must be ordered by start, `items` contains the items of the sessions by id.
+    /// Books finished more than once are ordered by the last time they were finished.
+    pub fn from_sessions(
+        sessions: &[MediaSourceListeningSession],
+        items: &HashMap<String, MediaSourceItem>,
+        today: NaiveDate,
+    ) -> Self {
+        let mut stats = Self::default();
+        let mut per_day: BTreeMap<NaiveDate, Duration> = BTreeMap::new();
+        let mut per_author: HashMap<String, Duration> = HashMap::new();
+        // end of the last session that reached the end of an audiobook by item id
+        let mut finished: HashMap<String, (DateTime<Utc>, String)> = HashMap::new();
+
+        for session in sessions {
+            let day = session.start.with_timezone(&Local).date_naive();
+            *per_day.entry(day).or_default() += session.listened;
+            stats.total_listened += session.listened;
+            stats.total_skipped += session.skipped;
+
+            let Some(item) = items.get(&session.item_id) else {
+                continue;
+            };
+            if let Some(author) = item.metadata.artist.as_ref().filter(|a| !a.trim().is_empty()) {
+                *per_author.entry(author.clone()).or_default() += session.listened;
+            }
+            if item.media_type == MediaType::Audiobook
+                && MediaSourceListeningState::is_finished_at(session.end_position, item.metadata.duration)
+            {
+                finished.insert(item.id.clone(), (session.end, item.title.clone()));
+            }
+        }
+
+        let mut finished: Vec<(DateTime<Utc>, String)> = finished.into_values().collect();
+        finished.sort_by(|a, b| b.0.cmp(&a.0).then_with(|| a.1.cmp(&b.1)));
+        stats.finished_books = finished.into_iter().map(|(_, title)| title).collect();
+
+        stats.days = (0..STATS_DAYS)
+            .rev()
+            .map(|i| today - Days::new(i))
+            .map(|day| (day, per_day.get(&day).copied().unwrap_or_default()))
+            .collect();
+
+        let this_week = today.week(Weekday::Mon).first_day();
+        stats.weeks = (0..STATS_WEEKS)
+            .rev()
+            .map(|i| this_week - Days::new(i * 7))
+            .map(|monday| {
+                let listened = per_day.range(monday..monday + Days::new(7)).map(|(_, d)| *d).sum();
+                (monday, listened)
+            })
+            .collect();
+
+        let listening_days: Vec<NaiveDate> = per_day
+            .iter()
+            .filter(|(_, listened)| !listened.is_zero())
+            .map(|(day, _)| *day)
+            .collect();
+        let mut streak = 0;
+        let mut previous: Option<NaiveDate> = None;
+        for day in &listening_days {
+            streak = match previous {
+                Some(previous) if previous.succ_opt() == Some(*day) => streak + 1,
+                _ => 1,
+            };
+            stats.longest_streak = stats.longest_streak.max(streak);
+            previous = Some(*day);
+        }
+        let yesterday = today - Days::new(1);
+        if previous == Some(today) || previous == Some(yesterday) {
+            stats.current_streak = streak;
+        }
+
+        let mut top_authors: Vec<(String, Duration)> = per_author.into_iter().collect();
+        top_authors.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        top_authors.truncate(TOP_AUTHORS);
+        stats.top_authors = top_authors;
+
+        stats
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::media_source::media_source_listening_progress::MediaSourceListeningProgress;
+    use crate::media_source::media_source_metadata::MediaSourceMetadata;
+    use chrono::{NaiveTime, TimeZone};
+
+    fn date(day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2026, 6, day).unwrap()
+    }
+
+    fn local(day: u32, hour: u32, minute: u32) -> DateTime<Utc> {
+        let time = NaiveTime::from_hms_opt(hour, minute, 0).unwrap();
+        Local.from_local_datetime(&date(day).and_time(time)).earliest().unwrap().with_timezone(&Utc)
+    }
+
+    fn book(id: &str, duration: Duration) -> MediaSourceItem {
+        let mut metadata = MediaSourceMetadata::new(None, Some(id.to_string()), None, None, None, None, None, None, vec![]);
+        metadata.duration = duration;
+        MediaSourceItem {
+            id: id.to_string(),
+            location: format!("{}.m4b", id),
+            title: id.to_string(),
+            media_type: MediaType::Audiobook,
+            metadata,
+            progress: MediaSourceListeningProgress::default(),
+        }
+    }
+
+    fn session(item_id: &str, start: DateTime<Utc>, listened: Duration, end_position: Duration) -> MediaSourceListeningSession {
+        MediaSourceListeningSession {
+            item_id: item_id.to_string(),
+            session_key: format!("{}-{}", item_id, start.timestamp_millis()),
+            start,
+            end: start + listened,
+            start_position: end_position.saturating_sub(listened),
+            end_position,
+            listened,
+            skipped: Duration::ZERO,
+        }
+    }
+
+    fn items(items: Vec<MediaSourceItem>) -> HashMap<String, MediaSourceItem> {
+        items.into_iter().map(|item| (item.id.clone(), item)).collect()
+    }
+
+    #[test]
+    fn finished_books_agree_with_the_listening_state() {
+        let minutes = |m: u64| Duration::from_secs(m * 60);
+        // 5% of 10 minutes is less than the 60s tolerance of long books
+        let items = items(vec![book("short", minutes(10)), book("long", minutes(600))]);
+        let sessions = vec![
+            session("short", local(10, 8, 0), minutes(5), minutes(10) - Duration::from_secs(45)),
+            session("long", local(10, 9, 0), minutes(5), minutes(600) - Duration::from_secs(45)),
+        ];
+        for s in &sessions {
+            let duration = items[&s.item_id].metadata.duration;
+            let finished = MediaSourceListeningState::at(s.end_position, duration) == MediaSourceListeningState::Finished;
+            assert_eq!(finished, s.item_id == "long");
+        }
+
+        let stats = MediaSourceListeningStats::from_sessions(&sessions, &items, date(10));
+        assert_eq!(stats.finished_books, vec!["long"]);
+    }
+
+    #[test]
+    fn finished_books_are_ordered_by_finish_date() {
+        let hour = Duration::from_secs(60 * 60);
+        let items = items(vec![book("a", hour), book("b", hour), book("c", hour)]);
+        // ordered by start, but "a" is finished last by a session that started first
+        let sessions = vec![
+            session("a", local(10, 8, 0), 5 * hour, hour),
+            session("b", local(10, 9, 0), hour / 2, hour),
+            session("c", local(10, 10, 0), hour / 2, hour),
+        ];
+
+        let stats = MediaSourceListeningStats::from_sessions(&sessions, &items, date(10));
+        assert_eq!(stats.finished_books, vec!["a", "c", "b"]);
+    }
+
+    #[test]
+    fn streaks_count_consecutive_local_days() {
+        let minutes = Duration::from_secs(10 * 60);
+        let items = items(vec![book("a", Duration::from_secs(60 * 60))]);
+        let sessions = vec![
+            // just before and after midnight count for two days
+            session("a", local(1, 23, 50), minutes, minutes),
+            session("a", local(2, 0, 10), minutes, minutes),
+            session("a", local(3, 12, 0), minutes, minutes),
+            // a gap day on the 4th
+            session("a", local(5, 12, 0), minutes, minutes),
+            session("a", local(6, 12, 0), minutes, minutes),
+        ];
+
+        let stats = MediaSourceListeningStats::from_sessions(&sessions, &items, date(6));
+        assert_eq!(stats.longest_streak, 3);
+        assert_eq!(stats.current_streak, 2);
+
+        // yesterday still counts, the day before does not
+        let stats = MediaSourceListeningStats::from_sessions(&sessions, &items, date(7));
+        assert_eq!(stats.current_streak, 2);
+        let stats = MediaSourceListeningStats::from_sessions(&sessions, &items, date(8));
+        assert_eq!(stats.current_streak, 0);
+        assert_eq!(stats.longest_streak, 3);
+    }
+}
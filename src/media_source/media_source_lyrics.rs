@@ -0,0 +1,232 @@
+use crate::media_source::media_source_lyrics_line::MediaSourceLyricsLine;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+#[derive(Debug, Clone)]
+#[derive(Serialize, Deserialize)]
+pub struct MediaSourceLyrics {
+    // false for plain text lyrics, all lines start at 0 then
+    pub synced: bool,
+    // LRC [offset:] tag in milliseconds, positive values show lines earlier
+    #[serde(default)]
+    pub offset: i64,
+    pub lines: Vec<MediaSourceLyricsLine>,
+}
+
+impl MediaSourceLyrics {
+    pub fn from_plain_text(text: &str) -> Option<Self> {
+        // embedded lyrics often are LRC formatted as well
+        if let Some(lyrics) = Self::from_lrc(text) {
+            return Some(lyrics);
+        }
+
+        let lines: Vec<MediaSourceLyricsLine> = text
+            .lines()
+            .map(|line| MediaSourceLyricsLine::new(Duration::ZERO, line.trim_end().to_string()))
+            .collect();
+        if lines.iter().all(|line| line.text.trim().is_empty()) {
+            return None;
+        }
+        Some(Self {
+            synced: false,
+            offset: 0,
+            lines,
+        })
+    }
+
+    /// Parses LRC content, returns None if it does not contain a single timestamped line
+    pub fn from_lrc(content: &str) -> Option<Self> {
+        let mut offset: i64 = 0;
+        let mut lines: Vec<MediaSourceLyricsLine> = Vec::new();
+
+        for raw_line in content.lines() {
+            let mut rest = raw_line.trim();
+            let mut timestamps: Vec<Duration> = Vec::new();
+
+            while rest.starts_with('[') {
+                let Some(end) = rest.find(']') else {
+                    break;
+                };
+                let tag = &rest[1..end];
+                rest = &rest[end + 1..];
+
+                if let Some(timestamp) = parse_lrc_timestamp(tag) {
+                    timestamps.push(timestamp);
+                } else if let Some((key, value)) = tag.split_once(':')
+                    && key.trim().eq_ignore_ascii_case("offset")
+                {
+                    offset = value.trim().trim_start_matches('+').parse().unwrap_or(0);
+                }
+            }
+
+            if timestamps.is_empty() {
+                continue;
+            }
+
+            let text = strip_word_timestamps(rest);
+            for timestamp in timestamps {
+                lines.push(MediaSourceLyricsLine::new(timestamp, text.clone()));
+            }
+        }
+
+        if lines.is_empty() {
+            return None;
+        }
+        lines.sort_by_key(|line| line.start);
+        Some(Self {
+            synced: true,
+            offset,
+            lines,
+        })
+    }
+
+    pub fn from_synced(offset: i64, mut lines: Vec<MediaSourceLyricsLine>) -> Option<Self> {
+        if lines.is_empty() {
+            return None;
+        }
+        lines.sort_by_key(|line| line.start);
+        Some(Self {
+            synced: true,
+            offset,
+            lines,
+        })
+    }
+
+    /// Start of the line in the playback timeline with the offset applied
+    pub fn line_start(&self, index: usize) -> Duration {
+        let start = self.lines[index].start.as_millis() as i64 - self.offset;
+        Duration::from_millis(start.max(0) as u64)
+    }
+
+    /// End of the line in the playback timeline (start of the next line)
+    pub fn line_end(&self, index: usize) -> Duration {
+        if index + 1 < self.lines.len() {
+            self.line_start(index + 1)
+        } else {
+            Duration::MAX
+        }
+    }
+
+    /// Start of the first line after `position`, None for plain text lyrics
+    pub fn next_line_start(&self, position: Duration) -> Option<Duration> {
+        if !self.synced {
+            return None;
+        }
+        (0..self.lines.len())
+            .map(|index| self.line_start(index))
+            .find(|start| *start > position)
+    }
+}
+
+// [mm:ss], [mm:ss.xx], [mm:ss.xxx] or [mm:ss:xx]
+fn parse_lrc_timestamp(tag: &str) -> Option<Duration> {
+    let (minutes, rest) = tag.split_once(':')?;
+    let minutes: u64 = minutes.trim().parse().ok()?;
+    let (seconds, fraction) = match rest.split_once(['.', ':']) {
+        Some((s, f)) => (s, f),
+        None => (rest, ""),
+    };
+    let seconds: u64 = seconds.trim().parse().ok()?;
+    let fraction = fraction.trim();
+    let millis: u64 = if fraction.is_empty() {
+        0
+    } else {
+        let digits: u64 = fraction.parse().ok()?;
+        match fraction.len() {
+            1 => digits * 100,
+            2 => digits * 10,
+            3 => digits,
+            _ => return None,
+        }
+    };
+    Some(Duration::from_millis((minutes * 60 + seconds) * 1000 + millis))
+}
+
+// enhanced LRC contains word timestamps like <00:12.34>
+fn strip_word_timestamps(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find('<') {
+        let Some(end) = rest[start..].find('>') else {
+            break;
+        };
+        if parse_lrc_timestamp(&rest[start + 1..start + end]).is_none() {
+            result.push_str(&rest[..start + end + 1]);
+        } else {
+            result.push_str(&rest[..start]);
+        }
+        rest = &rest[start + end + 1..];
+    }
+    result.push_str(rest);
+    result.trim().to_string()
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn starts_and_texts(lyrics: &MediaSourceLyrics) -> Vec<(u64, &str)> {
+        lyrics.lines.iter().map(|line| (line.start.as_millis() as u64, line.text.as_str())).collect()
+    }
+
+    #[test]
+    fn offset_tag_is_parsed() {
+        let lyrics = MediaSourceLyrics::from_lrc("[offset:+500]\n[00:01.00]one").unwrap();
+        assert_eq!(lyrics.offset, 500);
+        let lyrics = MediaSourceLyrics::from_lrc("[OFFSET: -250]\n[00:01.00]one").unwrap();
+        assert_eq!(lyrics.offset, -250);
+        // negative offsets show lines later
+        assert_eq!(lyrics.line_start(0), Duration::from_millis(1250));
+        let lyrics = MediaSourceLyrics::from_lrc("[offset:soon]\n[00:01.00]one").unwrap();
+        assert_eq!(lyrics.offset, 0);
+    }
+
+    #[test]
+    fn lines_with_multiple_timestamps_are_repeated() {
+        let lyrics = MediaSourceLyrics::from_lrc("[00:01.00][00:05.00]chorus\n[00:03.00]verse").unwrap();
+        assert!(lyrics.synced);
+        assert_eq!(starts_and_texts(&lyrics), vec![(1000, "chorus"), (3000, "verse"), (5000, "chorus")]);
+    }
+
+    #[test]
+    fn timestamp_formats() {
+        assert_eq!(parse_lrc_timestamp("01:02.50"), Some(Duration::from_millis(62_500)));
+        assert_eq!(parse_lrc_timestamp("01:02:50"), Some(Duration::from_millis(62_500)));
+        assert_eq!(parse_lrc_timestamp("01:02.5"), Some(Duration::from_millis(62_500)));
+        assert_eq!(parse_lrc_timestamp("01:02.505"), Some(Duration::from_millis(62_505)));
+        assert_eq!(parse_lrc_timestamp("01:02"), Some(Duration::from_millis(62_000)));
+        assert_eq!(parse_lrc_timestamp("01:02.5050"), None);
+        assert_eq!(parse_lrc_timestamp("ar:Artist"), None);
+        assert_eq!(parse_lrc_timestamp("00:xx.00"), None);
+    }
+
+    #[test]
+    fn enhanced_word_timestamps_are_stripped() {
+        let lyrics = MediaSourceLyrics::from_lrc("[00:01.00]<00:01.00>Hello <00:01.50>world <00:02.00>\n[00:03.00]a <b> c").unwrap();
+        assert_eq!(starts_and_texts(&lyrics), vec![(1000, "Hello world"), (3000, "a <b> c")]);
+    }
+
+    #[test]
+    fn malformed_lines_are_skipped() {
+        let content = "[ar:Artist]\nno tag\n[00:01.00 unterminated\n[00:xx.00]bad\n[00:02.00]good\n[]empty";
+        let lyrics = MediaSourceLyrics::from_lrc(content).unwrap();
+        assert_eq!(starts_and_texts(&lyrics), vec![(2000, "good")]);
+        assert!(MediaSourceLyrics::from_lrc("[ar:Artist]\njust text").is_none());
+    }
+
+    #[test]
+    fn next_line_start_applies_the_offset() {
+        let lyrics = MediaSourceLyrics::from_lrc("[offset:+500]\n[00:01.00]one\n[00:02.50]two\n[00:04.00]three").unwrap();
+        assert_eq!(lyrics.next_line_start(Duration::ZERO), Some(Duration::from_millis(500)));
+        assert_eq!(lyrics.next_line_start(Duration::from_millis(500)), Some(Duration::from_millis(2000)));
+        assert_eq!(lyrics.next_line_start(Duration::from_millis(2100)), Some(Duration::from_millis(3500)));
+        assert_eq!(lyrics.next_line_start(Duration::from_millis(3500)), None);
+    }
+
+    #[test]
+    fn plain_text_has_no_line_starts() {
+        let lyrics = MediaSourceLyrics::from_plain_text("one\ntwo").unwrap();
+        assert_eq!(lyrics.next_line_start(Duration::ZERO), None);
+    }
+}
@@ -0,0 +1,16 @@
+use std::time::Duration;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone)]
+#[derive(Serialize, Deserialize)]
+pub struct MediaSourceLyricsLine {
+    #[serde(with = "crate::serde_json_mods::duration_millis")]
+    pub start: Duration,
+    pub text: String,
+}
+
+impl MediaSourceLyricsLine {
+    pub fn new(start: Duration, text: String) -> Self {
+        Self { start, text }
+    }
+}
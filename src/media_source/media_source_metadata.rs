@@ -1,5 +1,8 @@
 use crate::media_source::media_source_chapter::MediaSourceChapter;
+use crate::media_source::media_source_lyrics::MediaSourceLyrics;
 use crate::media_source::media_source_picture::MediaSourcePicture;
+use crate::media_source::media_source_replay_gain::MediaSourceReplayGain;
+use std::time::Duration;
 
 #[derive(Debug, Clone)]
 pub struct MediaSourceMetadata {
@@ -13,11 +16,16 @@ pub struct MediaSourceMetadata {
     pub part: Option<String>,
     pub cover: Option<MediaSourcePicture>,
     pub chapters: Vec<MediaSourceChapter>,
+    pub lyrics: Option<MediaSourceLyrics>,
+    pub replay_gain: Option<MediaSourceReplayGain>,
+    // not a tag, but read from the audio properties
+    pub duration: Duration,
 }
 
 
 
 impl MediaSourceMetadata {
+    #[allow(clippy::too_many_arguments)]
     pub fn new(artist: Option<String>,
                title: Option<String>,
                album: Option<String>,
@@ -38,6 +46,9 @@ impl MediaSourceMetadata {
             part,
             cover,
             chapters,
+            lyrics: None,
+            replay_gain: None,
+            duration: Duration::ZERO,
         }
     }
 }
\ No newline at end of file
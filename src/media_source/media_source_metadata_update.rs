@@ -0,0 +1,24 @@
+use crate::media_source::media_source_chapter::MediaSourceChapter;
+
+/// Changes to apply to an item, fields that are None are kept as they are,
+/// empty strings remove the value
+#[derive(Debug, Clone, Default)]
+pub struct MediaSourceMetadataUpdate {
+    pub title: Option<String>,
+    pub artist: Option<String>,
+    pub series: Option<String>,
+    pub part: Option<String>,
+    pub genre: Option<String>,
+    pub chapters: Option<Vec<MediaSourceChapter>>,
+}
+
+impl MediaSourceMetadataUpdate {
+    pub fn is_empty(&self) -> bool {
+        self.title.is_none()
+            && self.artist.is_none()
+            && self.series.is_none()
+            && self.part.is_none()
+            && self.genre.is_none()
+            && self.chapters.is_none()
+    }
+}
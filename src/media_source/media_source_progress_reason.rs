@@ -0,0 +1,9 @@
+use serde::{Deserialize, Serialize};
+
+/// Why a progress history entry was recorded
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Serialize, Deserialize)]
+pub enum MediaSourceProgressReason {
+    Position = 0,
+    AutoRewind = 2,
+}
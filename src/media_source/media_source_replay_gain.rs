@@ -0,0 +1,56 @@
+use serde::{Deserialize, Serialize};
+
+/// Gains in dB relative to the ReplayGain reference level, peaks as linear amplitude (1.0 = full scale)
+#[derive(Debug, Clone, Default, PartialEq)]
+#[derive(Serialize, Deserialize)]
+pub struct MediaSourceReplayGain {
+    pub track_gain: Option<f32>,
+    pub track_peak: Option<f32>,
+    pub album_gain: Option<f32>,
+    pub album_peak: Option<f32>,
+    // computed from the audio (EBU R128) instead of read from tags
+    #[serde(default)]
+    pub analyzed: bool,
+}
+
+impl MediaSourceReplayGain {
+    pub fn is_empty(&self) -> bool {
+        self.track_gain.is_none() && self.album_gain.is_none()
+    }
+
+    /// "-6.54 dB", "+1.2 dB" or "-6.54"
+    pub fn parse_gain(value: &str) -> Option<f32> {
+        let value = value.trim();
+        let value = value
+            .strip_suffix("dB")
+            .or_else(|| value.strip_suffix("db"))
+            .or_else(|| value.strip_suffix("DB"))
+            .unwrap_or(value);
+        value.trim().trim_start_matches('+').parse().ok()
+    }
+
+    pub fn parse_peak(value: &str) -> Option<f32> {
+        value.trim().parse().ok().filter(|peak: &f32| *peak > 0.0)
+    }
+
+    /// iTunNORM contains ten hex values, the first two are the track volume
+    /// adjustments for 1/1000 and 1/2500 W, the larger one is used
+    pub fn from_itunes_normalization(value: &str) -> Option<Self> {
+        let values: Vec<u32> = value
+            .split_whitespace()
+            .filter_map(|v| u32::from_str_radix(v, 16).ok())
+            .collect();
+        if values.len() < 2 {
+            return None;
+        }
+        let adjustment = values[0].max(values[1]);
+        if adjustment == 0 {
+            return None;
+        }
+        let track_gain = -10.0 * (adjustment as f32 / 1000.0).log10();
+        Some(Self {
+            track_gain: Some(track_gain),
+            ..Default::default()
+        })
+    }
+}
@@ -1,6 +1,10 @@
+use serde::{Deserialize, Serialize};
+
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Serialize, Deserialize)]
 pub enum MediaType {
     Unspecified = 0,
     Audiobook = 2,
     Music = 4,
+    Podcast = 8,
 }
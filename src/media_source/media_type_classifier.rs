@@ -0,0 +1,203 @@
+use crate::media_source::media_type::MediaType;
+use crate::media_source::media_type_rule::MediaTypeRule;
+use globset::{Glob, GlobSet, GlobSetBuilder};
+use std::path::Path;
+use std::time::Duration;
+
+pub struct MediaTypeClassifier {
+    rules: Vec<(MediaTypeRule, Option<GlobSet>)>,
+}
+
+impl MediaTypeClassifier {
+    pub fn new(rules: Vec<MediaTypeRule>) -> Self {
+        let rules = rules
+            .into_iter()
+            .map(|rule| {
+                let glob_set = Self::build_glob_set(&rule.path_globs);
+                (rule, glob_set)
+            })
+            .collect();
+        Self { rules }
+    }
+
+    fn build_glob_set(path_globs: &[String]) -> Option<GlobSet> {
+        if path_globs.is_empty() {
+            return None;
+        }
+        let mut builder = GlobSetBuilder::new();
+        for path_glob in path_globs {
+            match Glob::new(path_glob) {
+                Ok(glob) => {
+                    builder.add(glob);
+                }
+                Err(e) => {
+                    println!("invalid media type rule glob {}: {}", path_glob, e);
+                }
+            }
+        }
+        builder.build().ok()
+    }
+
+    /// `rel_path` is relative to the library root, leading slashes are ignored
+    pub fn classify(&self, rel_path: &str, genre: Option<&str>, duration: Duration) -> MediaType {
+        let rel_path = rel_path.trim_start_matches('/');
+        let extension = Path::new(rel_path)
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .map(|ext| ext.to_lowercase());
+        let genres: Vec<String> = match genre {
+            Some(g) => g
+                .split([',', ';', '/'])
+                .map(|s| s.trim().to_lowercase())
+                .filter(|s| !s.is_empty())
+                .collect(),
+            None => vec![],
+        };
+
+        for (rule, glob_set) in &self.rules {
+            if !rule.path_globs.is_empty() {
+                match glob_set {
+                    Some(set) if set.is_match(rel_path) => {}
+                    _ => continue,
+                }
+            }
+
+            if !rule.genres.is_empty()
+                && !rule.genres.iter().any(|g| genres.contains(&g.to_lowercase()))
+            {
+                continue;
+            }
+
+            if !rule.extensions.is_empty() {
+                let matches = extension.as_ref().is_some_and(|ext| {
+                    rule.extensions
+                        .iter()
+                        .any(|e| e.trim_start_matches('.').to_lowercase() == *ext)
+                });
+                if !matches {
+                    continue;
+                }
+            }
+
+            // unknown durations never match duration rules
+            if (rule.min_duration.is_some() || rule.max_duration.is_some()) && duration.is_zero() {
+                continue;
+            }
+
+            if let Some(min_duration) = rule.min_duration
+                && duration < min_duration
+            {
+                continue;
+            }
+
+            if let Some(max_duration) = rule.max_duration
+                && duration > max_duration
+            {
+                continue;
+            }
+
+            return rule.media_type.clone();
+        }
+
+        MediaType::Unspecified
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MINUTE: Duration = Duration::from_secs(60);
+
+    fn classify(rules: Vec<MediaTypeRule>, rel_path: &str, genre: Option<&str>, duration: Duration) -> MediaType {
+        MediaTypeClassifier::new(rules).classify(rel_path, genre, duration)
+    }
+
+    #[test]
+    fn path_glob_matches_relative_path() {
+        let rules = vec![MediaTypeRule::new(MediaType::Podcast).with_path_globs(&["podcasts/**"])];
+        assert_eq!(classify(rules.clone(), "podcasts/show/01.mp3", None, MINUTE), MediaType::Podcast);
+        assert_eq!(classify(rules.clone(), "/podcasts/show/01.mp3", None, MINUTE), MediaType::Podcast);
+        assert_eq!(classify(rules, "music/podcasts/01.mp3", None, MINUTE), MediaType::Unspecified);
+    }
+
+    #[test]
+    fn invalid_glob_does_not_match() {
+        let rules = vec![MediaTypeRule::new(MediaType::Music).with_path_globs(&["music/[**"])];
+        assert_eq!(classify(rules, "music/a.mp3", None, MINUTE), MediaType::Unspecified);
+    }
+
+    #[test]
+    fn genre_matches_any_separated_value_ignoring_case() {
+        let rules = vec![MediaTypeRule::new(MediaType::Podcast).with_genres(&["Podcast"])];
+        assert_eq!(classify(rules.clone(), "a.mp3", Some("Rock; podcast"), MINUTE), MediaType::Podcast);
+        assert_eq!(classify(rules.clone(), "a.mp3", Some("News/PODCAST"), MINUTE), MediaType::Podcast);
+        assert_eq!(classify(rules.clone(), "a.mp3", Some("Podcasts"), MINUTE), MediaType::Unspecified);
+        assert_eq!(classify(rules, "a.mp3", None, MINUTE), MediaType::Unspecified);
+    }
+
+    #[test]
+    fn extension_ignores_case_and_leading_dot() {
+        let rules = vec![MediaTypeRule::new(MediaType::Audiobook).with_extensions(&[".m4b"])];
+        assert_eq!(classify(rules.clone(), "book.M4B", None, MINUTE), MediaType::Audiobook);
+        assert_eq!(classify(rules.clone(), "book.m4a", None, MINUTE), MediaType::Unspecified);
+        assert_eq!(classify(rules, "m4b", None, MINUTE), MediaType::Unspecified);
+    }
+
+    #[test]
+    fn duration_bounds_are_inclusive_and_zero_never_matches() {
+        let rules = vec![
+            MediaTypeRule::new(MediaType::Music)
+                .with_min_duration(MINUTE)
+                .with_max_duration(10 * MINUTE),
+        ];
+        assert_eq!(classify(rules.clone(), "a.mp3", None, MINUTE), MediaType::Music);
+        assert_eq!(classify(rules.clone(), "a.mp3", None, 10 * MINUTE), MediaType::Music);
+        assert_eq!(classify(rules.clone(), "a.mp3", None, MINUTE / 2), MediaType::Unspecified);
+        assert_eq!(classify(rules.clone(), "a.mp3", None, 11 * MINUTE), MediaType::Unspecified);
+
+        let rules = vec![MediaTypeRule::new(MediaType::Music).with_max_duration(10 * MINUTE)];
+        assert_eq!(classify(rules, "a.mp3", None, Duration::ZERO), MediaType::Unspecified);
+    }
+
+    #[test]
+    fn all_conditions_of_a_rule_have_to_match() {
+        let rules = vec![
+            MediaTypeRule::new(MediaType::Audiobook)
+                .with_path_globs(&["library/**"])
+                .with_genres(&["Audiobook"]),
+        ];
+        assert_eq!(classify(rules.clone(), "library/a.mp3", Some("Audiobook"), MINUTE), MediaType::Audiobook);
+        assert_eq!(classify(rules.clone(), "library/a.mp3", Some("Rock"), MINUTE), MediaType::Unspecified);
+        assert_eq!(classify(rules, "other/a.mp3", Some("Audiobook"), MINUTE), MediaType::Unspecified);
+    }
+
+    #[test]
+    fn first_matching_rule_wins() {
+        let rules = vec![
+            MediaTypeRule::new(MediaType::Music).with_path_globs(&["music/**"]),
+            MediaTypeRule::new(MediaType::Podcast).with_genres(&["Podcast"]),
+        ];
+        assert_eq!(classify(rules.clone(), "music/a.mp3", Some("Podcast"), MINUTE), MediaType::Music);
+
+        let rules = rules.into_iter().rev().collect();
+        assert_eq!(classify(rules, "music/a.mp3", Some("Podcast"), MINUTE), MediaType::Podcast);
+    }
+
+    #[test]
+    fn default_rules() {
+        let classify = |rel_path, genre, duration| classify(MediaTypeRule::defaults(), rel_path, genre, duration);
+
+        assert_eq!(classify("audiobooks/a/01.mp3", None, 3 * MINUTE), MediaType::Audiobook);
+        assert_eq!(classify("music/book.m4b", None, 120 * MINUTE), MediaType::Music);
+        assert_eq!(classify("podcasts/a.mp3", Some("Audiobook"), MINUTE), MediaType::Podcast);
+        assert_eq!(classify("a.mp3", Some("Hörbuch"), 3 * MINUTE), MediaType::Audiobook);
+        assert_eq!(classify("a.mp3", Some("Podcasts"), 3 * MINUTE), MediaType::Podcast);
+        assert_eq!(classify("a.m4b", None, 3 * MINUTE), MediaType::Audiobook);
+        assert_eq!(classify("a.mp3", None, 120 * MINUTE), MediaType::Audiobook);
+        assert_eq!(classify("a.mp3", None, 3 * MINUTE), MediaType::Music);
+        assert_eq!(classify("a.mp3", None, 30 * MINUTE), MediaType::Unspecified);
+        assert_eq!(classify("a.mp3", None, Duration::ZERO), MediaType::Unspecified);
+    }
+}
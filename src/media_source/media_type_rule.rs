@@ -0,0 +1,115 @@
+use crate::media_source::media_type::MediaType;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+/// A single classification rule. Every condition that is set has to match,
+/// empty lists and missing durations are ignored.
+#[derive(Debug, Clone)]
+#[derive(Serialize, Deserialize)]
+pub struct MediaTypeRule {
+    pub media_type: MediaType,
+    // globs relative to the library root, e.g. "audiobooks/**"
+    #[serde(default)]
+    pub path_globs: Vec<String>,
+    // case-insensitive, matches any of the (comma separated) genres
+    #[serde(default)]
+    pub genres: Vec<String>,
+    // case-insensitive, without leading dot
+    #[serde(default)]
+    pub extensions: Vec<String>,
+    #[serde(default, with = "crate::serde_json_mods::option_duration_millis")]
+    pub min_duration: Option<Duration>,
+    #[serde(default, with = "crate::serde_json_mods::option_duration_millis")]
+    pub max_duration: Option<Duration>,
+}
+
+impl MediaTypeRule {
+    pub fn new(media_type: MediaType) -> Self {
+        Self {
+            media_type,
+            path_globs: vec![],
+            genres: vec![],
+            extensions: vec![],
+            min_duration: None,
+            max_duration: None,
+        }
+    }
+
+    pub fn with_path_globs(mut self, path_globs: &[&str]) -> Self {
+        self.path_globs = path_globs.iter().map(|s| s.to_string()).collect();
+        self
+    }
+
+    pub fn with_genres(mut self, genres: &[&str]) -> Self {
+        self.genres = genres.iter().map(|s| s.to_string()).collect();
+        self
+    }
+
+    pub fn with_extensions(mut self, extensions: &[&str]) -> Self {
+        self.extensions = extensions.iter().map(|s| s.to_string()).collect();
+        self
+    }
+
+    pub fn with_min_duration(mut self, min_duration: Duration) -> Self {
+        self.min_duration = Some(min_duration);
+        self
+    }
+
+    pub fn with_max_duration(mut self, max_duration: Duration) -> Self {
+        self.max_duration = Some(max_duration);
+        self
+    }
+
+    /// Rules used when nothing has been configured yet, the first matching rule wins
+    pub fn defaults() -> Vec<MediaTypeRule> {
+        vec![
+            MediaTypeRule::new(MediaType::Audiobook).with_path_globs(&["audiobooks/**"]),
+            MediaTypeRule::new(MediaType::Music).with_path_globs(&["music/**"]),
+            MediaTypeRule::new(MediaType::Podcast).with_path_globs(&["podcasts/**"]),
+            MediaTypeRule::new(MediaType::Audiobook).with_genres(&[
+                "Audiobook",
+                "Audiobooks",
+                "Hörbuch",
+                "Hörbücher",
+                "Hoerbuch",
+                "Spoken Word",
+            ]),
+            MediaTypeRule::new(MediaType::Podcast).with_genres(&["Podcast", "Podcasts"]),
+            MediaTypeRule::new(MediaType::Audiobook).with_extensions(&["m4b"]),
+            MediaTypeRule::new(MediaType::Audiobook).with_min_duration(Duration::from_secs(60 * 60)),
+            MediaTypeRule::new(MediaType::Music).with_max_duration(Duration::from_secs(15 * 60)),
+        ]
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn missing_fields_use_defaults() {
+        let rule: MediaTypeRule = serde_json::from_str(r#"{"media_type":"Music","max_duration":900000}"#).unwrap();
+        assert_eq!(rule.media_type, MediaType::Music);
+        assert!(rule.path_globs.is_empty());
+        assert!(rule.genres.is_empty());
+        assert!(rule.extensions.is_empty());
+        assert_eq!(rule.min_duration, None);
+        assert_eq!(rule.max_duration, Some(Duration::from_secs(15 * 60)));
+    }
+
+    #[test]
+    fn defaults_survive_a_round_trip() {
+        let json = serde_json::to_string(&MediaTypeRule::defaults()).unwrap();
+        let rules: Vec<MediaTypeRule> = serde_json::from_str(&json).unwrap();
+        assert_eq!(rules.len(), MediaTypeRule::defaults().len());
+        for (rule, expected) in rules.iter().zip(MediaTypeRule::defaults()) {
+            assert_eq!(rule.media_type, expected.media_type);
+            assert_eq!(rule.path_globs, expected.path_globs);
+            assert_eq!(rule.genres, expected.genres);
+            assert_eq!(rule.extensions, expected.extensions);
+            assert_eq!(rule.min_duration, expected.min_duration);
+            assert_eq!(rule.max_duration, expected.max_duration);
+        }
+    }
+}
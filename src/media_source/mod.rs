@@ -4,8 +4,24 @@ pub mod media_type;
 pub mod media_source_item;
 pub mod media_source_event;
 pub mod media_source_metadata;
+pub mod media_source_metadata_update;
+pub mod media_source_error;
+pub mod media_source_progress_reason;
 pub mod media_source_chapter;
+pub mod media_source_bookmark;
+pub mod media_source_listening_session;
+pub mod media_source_listening_stats;
+pub mod media_source_listening_state;
+pub mod media_source_listening_progress;
+pub mod media_source_lyrics;
+pub mod media_source_lyrics_line;
+pub mod media_source_replay_gain;
+pub mod loudness_analyzer;
 pub mod media_source_image_codec;
 pub mod media_source_picture;
 pub mod media_source_command;
-pub(crate) mod utils;
\ No newline at end of file
+pub mod media_type_rule;
+pub mod media_type_classifier;
+pub mod library_root;
+pub mod exclusion_rules;
+pub mod utils;
\ No newline at end of file
@@ -1,9 +1,148 @@
+use crate::entity::{item, items_listening_states, items_progress_history};
 use crate::media_source::media_source::MediaType;
+use crate::media_source::media_source_progress_reason::MediaSourceProgressReason;
+use crate::media_source::media_source_listening_state::MediaSourceListeningState;
+use std::cmp::Ordering;
+use std::iter::Peekable;
+use std::str::Chars;
 
 pub fn convert_media_type_to_int(media_type: &MediaType) -> i32 {
     match media_type {
         MediaType::Unspecified => 0,
         MediaType::Audiobook => 2,
         MediaType::Music => 4,
+        MediaType::Podcast => 8,
+    }
+}
+
+pub fn convert_db_media_type(media_type: &item::MediaType) -> MediaType {
+    match media_type {
+        item::MediaType::Unspecified => MediaType::Unspecified,
+        item::MediaType::Audiobook => MediaType::Audiobook,
+        item::MediaType::Music => MediaType::Music,
+        item::MediaType::Podcast => MediaType::Podcast,
+    }
+}
+
+pub fn convert_media_type_to_db(media_type: &MediaType) -> item::MediaType {
+    match media_type {
+        MediaType::Unspecified => item::MediaType::Unspecified,
+        MediaType::Audiobook => item::MediaType::Audiobook,
+        MediaType::Music => item::MediaType::Music,
+        MediaType::Podcast => item::MediaType::Podcast,
+    }
+}
+
+pub fn convert_progress_reason_to_db(reason: &MediaSourceProgressReason) -> items_progress_history::ProgressReason {
+    match reason {
+        MediaSourceProgressReason::Position => items_progress_history::ProgressReason::Position,
+        MediaSourceProgressReason::AutoRewind => items_progress_history::ProgressReason::AutoRewind,
+    }
+}
+
+pub fn convert_listening_state_to_db(state: &MediaSourceListeningState) -> items_listening_states::ListeningState {
+    match state {
+        MediaSourceListeningState::New => items_listening_states::ListeningState::New,
+        MediaSourceListeningState::InProgress => items_listening_states::ListeningState::InProgress,
+        MediaSourceListeningState::Finished => items_listening_states::ListeningState::Finished,
+    }
+}
+
+pub fn convert_db_listening_state(state: &items_listening_states::ListeningState) -> MediaSourceListeningState {
+    match state {
+        items_listening_states::ListeningState::New => MediaSourceListeningState::New,
+        items_listening_states::ListeningState::InProgress => MediaSourceListeningState::InProgress,
+        items_listening_states::ListeningState::Finished => MediaSourceListeningState::Finished,
+    }
+}
+
+/// Compares like a human would, e.g. part "2" before "10" and "Episode 9" before "episode 10".
+/// Strings that only differ in case or leading zeros are ordered by their characters, so
+/// sorting does not depend on the input order.
+pub fn natural_cmp(a: &str, b: &str) -> Ordering {
+    natural_cmp_ignoring_case(a, b).then_with(|| a.cmp(b))
+}
+
+fn natural_cmp_ignoring_case(a: &str, b: &str) -> Ordering {
+    let mut a = a.chars().peekable();
+    let mut b = b.chars().peekable();
+    loop {
+        let ordering = match (a.peek().copied(), b.peek().copied()) {
+            (None, None) => return Ordering::Equal,
+            (None, Some(_)) => return Ordering::Less,
+            (Some(_), None) => return Ordering::Greater,
+            (Some(x), Some(y)) if x.is_ascii_digit() && y.is_ascii_digit() => {
+                compare_numbers(&take_digits(&mut a), &take_digits(&mut b))
+            }
+            (Some(x), Some(y)) => {
+                a.next();
+                b.next();
+                x.to_lowercase().cmp(y.to_lowercase())
+            }
+        };
+        if ordering != Ordering::Equal {
+            return ordering;
+        }
+    }
+}
+
+fn take_digits(chars: &mut Peekable<Chars>) -> String {
+    let mut digits = String::new();
+    while let Some(digit) = chars.next_if(|c| c.is_ascii_digit()) {
+        digits.push(digit);
+    }
+    digits
+}
+
+// digit strings of any length, without parsing them into a number that could overflow
+fn compare_numbers(a: &str, b: &str) -> Ordering {
+    let a = a.trim_start_matches('0');
+    let b = b.trim_start_matches('0');
+    a.len().cmp(&b.len()).then_with(|| a.cmp(b))
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sorted(mut values: Vec<&str>) -> Vec<&str> {
+        values.sort_by(|a, b| natural_cmp(a, b));
+        values
+    }
+
+    #[test]
+    fn numbers_are_compared_by_value() {
+        assert_eq!(natural_cmp("Part 2", "Part 10"), Ordering::Less);
+        assert_eq!(natural_cmp("Part 10", "Part 2"), Ordering::Greater);
+        assert_eq!(sorted(vec!["Part 10", "Part 1", "Part 2", "Part 100"]), vec!["Part 1", "Part 2", "Part 10", "Part 100"]);
+        // longer than any integer type
+        assert_eq!(natural_cmp("Part 99999999999999999999999", "Part 100000000000000000000000"), Ordering::Less);
+    }
+
+    #[test]
+    fn leading_zeros() {
+        assert_eq!(natural_cmp("Part 02", "Part 10"), Ordering::Less);
+        assert_eq!(natural_cmp("Part 010", "Part 9"), Ordering::Greater);
+        assert_eq!(sorted(vec!["Part 1", "Part 001", "Part 01"]), vec!["Part 001", "Part 01", "Part 1"]);
+    }
+
+    #[test]
+    fn case_is_ignored_first() {
+        assert_eq!(natural_cmp("episode 9", "Episode 10"), Ordering::Less);
+        assert_eq!(natural_cmp("Book a", "book B"), Ordering::Less);
+        assert_eq!(natural_cmp("Ärger", "ärger 2"), Ordering::Less);
+        assert_eq!(sorted(vec!["part 1", "Part 1"]), vec!["Part 1", "part 1"]);
+        assert_eq!(natural_cmp("Part 1", "Part 1"), Ordering::Equal);
+    }
+
+    #[test]
+    fn non_numeric_suffixes() {
+        assert_eq!(
+            sorted(vec!["Part 10", "Part 2b", "Part 2", "Part 2a", "Part 2 (Bonus)"]),
+            vec!["Part 2", "Part 2 (Bonus)", "Part 2a", "Part 2b", "Part 10"],
+        );
+        assert_eq!(natural_cmp("Part", "Part 1"), Ordering::Less);
+        assert_eq!(natural_cmp("Part 1 - Intro", "Part 1 - intro"), Ordering::Less);
     }
 }
@@ -0,0 +1,124 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+/// Schema of the first release, databases created by it already contain these tables
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(Items::Table)
+                    .if_not_exists()
+                    .col(pk_auto(Items::Id))
+                    .col(string(Items::FileId))
+                    .col(integer(Items::MediaType))
+                    .col(string(Items::CoverHash))
+                    .col(string(Items::Location))
+                    .col(string(Items::LastScanRandomKey))
+                    .col(timestamp_with_time_zone(Items::DateModified))
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(ItemsMetadata::Table)
+                    .if_not_exists()
+                    .col(pk_auto(ItemsMetadata::Id))
+                    .col(integer(ItemsMetadata::ItemId))
+                    .col(integer(ItemsMetadata::TagField))
+                    .col(string(ItemsMetadata::Value))
+                    .col(timestamp_with_time_zone(ItemsMetadata::DateModified))
+                    .foreign_key(ForeignKey::create().from(ItemsMetadata::Table, ItemsMetadata::ItemId).to(Items::Table, Items::Id))
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(ItemsJsonMetadata::Table)
+                    .if_not_exists()
+                    .col(pk_auto(ItemsJsonMetadata::Id))
+                    .col(integer(ItemsJsonMetadata::ItemId))
+                    .col(integer(ItemsJsonMetadata::TagField))
+                    .col(string(ItemsJsonMetadata::Value))
+                    .col(timestamp_with_time_zone(ItemsJsonMetadata::DateModified))
+                    .foreign_key(ForeignKey::create().from(ItemsJsonMetadata::Table, ItemsJsonMetadata::ItemId).to(Items::Table, Items::Id))
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(ItemsProgressHistory::Table)
+                    .if_not_exists()
+                    .col(pk_auto(ItemsProgressHistory::Id))
+                    .col(integer(ItemsProgressHistory::ItemId))
+                    .col(string(ItemsProgressHistory::SessionKey))
+                    .col(time(ItemsProgressHistory::Position))
+                    .col(timestamp_with_time_zone(ItemsProgressHistory::DateModified))
+                    .foreign_key(
+                        ForeignKey::create()
+                            .from(ItemsProgressHistory::Table, ItemsProgressHistory::ItemId)
+                            .to(Items::Table, Items::Id),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager.drop_table(Table::drop().table(ItemsProgressHistory::Table).to_owned()).await?;
+        manager.drop_table(Table::drop().table(ItemsJsonMetadata::Table).to_owned()).await?;
+        manager.drop_table(Table::drop().table(ItemsMetadata::Table).to_owned()).await?;
+        manager.drop_table(Table::drop().table(Items::Table).to_owned()).await
+    }
+}
+
+#[derive(DeriveIden)]
+enum Items {
+    Table,
+    Id,
+    FileId,
+    MediaType,
+    CoverHash,
+    Location,
+    LastScanRandomKey,
+    DateModified,
+}
+
+#[derive(DeriveIden)]
+enum ItemsMetadata {
+    Table,
+    Id,
+    ItemId,
+    TagField,
+    Value,
+    DateModified,
+}
+
+#[derive(DeriveIden)]
+enum ItemsJsonMetadata {
+    Table,
+    Id,
+    ItemId,
+    TagField,
+    Value,
+    DateModified,
+}
+
+#[derive(DeriveIden)]
+enum ItemsProgressHistory {
+    Table,
+    Id,
+    ItemId,
+    SessionKey,
+    Position,
+    DateModified,
+}
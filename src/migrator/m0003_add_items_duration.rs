@@ -0,0 +1,32 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+/// The item duration used by the media type rules. Items indexed before have no
+/// duration until their file is indexed again
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Items::Table)
+                    .add_column(big_integer(Items::Duration).default(0))
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(Table::alter().table(Items::Table).drop_column(Items::Duration).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum Items {
+    Table,
+    Duration,
+}
@@ -0,0 +1,38 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+/// Library roots and offline items. Items indexed before get their library root
+/// with the next scan
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // sqlite only supports a single column per alter statement
+        for column in [
+            string(Items::LibraryRoot).default("").to_owned(),
+            boolean(Items::IsOffline).default(false).to_owned(),
+        ] {
+            manager
+                .alter_table(Table::alter().table(Items::Table).add_column(column).to_owned())
+                .await?;
+        }
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        for column in [Items::LibraryRoot, Items::IsOffline] {
+            manager
+                .alter_table(Table::alter().table(Items::Table).drop_column(column).to_owned())
+                .await?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum Items {
+    Table,
+    LibraryRoot,
+    IsOffline,
+}
@@ -0,0 +1,32 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+/// Items whose files are no longer found by the scan of their library root are
+/// hidden instead of deleted, so their progress and bookmarks survive
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Items::Table)
+                    .add_column(boolean(Items::IsMissing).default(false))
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(Table::alter().table(Items::Table).drop_column(Items::IsMissing).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum Items {
+    Table,
+    IsMissing,
+}
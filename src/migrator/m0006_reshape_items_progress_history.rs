@@ -0,0 +1,52 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+/// Positions are stored in milliseconds together with the reason of the entry. Earlier
+/// versions never wrote the history, so the table is recreated instead of converted
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(ItemsProgressHistory::Table).if_exists().to_owned())
+            .await?;
+        manager
+            .create_table(
+                Table::create()
+                    .table(ItemsProgressHistory::Table)
+                    .col(pk_auto(ItemsProgressHistory::Id))
+                    .col(integer(ItemsProgressHistory::ItemId))
+                    .col(string(ItemsProgressHistory::SessionKey))
+                    .col(integer(ItemsProgressHistory::Reason))
+                    .col(big_integer(ItemsProgressHistory::Position))
+                    .col(big_integer(ItemsProgressHistory::PreviousPosition))
+                    .col(timestamp_with_time_zone(ItemsProgressHistory::DateModified))
+                    .foreign_key(
+                        ForeignKey::create()
+                            .from(ItemsProgressHistory::Table, ItemsProgressHistory::ItemId)
+                            .to(Items::Table, Items::Id),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum Items {
+    Table,
+    Id,
+}
+
+#[derive(DeriveIden)]
+enum ItemsProgressHistory {
+    Table,
+    Id,
+    ItemId,
+    SessionKey,
+    Reason,
+    Position,
+    PreviousPosition,
+    DateModified,
+}
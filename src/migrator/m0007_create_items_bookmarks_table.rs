@@ -0,0 +1,52 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(ItemsBookmarks::Table)
+                    .if_not_exists()
+                    .col(pk_auto(ItemsBookmarks::Id))
+                    .col(integer(ItemsBookmarks::ItemId))
+                    .col(big_integer(ItemsBookmarks::Position))
+                    .col(integer_null(ItemsBookmarks::ChapterIndex))
+                    .col(string_null(ItemsBookmarks::ChapterName))
+                    .col(string_null(ItemsBookmarks::Note))
+                    .col(timestamp_with_time_zone(ItemsBookmarks::DateCreated))
+                    .foreign_key(
+                        ForeignKey::create()
+                            .from(ItemsBookmarks::Table, ItemsBookmarks::ItemId)
+                            .to(Items::Table, Items::Id),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager.drop_table(Table::drop().table(ItemsBookmarks::Table).to_owned()).await
+    }
+}
+
+#[derive(DeriveIden)]
+enum Items {
+    Table,
+    Id,
+}
+
+#[derive(DeriveIden)]
+enum ItemsBookmarks {
+    Table,
+    Id,
+    ItemId,
+    Position,
+    ChapterIndex,
+    ChapterName,
+    Note,
+    DateCreated,
+}
@@ -0,0 +1,56 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(ItemsListeningSessions::Table)
+                    .if_not_exists()
+                    .col(pk_auto(ItemsListeningSessions::Id))
+                    .col(integer(ItemsListeningSessions::ItemId))
+                    .col(string(ItemsListeningSessions::SessionKey))
+                    .col(timestamp_with_time_zone(ItemsListeningSessions::DateStart))
+                    .col(timestamp_with_time_zone(ItemsListeningSessions::DateEnd))
+                    .col(big_integer(ItemsListeningSessions::StartPosition))
+                    .col(big_integer(ItemsListeningSessions::EndPosition))
+                    .col(big_integer(ItemsListeningSessions::Listened))
+                    .col(big_integer(ItemsListeningSessions::Skipped))
+                    .foreign_key(
+                        ForeignKey::create()
+                            .from(ItemsListeningSessions::Table, ItemsListeningSessions::ItemId)
+                            .to(Items::Table, Items::Id),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager.drop_table(Table::drop().table(ItemsListeningSessions::Table).to_owned()).await
+    }
+}
+
+#[derive(DeriveIden)]
+enum Items {
+    Table,
+    Id,
+}
+
+#[derive(DeriveIden)]
+enum ItemsListeningSessions {
+    Table,
+    Id,
+    ItemId,
+    SessionKey,
+    DateStart,
+    DateEnd,
+    StartPosition,
+    EndPosition,
+    Listened,
+    Skipped,
+}
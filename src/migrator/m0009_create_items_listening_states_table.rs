@@ -0,0 +1,49 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(ItemsListeningStates::Table)
+                    .if_not_exists()
+                    .col(pk_auto(ItemsListeningStates::Id))
+                    // at most one state per item, the progress is upserted
+                    .col(integer_uniq(ItemsListeningStates::ItemId))
+                    .col(integer(ItemsListeningStates::State))
+                    .col(big_integer(ItemsListeningStates::Position))
+                    .col(timestamp_with_time_zone(ItemsListeningStates::DateModified))
+                    .foreign_key(
+                        ForeignKey::create()
+                            .from(ItemsListeningStates::Table, ItemsListeningStates::ItemId)
+                            .to(Items::Table, Items::Id),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager.drop_table(Table::drop().table(ItemsListeningStates::Table).to_owned()).await
+    }
+}
+
+#[derive(DeriveIden)]
+enum Items {
+    Table,
+    Id,
+}
+
+#[derive(DeriveIden)]
+enum ItemsListeningStates {
+    Table,
+    Id,
+    ItemId,
+    State,
+    Position,
+    DateModified,
+}
@@ -0,0 +1,37 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+/// States chosen by the user (e.g. marking a book as finished) are not overwritten
+/// by later listening sessions
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(ItemsListeningStates::Table)
+                    .add_column(boolean(ItemsListeningStates::IsManual).default(false))
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(ItemsListeningStates::Table)
+                    .drop_column(ItemsListeningStates::IsManual)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum ItemsListeningStates {
+    Table,
+    IsManual,
+}
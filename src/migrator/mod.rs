@@ -1,10 +1,32 @@
 use sea_orm_migration::prelude::*;
 
+pub mod m0001_create_items_tables;
+pub mod m0002_create_settings_table;
+pub mod m0003_add_items_duration;
+pub mod m0004_add_items_library_root;
+pub mod m0005_add_items_is_missing;
+pub mod m0006_reshape_items_progress_history;
+pub mod m0007_create_items_bookmarks_table;
+pub mod m0008_create_items_listening_sessions_table;
+pub mod m0009_create_items_listening_states_table;
+pub mod m0010_add_items_listening_states_is_manual;
+
 pub struct Migrator;
 
 #[async_trait::async_trait]
 impl MigratorTrait for Migrator {
     fn migrations() -> Vec<Box<dyn MigrationTrait>> {
-        vec![]
+        vec![
+            Box::new(m0001_create_items_tables::Migration),
+            Box::new(m0002_create_settings_table::Migration),
+            Box::new(m0003_add_items_duration::Migration),
+            Box::new(m0004_add_items_library_root::Migration),
+            Box::new(m0005_add_items_is_missing::Migration),
+            Box::new(m0006_reshape_items_progress_history::Migration),
+            Box::new(m0007_create_items_bookmarks_table::Migration),
+            Box::new(m0008_create_items_listening_sessions_table::Migration),
+            Box::new(m0009_create_items_listening_states_table::Migration),
+            Box::new(m0010_add_items_listening_states_is_manual::Migration),
+        ]
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,80 @@
+use crate::player::auto_rewind_step::AutoRewindStep;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+/// Seeks back a little when playback is resumed after a pause, the longer
+/// the pause, the more context is repeated
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Serialize, Deserialize)]
+pub struct AutoRewind {
+    pub enabled: bool,
+    #[serde(default)]
+    pub steps: Vec<AutoRewindStep>,
+}
+
+impl AutoRewind {
+    pub fn defaults() -> Self {
+        Self {
+            enabled: true,
+            steps: vec![
+                AutoRewindStep::new(Duration::from_secs(60), Duration::from_secs(2)),
+                AutoRewindStep::new(Duration::from_secs(5 * 60), Duration::from_secs(5)),
+                AutoRewindStep::new(Duration::from_secs(60 * 60), Duration::from_secs(10)),
+                AutoRewindStep::new(Duration::from_secs(24 * 60 * 60), Duration::from_secs(30)),
+            ],
+        }
+    }
+
+    /// The rewind of the longest step that is covered by the pause
+    pub fn rewind_for(&self, pause: Duration) -> Duration {
+        if !self.enabled {
+            return Duration::ZERO;
+        }
+        self.steps
+            .iter()
+            .filter(|step| step.min_pause <= pause)
+            .max_by_key(|step| step.min_pause)
+            .map(|step| step.rewind)
+            .unwrap_or(Duration::ZERO)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SECOND: Duration = Duration::from_secs(1);
+    const MINUTE: Duration = Duration::from_secs(60);
+    const HOUR: Duration = Duration::from_secs(60 * 60);
+    const DAY: Duration = Duration::from_secs(24 * 60 * 60);
+
+    #[test]
+    fn rewind_for_the_default_thresholds() {
+        let auto_rewind = AutoRewind::defaults();
+        let cases = [
+            (Duration::ZERO, Duration::ZERO),
+            (MINUTE - SECOND, Duration::ZERO),
+            (MINUTE, 2 * SECOND),
+            (5 * MINUTE - SECOND, 2 * SECOND),
+            (5 * MINUTE, 5 * SECOND),
+            (HOUR - SECOND, 5 * SECOND),
+            (HOUR, 10 * SECOND),
+            (DAY - SECOND, 10 * SECOND),
+            (DAY, 30 * SECOND),
+            (30 * DAY, 30 * SECOND),
+        ];
+        for (pause, rewind) in cases {
+            assert_eq!(auto_rewind.rewind_for(pause), rewind, "pause of {:?}", pause);
+        }
+    }
+
+    #[test]
+    fn rewind_for_unordered_or_disabled_steps() {
+        let mut auto_rewind = AutoRewind::defaults();
+        auto_rewind.steps.reverse();
+        assert_eq!(auto_rewind.rewind_for(2 * HOUR), 10 * SECOND);
+
+        auto_rewind.enabled = false;
+        assert_eq!(auto_rewind.rewind_for(2 * DAY), Duration::ZERO);
+    }
+}
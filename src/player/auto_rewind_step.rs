@@ -0,0 +1,18 @@
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Serialize, Deserialize)]
+pub struct AutoRewindStep {
+    // the step applies to pauses of at least this length
+    #[serde(with = "crate::serde_json_mods::duration_millis")]
+    pub min_pause: Duration,
+    #[serde(with = "crate::serde_json_mods::duration_millis")]
+    pub rewind: Duration,
+}
+
+impl AutoRewindStep {
+    pub fn new(min_pause: Duration, rewind: Duration) -> Self {
+        Self { min_pause, rewind }
+    }
+}
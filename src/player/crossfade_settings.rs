@@ -0,0 +1,42 @@
+use crate::media_source::media_source_item::MediaSourceItem;
+use crate::media_source::media_type::MediaType;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+pub const CROSSFADE_MAX_DURATION: Duration = Duration::from_secs(12);
+
+#[derive(Debug, Clone, PartialEq)]
+#[derive(Serialize, Deserialize)]
+pub struct CrossfadeSettings {
+    // pre-decode the next queue item so that there is no gap between items
+    #[serde(default = "default_gapless")]
+    pub gapless: bool,
+    // zero disables cross fading
+    #[serde(with = "crate::serde_json_mods::duration_millis")]
+    pub duration: Duration,
+    // cross fading only happens between items of these types, others are played gapless
+    #[serde(default)]
+    pub media_types: Vec<MediaType>,
+}
+
+fn default_gapless() -> bool {
+    true
+}
+
+impl CrossfadeSettings {
+    pub fn defaults() -> Self {
+        Self {
+            gapless: true,
+            duration: Duration::ZERO,
+            media_types: vec![MediaType::Music],
+        }
+    }
+
+    pub fn duration_between(&self, current: &MediaSourceItem, next: &MediaSourceItem) -> Duration {
+        if self.media_types.contains(&current.media_type) && self.media_types.contains(&next.media_type) {
+            self.duration.min(CROSSFADE_MAX_DURATION)
+        } else {
+            Duration::ZERO
+        }
+    }
+}
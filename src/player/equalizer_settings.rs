@@ -0,0 +1,59 @@
+use crate::audio::equalizer_band::EqualizerBand;
+use crate::audio::equalizer_band_type::EqualizerBandType::{HighShelf, LowShelf, Peaking};
+use crate::audio::equalizer_preset::EqualizerPreset;
+use serde::{Deserialize, Serialize};
+
+pub const EQUALIZER_MAX_GAIN_DB: f32 = 12.0;
+
+#[derive(Debug, Clone, PartialEq)]
+#[derive(Serialize, Deserialize)]
+pub struct EqualizerSettings {
+    pub preset: EqualizerPreset,
+    // used when the preset is custom
+    #[serde(default = "EqualizerSettings::default_custom_bands")]
+    pub custom_bands: Vec<EqualizerBand>,
+}
+
+impl Default for EqualizerSettings {
+    fn default() -> Self {
+        Self {
+            preset: EqualizerPreset::Flat,
+            custom_bands: Self::default_custom_bands(),
+        }
+    }
+}
+
+impl EqualizerSettings {
+    /// five neutral bands, shelves at both ends
+    pub fn default_custom_bands() -> Vec<EqualizerBand> {
+        vec![
+            EqualizerBand::new(LowShelf, 60.0, 0.0, 0.707),
+            EqualizerBand::new(Peaking, 230.0, 0.0, 1.0),
+            EqualizerBand::new(Peaking, 910.0, 0.0, 1.0),
+            EqualizerBand::new(Peaking, 3600.0, 0.0, 1.0),
+            EqualizerBand::new(HighShelf, 14000.0, 0.0, 0.707),
+        ]
+    }
+
+    pub fn bands(&self) -> Vec<EqualizerBand> {
+        match self.preset {
+            EqualizerPreset::Custom => self
+                .custom_bands
+                .iter()
+                .map(|band| EqualizerBand {
+                    gain_db: band.gain_db.clamp(-EQUALIZER_MAX_GAIN_DB, EQUALIZER_MAX_GAIN_DB),
+                    ..band.clone()
+                })
+                .collect(),
+            preset => preset.bands(),
+        }
+    }
+
+    /// Switches to the custom preset and changes the gain of one band
+    pub fn set_custom_gain(&mut self, index: usize, gain_db: f32) {
+        if let Some(band) = self.custom_bands.get_mut(index) {
+            band.gain_db = gain_db.clamp(-EQUALIZER_MAX_GAIN_DB, EQUALIZER_MAX_GAIN_DB);
+            self.preset = EqualizerPreset::Custom;
+        }
+    }
+}
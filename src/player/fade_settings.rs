@@ -0,0 +1,24 @@
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+/// Length of the volume ramps, zero disables a ramp
+#[derive(Debug, Clone, PartialEq)]
+#[derive(Serialize, Deserialize)]
+pub struct FadeSettings {
+    #[serde(with = "crate::serde_json_mods::duration_millis")]
+    pub pause_resume: Duration,
+    #[serde(with = "crate::serde_json_mods::duration_millis")]
+    pub seek: Duration,
+    #[serde(with = "crate::serde_json_mods::duration_millis")]
+    pub item_switch: Duration,
+}
+
+impl FadeSettings {
+    pub fn defaults() -> Self {
+        Self {
+            pause_resume: Duration::from_millis(200),
+            seek: Duration::from_millis(40),
+            item_switch: Duration::from_millis(100),
+        }
+    }
+}
@@ -0,0 +1,13 @@
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+#[derive(Debug, Clone, PartialEq, Default)]
+#[derive(Serialize, Deserialize)]
+pub struct LoopOptions {
+    // silence between two repetitions, e.g. to repeat a sentence
+    #[serde(with = "crate::serde_json_mods::duration_millis")]
+    pub pause: Duration,
+    // the loop ends after this many repetitions and playback continues
+    #[serde(default)]
+    pub max_repetitions: Option<u32>,
+}
@@ -0,0 +1,9 @@
+use std::time::Duration;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum LoopTarget {
+    // start and end of an A-B loop
+    Range(Duration, Duration),
+    // follows the chapter that is currently played
+    CurrentChapter,
+}
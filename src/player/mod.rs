@@ -1,4 +1,23 @@
 pub mod player;
 pub mod player_command;
 pub mod trigger_action;
-pub mod player_event;
\ No newline at end of file
+pub mod player_event;
+pub mod player_error;
+pub mod player_state;
+pub mod player_position;
+pub mod auto_rewind;
+pub mod auto_rewind_step;
+pub mod repeat_mode;
+pub mod play_queue;
+pub mod replay_gain_mode;
+pub mod replay_gain_settings;
+pub mod voice_boost_settings;
+pub mod equalizer_settings;
+pub mod crossfade_settings;
+pub mod prepared_item;
+pub mod silence_skip_settings;
+pub mod loop_target;
+pub mod loop_options;
+pub mod section_loop;
+pub mod fade_settings;
+pub mod series_autoplay_settings;
@@ -0,0 +1,213 @@
+use serde::{Deserialize, Serialize};
+
+/// Item ids to be played one after another. The play order is either the
+/// natural order or a shuffle order derived from a seed, so the same seed
+/// always results in the same order (e.g. after a restart).
+#[derive(Debug, Clone, Default)]
+#[derive(Serialize, Deserialize)]
+pub struct PlayQueue {
+    item_ids: Vec<String>,
+    // indexes into item_ids in play order
+    order: Vec<usize>,
+    // position in order
+    position: usize,
+    shuffle_seed: Option<u64>,
+}
+
+impl PlayQueue {
+    pub fn new(item_ids: Vec<String>, start_index: usize) -> Self {
+        let order = (0..item_ids.len()).collect();
+        Self {
+            position: start_index.min(item_ids.len().saturating_sub(1)),
+            item_ids,
+            order,
+            shuffle_seed: None,
+        }
+    }
+
+    /// Rebuilds the play order if a restored queue is inconsistent (e.g. edited or
+    /// written by an older version), so that indexing into the items cannot fail
+    pub fn validated(mut self) -> Self {
+        let len = self.item_ids.len();
+        let mut seen = vec![false; len];
+        let is_permutation = self.order.len() == len
+            && self.order.iter().all(|i| *i < len && !std::mem::replace(&mut seen[*i], true));
+        if !is_permutation {
+            println!("restored queue order does not match its {} items, rebuilding it", len);
+            self.order = (0..len).collect();
+            if let Some(seed) = self.shuffle_seed {
+                Self::shuffle(&mut self.order, seed);
+            }
+        }
+        self.position = self.position.min(len.saturating_sub(1));
+        self
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.item_ids.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.item_ids.len()
+    }
+
+    pub fn is_shuffled(&self) -> bool {
+        self.shuffle_seed.is_some()
+    }
+
+    pub fn current(&self) -> Option<String> {
+        self.order.get(self.position).map(|i| self.item_ids[*i].clone())
+    }
+
+    /// Moves to the given item if it is part of the queue
+    pub fn select(&mut self, id: &str) -> bool {
+        match self.order.iter().position(|i| self.item_ids[*i] == id) {
+            Some(position) => {
+                self.position = position;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Moves to the next item, wraps around if `wrap` is set
+    pub fn next(&mut self, wrap: bool) -> Option<String> {
+        if self.position + 1 < self.order.len() {
+            self.position += 1;
+        } else if wrap && !self.order.is_empty() {
+            self.position = 0;
+        } else {
+            return None;
+        }
+        self.current()
+    }
+
+    /// The item `next` would move to, without moving
+    pub fn peek_next(&self, wrap: bool) -> Option<String> {
+        let position = if self.position + 1 < self.order.len() {
+            self.position + 1
+        } else if wrap && !self.order.is_empty() {
+            0
+        } else {
+            return None;
+        };
+        self.order.get(position).map(|i| self.item_ids[*i].clone())
+    }
+
+    /// Moves to the previous item, wraps around if `wrap` is set
+    pub fn previous(&mut self, wrap: bool) -> Option<String> {
+        if self.position > 0 {
+            self.position -= 1;
+        } else if wrap && !self.order.is_empty() {
+            self.position = self.order.len() - 1;
+        } else {
+            return None;
+        }
+        self.current()
+    }
+
+    /// Shuffles with the given seed (the current item is played first) or restores the natural order
+    pub fn set_shuffle(&mut self, shuffle_seed: Option<u64>) {
+        let current_index = self.order.get(self.position).copied();
+        self.shuffle_seed = shuffle_seed;
+        self.order = (0..self.item_ids.len()).collect();
+
+        if let Some(seed) = shuffle_seed {
+            Self::shuffle(&mut self.order, seed);
+            if let Some(index) = current_index
+                && let Some(pos) = self.order.iter().position(|i| *i == index)
+            {
+                self.order.remove(pos);
+                self.order.insert(0, index);
+            }
+            self.position = 0;
+        } else {
+            self.position = current_index.unwrap_or(0);
+        }
+    }
+
+    /// Fisher-Yates with splitmix64, independent of any platform random source
+    fn shuffle(order: &mut [usize], seed: u64) {
+        let mut state = seed;
+        for i in (1..order.len()).rev() {
+            state = state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+            let mut z = state;
+            z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+            z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+            z ^= z >> 31;
+            let j = (z % (i as u64 + 1)) as usize;
+            order.swap(i, j);
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ids(count: usize) -> Vec<String> {
+        (0..count).map(|i| i.to_string()).collect()
+    }
+
+    /// Item ids in play order, starting at the current item
+    fn play_order(queue: &mut PlayQueue) -> Vec<String> {
+        let mut order: Vec<String> = queue.current().into_iter().collect();
+        while let Some(id) = queue.next(false) {
+            order.push(id);
+        }
+        order
+    }
+
+    #[test]
+    fn same_seed_gives_the_same_order() {
+        let mut first = PlayQueue::new(ids(20), 0);
+        first.set_shuffle(Some(42));
+        let mut second = PlayQueue::new(ids(20), 0);
+        second.set_shuffle(Some(42));
+        let mut other = PlayQueue::new(ids(20), 0);
+        other.set_shuffle(Some(43));
+
+        let order = play_order(&mut first);
+        assert_eq!(order, play_order(&mut second));
+        assert_ne!(order, play_order(&mut other));
+        assert_ne!(order, ids(20));
+        let mut sorted = order.clone();
+        sorted.sort_by_key(|id| id.parse::<usize>().unwrap());
+        assert_eq!(sorted, ids(20));
+    }
+
+    #[test]
+    fn shuffled_order_survives_a_restart() {
+        let mut queue = PlayQueue::new(ids(20), 5);
+        queue.set_shuffle(Some(7));
+        queue.next(false);
+        queue.next(false);
+
+        let json = serde_json::to_string(&queue).unwrap();
+        let mut restored = serde_json::from_str::<PlayQueue>(&json).unwrap().validated();
+        assert_eq!(restored.current(), queue.current());
+        assert_eq!(play_order(&mut restored), play_order(&mut queue));
+    }
+
+    #[test]
+    fn mismatched_order_is_rebuilt() {
+        // the order refers to an item that is no longer part of the queue
+        let json = r#"{"item_ids":["a","b","c"],"order":[0,1,5],"position":2,"shuffle_seed":null}"#;
+        let mut queue = serde_json::from_str::<PlayQueue>(json).unwrap().validated();
+        assert_eq!(queue.current().as_deref(), Some("c"));
+        assert_eq!(queue.previous(false).as_deref(), Some("b"));
+
+        // too short, duplicate indexes and a position behind the end, the
+        // shuffle order is derived from the seed again
+        for order in ["[0]", "[0,0,1]"] {
+            let json = format!(r#"{{"item_ids":["a","b","c"],"order":{},"position":7,"shuffle_seed":3}}"#, order);
+            let queue = serde_json::from_str::<PlayQueue>(&json).unwrap().validated();
+            let mut expected = vec![0, 1, 2];
+            PlayQueue::shuffle(&mut expected, 3);
+            assert_eq!(queue.order, expected);
+            assert_eq!(queue.position, 2);
+            assert!(queue.current().is_some());
+        }
+    }
+}
@@ -2,60 +2,184 @@
 // https://github.com/tsirysndr/music-player/blob/master/playback/src/audio_backend/rodio.rs
 // load multiple sources with rodio: https://stackoverflow.com/questions/75505017/how-can-i-make-rust-with-the-rodio-crate-load-multiple-sources-in-a-vec-so-i
 
-use crate::media_source::media_source::{MediaSource, MediaSourceItem};
-use cpal::traits::{DeviceTrait, HostTrait};
-use cpal::Device;
-use rodio::source::SeekError;
-use rodio::{OutputStream, OutputStreamBuilder, Sink, Source};
+use crate::media_source::media_source::{MediaSource, MediaSourceBookmark, MediaSourceItem, MediaSourceListeningSession, MediaSourceListeningState, MediaSourceProgressReason};
+use crate::audio::output_device_provider::{OutputConnection, OutputDeviceProvider};
+use crate::player::auto_rewind::AutoRewind;
+use crate::player::play_queue::PlayQueue;
+use crate::player::repeat_mode::RepeatMode;
+use crate::player::replay_gain_settings::ReplayGainSettings;
+use crate::player::voice_boost_settings::VoiceBoostSettings;
+use crate::audio::voice_boost_source::{VoiceBoostControl, VoiceBoostSource};
+use crate::audio::equalizer_preset::EqualizerPreset;
+use crate::audio::equalizer_source::{EqualizerControl, EqualizerSource};
+use crate::player::equalizer_settings::EqualizerSettings;
+use crate::audio::crossfade_source::CrossfadeSource;
+use crate::audio::item_transition::ItemTransition;
+use crate::player::crossfade_settings::CrossfadeSettings;
+use crate::player::prepared_item::PreparedItem;
+use crate::audio::silence_skip_source::{SilenceSkipControl, SilenceSkipSource, SkippedTime};
+use crate::player::silence_skip_settings::SilenceSkipSettings;
+use crate::player::loop_options::LoopOptions;
+use crate::player::section_loop::SectionLoop;
+use crate::audio::fade_source::{FadeControl, FadeSource};
+use crate::player::fade_settings::FadeSettings;
+use crate::player::series_autoplay_settings::SeriesAutoplaySettings;
+use crate::media_source::utils::natural_cmp;
+use crate::media_source::media_type::MediaType;
+use crate::settings::settings_manager::SettingsManager;
+use crate::player::player_error::PlayerError;
+use rodio::{Sink, Source};
 use std::cmp::max;
 use std::fs::File;
-use std::io;
 use std::path::Path;
 use std::sync::Arc;
 use std::time::{Duration, SystemTime};
 use mpsc::UnboundedReceiver;
 use tokio::sync::mpsc;
 use tokio::sync::mpsc::UnboundedSender;
-use tokio::time::sleep;
+use tokio::time::{sleep, Instant};
 use crate::media_source::media_source_chapter::MediaSourceChapter;
-pub(crate) use crate::player::player_command::PlayerCommand;
-pub(crate) use crate::player::player_event::PlayerEvent;
+pub use crate::player::player_command::PlayerCommand;
+pub use crate::player::player_event::PlayerEvent;
+pub use crate::player::player_state::PlayerState;
+use crate::player::player_position::PlayerPosition;
+
+mod queue;
+mod section_loops;
+mod sessions;
+mod transition;
+
+pub const SETTINGS_KEY_OUTPUT_DEVICE: &str = "player.output_device";
+pub const SETTINGS_KEY_AUTO_REWIND: &str = "player.auto_rewind";
+pub const SETTINGS_KEY_REPEAT_MODE: &str = "player.repeat_mode";
+pub const SETTINGS_KEY_QUEUE: &str = "player.queue";
+pub const SETTINGS_KEY_REPLAY_GAIN: &str = "player.replay_gain";
+pub const SETTINGS_KEY_VOICE_BOOST: &str = "player.voice_boost";
+pub const SETTINGS_KEY_EQUALIZER: &str = "player.equalizer";
+pub const SETTINGS_KEY_CROSSFADE: &str = "player.crossfade";
+pub const SETTINGS_KEY_SILENCE_SKIP: &str = "player.silence_skip";
+// total time saved by skipping silence in milliseconds
+pub const SETTINGS_KEY_SILENCE_SKIP_TIME_SAVED: &str = "player.silence_skip_time_saved";
+pub const SETTINGS_KEY_LOOP_OPTIONS: &str = "player.loop_options";
+pub const SETTINGS_KEY_FADES: &str = "player.fades";
+pub const SETTINGS_KEY_SERIES_AUTOPLAY: &str = "player.series_autoplay";
+
+// the next queue item is decoded this long (plus the cross fade) before the current one ends
+const NEXT_ITEM_PRELOAD: Duration = Duration::from_secs(10);
+// position updates while the player is not visible, e.g. for the loops and the progress
+const BACKGROUND_TICK_INTERVAL: Duration = Duration::from_secs(5);
+// the item is longer than its metadata says, wait for the sink to run empty
+const OVERRUN_TICK_INTERVAL: Duration = Duration::from_millis(250);
+const DEVICE_CHECK_INTERVAL: Duration = Duration::from_secs(2);
+const PREFERRED_DEVICE_CHECK_INTERVAL: Duration = Duration::from_secs(10);
+// longest wait for a fade out beyond its duration, e.g. when the output is slow
+const FADE_SETTLE_TIMEOUT: Duration = Duration::from_millis(100);
 
 pub struct Player {
     media_source: Arc<dyn MediaSource>,
+    settings: SettingsManager,
+    device_provider: Box<dyn OutputDeviceProvider>,
     preferred_device_name: String,
     fallback_device_name: String,
-    stream: Option<OutputStream>, // when removed, the samples do not play
+    output: Option<OutputConnection>, // when removed, the samples do not play
     sink: Option<Sink>,
+    // position to restore after the output device was lost
+    saved_position: Option<Duration>,
+    auto_rewind: AutoRewind,
+    paused_at: Option<SystemTime>,
+    // identifies the progress history entries of one listening session
+    session_key: String,
+    // continuous playback of the current item, ends on pause, stop or item change
+    session: Option<MediaSourceListeningSession>,
+    // ended sessions with the resulting listening state of their item, waiting to be stored
+    ended_sessions: Vec<(MediaSourceListeningSession, MediaSourceListeningState)>,
+    progress_persisted_at: SystemTime,
+    queue: PlayQueue,
+    repeat_mode: RepeatMode,
+    replay_gain: ReplayGainSettings,
+    voice_boost: VoiceBoostSettings,
+    voice_boost_control: Arc<VoiceBoostControl>,
+    series_autoplay: SeriesAutoplaySettings,
+    equalizer: EqualizerSettings,
+    equalizer_control: Arc<EqualizerControl>,
+    crossfade: CrossfadeSettings,
+    // hands the next item over to the sink entry queued behind the current item
+    transition: Arc<ItemTransition>,
+    next_item: Option<PreparedItem>,
+    // preparing is attempted once per item
+    next_item_attempted: bool,
+    // the sink position of an item starts after the part played while cross fading
+    position_offset: Duration,
+    silence_skip: SilenceSkipSettings,
+    silence_skip_control: Arc<SilenceSkipControl>,
+    // silence skipped in the current item, added to the sink position
+    skipped_time: Arc<SkippedTime>,
+    silence_time_saved: Duration,
+    silence_time_saved_persisted: Duration,
+    section_loop: Option<SectionLoop>,
+    loop_options: LoopOptions,
+    // end of the pause between two loop repetitions
+    loop_resume_at: Option<SystemTime>,
+    fades: FadeSettings,
+    fade_control: Arc<FadeControl>,
+    // position updates are only needed frequently while the player is shown
+    ui_visible: bool,
     item: Option<MediaSourceItem>,
+    state: PlayerState,
+    chapter_index: Option<usize>,
 }
 
 impl Player {
-    // sink:Option<Sink>, stream: Option<OutputStream>
     pub fn new(
         media_source: Arc<dyn MediaSource>,
+        settings: SettingsManager,
+        device_provider: Box<dyn OutputDeviceProvider>,
         preferred_device_name: String,
         fallback_device_name: String,
     ) -> Player {
         Self {
             media_source,
+            settings,
+            device_provider,
             preferred_device_name,
             fallback_device_name,
-            stream: None,
+            output: None,
             sink: None,
+            saved_position: None,
+            auto_rewind: AutoRewind::defaults(),
+            paused_at: None,
+            session_key: String::new(),
+            session: None,
+            ended_sessions: vec![],
+            progress_persisted_at: SystemTime::now(),
+            queue: PlayQueue::default(),
+            repeat_mode: RepeatMode::Off,
+            replay_gain: ReplayGainSettings::defaults(),
+            voice_boost: VoiceBoostSettings::default(),
+            voice_boost_control: Arc::new(VoiceBoostControl::default()),
+            series_autoplay: SeriesAutoplaySettings::defaults(),
+            equalizer: EqualizerSettings::default(),
+            equalizer_control: Arc::new(EqualizerControl::default()),
+            crossfade: CrossfadeSettings::defaults(),
+            transition: Arc::new(ItemTransition::default()),
+            next_item: None,
+            next_item_attempted: false,
+            position_offset: Duration::ZERO,
+            silence_skip: SilenceSkipSettings::defaults(),
+            silence_skip_control: Arc::new(SilenceSkipControl::default()),
+            skipped_time: Arc::new(SkippedTime::default()),
+            silence_time_saved: Duration::ZERO,
+            silence_time_saved_persisted: Duration::ZERO,
+            section_loop: None,
+            loop_options: LoopOptions::default(),
+            loop_resume_at: None,
+            fades: FadeSettings::defaults(),
+            fade_control: Arc::new(FadeControl::default()),
+            // the app starts on the home screen
+            ui_visible: false,
             item: None,
-        }
-    }
-
-    pub fn connect_sink(&mut self) {
-        let builder_option = Self::create_device_output_builder(
-            self.preferred_device_name.clone(),
-            self.fallback_device_name.clone(),
-        );
-        if let Some(builder) = builder_option {
-            let stream = builder.open_stream_or_fallback().unwrap();
-            self.sink = Some(Sink::connect_new(stream.mixer()));
-            self.stream = Some(stream);
+            state: PlayerState::Idle,
+            chapter_index: None,
         }
     }
 
@@ -64,49 +188,124 @@ impl Player {
         Duration::from_secs(3)
     }
 
-    //                     let match_string = "USB-C to 3.5mm Headphone Jack A";
-    //                     let match_string2 = "pipewire";
-    fn create_device_output_builder(
-        preferred_name: String,
-        fallback_name: String,
-    ) -> Option<OutputStreamBuilder> {
-        let host = cpal::default_host();
-        let devices = host.output_devices().unwrap();
-
-        let device: Option<Device> = {
-            let mut preferred_dev: Option<cpal::Device> = None;
-            let mut fallback_dev: Option<cpal::Device> = None;
-            let mut first_dev: Option<cpal::Device> = None;
-            for d in devices {
-                // println!("====={}", d.name().unwrap().to_string());
-                if d.name().unwrap() == preferred_name {
-                    preferred_dev = Some(d);
-                    break;
-                } else if d.name().unwrap() == fallback_name {
-                    fallback_dev = Some(d);
-                } else if first_dev.is_none() {
-                    first_dev = Some(d)
-                }
-            }
+    /// preferred device first, then the fallback, then whatever is available
+    fn select_device_name(&self, available: &[String]) -> Option<String> {
+        if available.contains(&self.preferred_device_name) {
+            Some(self.preferred_device_name.clone())
+        } else if available.contains(&self.fallback_device_name) {
+            Some(self.fallback_device_name.clone())
+        } else {
+            available.first().cloned()
+        }
+    }
 
-            if preferred_dev.is_some() {
-                preferred_dev
-            } else if fallback_dev.is_some() {
-                fallback_dev
-            } else {
-                first_dev
-            }
+    /// Detects lost devices and (re-)connects as soon as a matching device is available
+    fn check_output_device(&mut self, evt_tx: &UnboundedSender<PlayerEvent>) {
+        let available = self.device_provider.available_devices();
+
+        if let Some(output) = &self.output
+            && (output.is_lost() || !available.contains(&output.device_name))
+        {
+            self.handle_output_lost(evt_tx);
+        }
+
+        let Some(device_name) = self.select_device_name(&available) else {
+            return;
+        };
+        let should_connect = match &self.output {
+            None => true,
+            // switch back as soon as the preferred device reappears
+            Some(output) => output.device_name != device_name && device_name == self.preferred_device_name,
         };
+        if should_connect {
+            self.connect_output(evt_tx, &device_name);
+        }
+    }
 
-        let builder: Option<OutputStreamBuilder> = if device.is_some() {
-            let selected_device = device.unwrap();
-            let builder_result = OutputStreamBuilder::from_device(selected_device);
-            Some(builder_result.unwrap())
-        } else {
-            None
+    fn handle_output_lost(&mut self, evt_tx: &UnboundedSender<PlayerEvent>) {
+        if let Some(output) = &self.output {
+            println!("audio output lost: {}", output.device_name);
+        }
+        if let Some(sink) = self.sink.take()
+            && self.item.is_some()
+        {
+            self.saved_position = Some(sink.get_pos() + self.position_offset + self.skipped_time.get());
+        }
+        self.output = None;
+        if self.state.is_playing() {
+            self.set_state(evt_tx, PlayerState::Paused);
+        }
+        let _ = evt_tx.send(PlayerEvent::OutputDeviceChanged(None));
+    }
+
+    fn connect_output(&mut self, evt_tx: &UnboundedSender<PlayerEvent>, device_name: &str) {
+        let Some((sink, output)) = self.device_provider.connect(device_name) else {
+            return;
         };
 
-        builder
+        // switching between two working devices keeps playing, a lost device stays paused
+        let resume = self.state.is_playing();
+        if let Some(previous_sink) = self.sink.take()
+            && self.item.is_some()
+        {
+            self.saved_position = Some(previous_sink.get_pos() + self.position_offset + self.skipped_time.get());
+        }
+
+        println!("audio output connected: {}", output.device_name);
+        self.sink = Some(sink);
+        self.output = Some(output);
+        let _ = evt_tx.send(PlayerEvent::OutputDeviceChanged(Some(device_name.to_string())));
+
+        if let Some(position) = self.saved_position.take() {
+            self.restore_item(evt_tx, position, resume);
+        }
+    }
+
+    /// Switches to the given device right away and remembers it as preferred device
+    /// once it is connected
+    async fn select_output(&mut self, evt_tx: &UnboundedSender<PlayerEvent>, device_name: String) {
+        if self.output.as_ref().is_none_or(|o| o.device_name != device_name) {
+            self.connect_output(evt_tx, &device_name);
+        }
+        if self.output.as_ref().is_none_or(|o| o.device_name != device_name) {
+            println!("could not select output device: {}", device_name);
+            return;
+        }
+        self.preferred_device_name = device_name.clone();
+        if let Err(e) = self.settings.set(SETTINGS_KEY_OUTPUT_DEVICE, &device_name).await {
+            println!("could not persist output device: {}", e);
+        }
+    }
+
+    /// Re-appends the current item to a newly connected sink at the saved position
+    fn restore_item(&mut self, evt_tx: &UnboundedSender<PlayerEvent>, position: Duration, resume: bool) {
+        let Some(item) = self.item.clone() else {
+            return;
+        };
+        let decoder = match Self::open_decoder(&item) {
+            Ok(decoder) => decoder,
+            Err(e) => {
+                self.fail_item(evt_tx);
+                self.report_error(evt_tx, e);
+                return;
+            }
+        };
+        self.reset_transition();
+        let Some(sink) = &self.sink else {
+            return;
+        };
+        sink.pause();
+        let chain = self.processing_chain(decoder, self.skipped_time.clone());
+        sink.append(CrossfadeSource::new(chain, self.transition.clone(), self.item_duration()));
+        self.apply_replay_gain();
+        if let Err(e) = self.try_seek(position) {
+            self.report_error(evt_tx, e);
+        }
+        if resume {
+            self.resume_sink();
+        } else {
+            self.set_state(evt_tx, PlayerState::Paused);
+        }
     }
 
     async fn play_test(&mut self) {
@@ -124,75 +323,598 @@ impl Player {
             }
         }
     }
-    async fn play_media(&mut self, id: String) -> io::Result<()> {
+    /// The only place where the state changes, every transition is emitted as event
+    fn set_state(&mut self, evt_tx: &UnboundedSender<PlayerEvent>, state: PlayerState) {
+        if !self.state.can_transition_to(state) {
+            return;
+        }
+        self.state = state;
+        match state {
+            // keep the start of the pause when seeking while paused
+            PlayerState::Paused => {
+                if self.paused_at.is_none() {
+                    self.paused_at = Some(SystemTime::now());
+                }
+            }
+            PlayerState::Buffering => {}
+            _ => self.paused_at = None,
+        }
+        // buffering while seeking continues the session
+        if state == PlayerState::Playing {
+            self.start_session();
+        } else if !state.is_playing() {
+            self.end_session(self.position());
+        }
+        let item_id = self.item.as_ref().map(|i| i.id.to_string()).unwrap_or_default();
+        let _ = evt_tx.send(PlayerEvent::State(item_id, state));
+    }
+
+    async fn play_media(&mut self, evt_tx: &UnboundedSender<PlayerEvent>, id: String) {
         let self_item = self.item.clone();
 
         if let Some(i) = self_item
             && id == i.id
         {
-            self.toggle();
-            return Ok(());
+            self.toggle(evt_tx).await;
+            return;
+        }
+
+        // playing an item outside of the queue replaces the queue
+        if !self.queue.select(&id) {
+            self.queue = PlayQueue::new(vec![id.clone()], 0);
+        }
+        self.persist_queue().await;
+        self.load_media(evt_tx, id).await
+    }
+
+    /// Loads the given item, items that cannot be played are reported and skipped
+    /// until a playable one is found or every queue item failed once
+    async fn load_media(&mut self, evt_tx: &UnboundedSender<PlayerEvent>, mut id: String) {
+        let mut failures = 0;
+        loop {
+            let Err(e) = self.try_load_media(evt_tx, id).await else {
+                return;
+            };
+            self.report_error(evt_tx, e);
+            failures += 1;
+            if failures >= self.queue.len() {
+                return;
+            }
+            let Some(next_id) = self.queue.next(self.repeat_mode == RepeatMode::All) else {
+                return;
+            };
+            self.persist_queue().await;
+            id = next_id;
         }
+    }
 
+    async fn try_load_media(&mut self, evt_tx: &UnboundedSender<PlayerEvent>, id: String) -> Result<(), PlayerError> {
+        self.fade_out(self.fades.item_switch).await;
+        self.set_state(evt_tx, PlayerState::Loading);
         self.item = self.media_source.find(&id).await;
+        self.chapter_index = None;
+        self.saved_position = None;
+        self.session_key = Self::create_session_key(&id);
+        self.clear_loop(evt_tx);
         if self.item.is_none() {
-            return Ok(());
+            self.fail_item(evt_tx);
+            return Err(PlayerError::NotFound(id));
         }
-        let self_item = self.item.clone();
-        let item = self_item.unwrap();
-        let path = Path::new(item.location.as_str());
-        let file = File::open(path)?;
+        self.apply_voice_boost(evt_tx);
+        self.apply_silence_skip(evt_tx);
+        self.send_series_autoplay(evt_tx);
+        self.start_item(evt_tx)
+    }
+
+    /// Continues with the part following the current item in its series (ordered naturally
+    /// by part), returns false if there is none or autoplay is disabled for the series
+    async fn play_next_in_series(&mut self, evt_tx: &UnboundedSender<PlayerEvent>) -> bool {
+        let Some(item) = &self.item else {
+            return false;
+        };
+        let Some(series) = item.metadata.series.clone().filter(|s| !s.trim().is_empty()) else {
+            return false;
+        };
+        if !self.series_autoplay.is_enabled_for(&series) {
+            return false;
+        }
+        let item_id = item.id.clone();
 
+        let mut parts: Vec<MediaSourceItem> = self
+            .media_source
+            .series_items(&series)
+            .await
+            .into_iter()
+            .filter(|i| i.metadata.part.is_some())
+            .collect();
+        parts.sort_by(|a, b| {
+            natural_cmp(
+                a.metadata.part.as_deref().unwrap_or_default(),
+                b.metadata.part.as_deref().unwrap_or_default(),
+            )
+        });
+        let Some(index) = parts.iter().position(|i| i.id == item_id) else {
+            return false;
+        };
+        let Some(next) = parts.get(index + 1) else {
+            return false;
+        };
+
+        println!("continuing series {} with {}", series, next.title);
+        let next_id = next.id.clone();
+        self.queue = PlayQueue::new(vec![next_id.clone()], 0);
+        self.persist_queue().await;
+        self.load_media(evt_tx, next_id).await;
+        true
+    }
+
+    fn current_series(&self) -> Option<String> {
+        self.item
+            .as_ref()
+            .and_then(|item| item.metadata.series.clone())
+            .filter(|series| !series.trim().is_empty())
+    }
+
+    fn send_series_autoplay(&self, evt_tx: &UnboundedSender<PlayerEvent>) {
+        let enabled = self
+            .current_series()
+            .is_some_and(|series| self.series_autoplay.is_enabled_for(&series));
+        let _ = evt_tx.send(PlayerEvent::SeriesAutoplayChanged(self.series_autoplay.enabled, enabled));
+    }
+
+    /// Enables or disables autoplay for the series of the current item
+    async fn set_series_autoplay(&mut self, evt_tx: &UnboundedSender<PlayerEvent>, enabled: bool) {
+        let Some(series) = self.current_series() else {
+            return;
+        };
+        self.series_autoplay.set_for_series(&series, enabled);
+        self.persist_series_autoplay().await;
+        self.send_series_autoplay(evt_tx);
+    }
+
+    /// Enables or disables autoplay for series without their own setting
+    async fn set_series_autoplay_default(&mut self, evt_tx: &UnboundedSender<PlayerEvent>, enabled: bool) {
+        self.series_autoplay.enabled = enabled;
+        self.persist_series_autoplay().await;
+        self.send_series_autoplay(evt_tx);
+    }
+
+    async fn persist_series_autoplay(&self) {
+        if let Err(e) = self.settings.set(SETTINGS_KEY_SERIES_AUTOPLAY, &self.series_autoplay).await {
+            println!("could not persist series autoplay settings: {}", e);
+        }
+    }
+
+    fn restart_item(&mut self, evt_tx: &UnboundedSender<PlayerEvent>) {
+        if let Err(e) = self.start_item(evt_tx) {
+            self.report_error(evt_tx, e);
+        }
+    }
+
+    /// Failures are logged and shown to the user
+    fn report_error(&self, evt_tx: &UnboundedSender<PlayerEvent>, error: PlayerError) {
+        println!("player error: {:?}", error);
+        let _ = evt_tx.send(PlayerEvent::Error(error));
+    }
+
+    /// (Re-)starts the current item from the beginning
+    fn start_item(&mut self, evt_tx: &UnboundedSender<PlayerEvent>) -> Result<(), PlayerError> {
+        let Some(item) = self.item.clone() else {
+            return Ok(());
+        };
+        let decoder = match Self::open_decoder(&item) {
+            Ok(decoder) => decoder,
+            Err(e) => {
+                self.fail_item(evt_tx);
+                return Err(e);
+            }
+        };
+
+        self.reset_transition();
+        // the new item starts silent and fades in
+        if !self.fades.item_switch.is_zero() {
+            self.fade_control.set_gain(0.0);
+        }
         if let Some(sink) = &self.sink {
             sink.clear();
-            sink.append(rodio::Decoder::try_from(file).unwrap());
+            let chain = self.processing_chain(decoder, self.skipped_time.clone());
+            sink.append(CrossfadeSource::new(chain, self.transition.clone(), self.item_duration()));
+            self.apply_replay_gain();
             sink.play();
+            self.fade_control.fade_to(1.0, self.fades.item_switch);
+            self.set_state(evt_tx, PlayerState::Playing);
+        } else {
+            // starts from the beginning as soon as an output is connected
+            self.saved_position = Some(Duration::ZERO);
+            self.set_state(evt_tx, PlayerState::Paused);
         }
         Ok(())
     }
 
-    fn toggle(&self) {
+    /// Enters the error state and drops the item, the previous audio must not
+    /// continue on the next play
+    fn fail_item(&mut self, evt_tx: &UnboundedSender<PlayerEvent>) {
+        self.set_state(evt_tx, PlayerState::Error);
         if let Some(sink) = &self.sink {
-            if sink.is_paused() {
-                sink.play()
-            } else {
-                sink.pause()
-            }
+            sink.clear();
         }
+        self.reset_transition();
+        self.item = None;
+        self.chapter_index = None;
+        self.saved_position = None;
     }
 
-    fn play(&self) {
-        if let Some(sink) = &self.sink {
-            sink.play();
+    fn apply_replay_gain(&self) {
+        let (Some(sink), Some(item)) = (&self.sink, &self.item) else {
+            return;
+        };
+        sink.set_volume(self.replay_gain.volume_for(item.metadata.replay_gain.as_ref()));
+    }
+
+    fn open_decoder(item: &MediaSourceItem) -> Result<impl Source + Send + 'static, PlayerError> {
+        let file = File::open(Path::new(item.location.as_str())).map_err(|e| PlayerError::Open(item.title.clone(), e))?;
+        rodio::Decoder::try_from(file).map_err(|e| PlayerError::Decode(item.title.clone(), e))
+    }
+
+    /// Silence skipping, equalizer, voice boost and fade stages applied to every decoded item
+    fn processing_chain<S: Source>(
+        &self,
+        source: S,
+        skipped_time: Arc<SkippedTime>,
+    ) -> FadeSource<VoiceBoostSource<EqualizerSource<SilenceSkipSource<S>>>> {
+        let shortened = SilenceSkipSource::new(source, self.silence_skip_control.clone(), skipped_time);
+        let equalized = EqualizerSource::new(shortened, self.equalizer_control.clone());
+        let boosted = VoiceBoostSource::new(equalized, self.voice_boost_control.clone());
+        FadeSource::new(boosted, self.fade_control.clone())
+    }
+
+    /// Whether samples are currently reaching the output
+    fn is_audible(&self) -> bool {
+        self.state.is_playing() && self.sink.as_ref().is_some_and(|sink| !sink.is_paused() && !sink.empty())
+    }
+
+    /// Ramps down and waits until the sources reached silence, so the sink can be paused,
+    /// seeked or cleared without a click
+    async fn fade_out(&self, duration: Duration) {
+        if duration.is_zero() || !self.is_audible() {
+            return;
+        }
+        let generation = self.fade_control.fade_to(0.0, duration);
+        let deadline = SystemTime::now() + duration + FADE_SETTLE_TIMEOUT;
+        sleep(duration).await;
+        while !self.fade_control.is_settled(generation) && SystemTime::now() < deadline {
+            sleep(Duration::from_millis(5)).await;
+        }
+    }
+
+    /// Plays the sink again, fading in from silence
+    fn resume_sink(&self) {
+        let Some(sink) = &self.sink else {
+            return;
+        };
+        if !self.fades.pause_resume.is_zero() {
+            self.fade_control.set_gain(0.0);
+        }
+        sink.play();
+        self.fade_control.fade_to(1.0, self.fades.pause_resume);
+    }
+
+    async fn update_fades(&mut self, fades: FadeSettings) {
+        if let Err(e) = self.settings.set(SETTINGS_KEY_FADES, &fades).await {
+            println!("could not persist fade settings: {}", e);
+        }
+        self.fades = fades;
+    }
+
+    fn apply_silence_skip(&self, evt_tx: &UnboundedSender<PlayerEvent>) {
+        let active = self
+            .item
+            .as_ref()
+            .is_some_and(|item| self.silence_skip.is_active_for(item));
+        self.silence_skip_control.set_enabled(active);
+        let _ = evt_tx.send(PlayerEvent::SilenceSkipChanged(self.silence_skip.enabled));
+    }
+
+    async fn update_silence_skip(&mut self, evt_tx: &UnboundedSender<PlayerEvent>, silence_skip: SilenceSkipSettings) {
+        self.silence_skip = silence_skip;
+        self.silence_skip_control
+            .configure(self.silence_skip.threshold_db, self.silence_skip.min_silence);
+        if let Err(e) = self.settings.set(SETTINGS_KEY_SILENCE_SKIP, &self.silence_skip).await {
+            println!("could not persist silence skip settings: {}", e);
+        }
+        self.apply_silence_skip(evt_tx);
+    }
+
+    /// Adds the time saved since the last tick to the statistic, persisted every few seconds
+    async fn collect_silence_time_saved(&mut self, evt_tx: &UnboundedSender<PlayerEvent>) {
+        let saved = self.silence_skip_control.take_saved();
+        if saved.is_zero() {
+            return;
+        }
+        if let Some(session) = &mut self.session {
+            session.skipped += saved;
+        }
+        self.silence_time_saved += saved;
+        let _ = evt_tx.send(PlayerEvent::SilenceTimeSaved(self.silence_time_saved));
+        if self.silence_time_saved - self.silence_time_saved_persisted < Duration::from_secs(10) {
+            return;
+        }
+        let millis = self.silence_time_saved.as_millis() as u64;
+        match self.settings.set(SETTINGS_KEY_SILENCE_SKIP_TIME_SAVED, &millis).await {
+            Ok(_) => self.silence_time_saved_persisted = self.silence_time_saved,
+            Err(e) => println!("could not persist time saved by skipping silence: {}", e),
+        }
+    }
+
+    async fn select_equalizer_preset(&mut self, evt_tx: &UnboundedSender<PlayerEvent>, preset: EqualizerPreset) {
+        self.equalizer.preset = preset;
+        self.apply_equalizer(evt_tx).await;
+    }
+
+    async fn set_equalizer_gain(&mut self, evt_tx: &UnboundedSender<PlayerEvent>, band_index: usize, gain_db: f32) {
+        self.equalizer.set_custom_gain(band_index, gain_db);
+        self.apply_equalizer(evt_tx).await;
+    }
+
+    async fn apply_equalizer(&mut self, evt_tx: &UnboundedSender<PlayerEvent>) {
+        self.equalizer_control.set_bands(self.equalizer.bands());
+        let _ = evt_tx.send(PlayerEvent::EqualizerChanged(self.equalizer.clone()));
+        if let Err(e) = self.settings.set(SETTINGS_KEY_EQUALIZER, &self.equalizer).await {
+            println!("could not persist equalizer settings: {}", e);
+        }
+    }
+
+    fn apply_voice_boost(&self, evt_tx: &UnboundedSender<PlayerEvent>) {
+        let enabled = self
+            .item
+            .as_ref()
+            .is_some_and(|item| self.voice_boost.is_enabled_for(item));
+        self.voice_boost_control.set_enabled(enabled);
+        let _ = evt_tx.send(PlayerEvent::VoiceBoostChanged(enabled));
+    }
+
+    /// Enables or disables voice boost for the current item
+    async fn set_voice_boost(&mut self, evt_tx: &UnboundedSender<PlayerEvent>, enabled: bool) {
+        let Some(item) = &self.item else {
+            return;
+        };
+        self.voice_boost.set_for_item(&item.id, enabled);
+        self.persist_voice_boost().await;
+        self.apply_voice_boost(evt_tx);
+    }
+
+    async fn set_voice_boost_for_media_type(&mut self, evt_tx: &UnboundedSender<PlayerEvent>, media_type: MediaType, enabled: bool) {
+        self.voice_boost.set_for_media_type(media_type, enabled);
+        self.persist_voice_boost().await;
+        self.apply_voice_boost(evt_tx);
+    }
+
+    async fn persist_voice_boost(&self) {
+        if let Err(e) = self.settings.set(SETTINGS_KEY_VOICE_BOOST, &self.voice_boost).await {
+            println!("could not persist voice boost settings: {}", e);
+        }
+    }
+
+    async fn update_replay_gain(&mut self, replay_gain: ReplayGainSettings) {
+        if let Err(e) = self.settings.set(SETTINGS_KEY_REPLAY_GAIN, &replay_gain).await {
+            println!("could not persist replay gain settings: {}", e);
+        }
+        self.replay_gain = replay_gain;
+        self.apply_replay_gain();
+    }
+
+    async fn toggle(&mut self, evt_tx: &UnboundedSender<PlayerEvent>) {
+        if self.state.is_playing() {
+            self.pause(evt_tx).await;
+        } else {
+            self.play(evt_tx).await;
+        }
+    }
+
+    async fn play(&mut self, evt_tx: &UnboundedSender<PlayerEvent>) {
+        // Error only leads to Loading, a new item has to be selected
+        if self.item.is_none() || self.state == PlayerState::Error {
+            return;
+        }
+        if self.state == PlayerState::Ended {
+            self.restart_item(evt_tx);
+            return;
+        }
+        if self.state == PlayerState::Paused {
+            self.auto_rewind(evt_tx).await;
+        }
+        if self.sink.is_some() {
+            self.resume_sink();
+            self.set_state(evt_tx, PlayerState::Playing);
         }
     }
 
-    fn pause(&self) {
+    async fn pause(&mut self, evt_tx: &UnboundedSender<PlayerEvent>) {
+        // a pause between loop repetitions becomes a regular pause
+        self.loop_resume_at = None;
+        self.fade_out(self.fades.pause_resume).await;
         if let Some(sink) = &self.sink {
             sink.pause();
+            self.set_state(evt_tx, PlayerState::Paused);
+        }
+    }
+
+    /// Seeks back depending on the pause length, but never before the start of the current chapter
+    async fn auto_rewind(&mut self, evt_tx: &UnboundedSender<PlayerEvent>) {
+        let Some(paused_at) = self.paused_at else {
+            return;
+        };
+        let rewind = self.auto_rewind.rewind_for(paused_at.elapsed().unwrap_or_default());
+        if rewind.is_zero() {
+            return;
+        }
+        let (Some(_), Some(item)) = (&self.sink, self.item.clone()) else {
+            return;
+        };
+        let current_pos = self.position();
+        let chapter_start = self
+            .chapter_index_at(current_pos)
+            .map(|index| self.chapters()[index].start)
+            .unwrap_or(Duration::ZERO);
+        let new_pos = max(current_pos.saturating_sub(rewind), chapter_start);
+        if new_pos >= current_pos || !self.seek(evt_tx, new_pos).await {
+            return;
+        }
+        self.update_position(evt_tx, new_pos).await;
+
+        if let Err(e) = self
+            .media_source
+            .add_progress_history(&item.id, &self.session_key, MediaSourceProgressReason::AutoRewind, new_pos, current_pos)
+            .await
+        {
+            println!("could not record auto rewind: {}", e);
         }
     }
 
-    fn try_seek(&self, position: Duration) -> Result<(), SeekError> {
-        if self.sink.is_none() {
+    async fn update_auto_rewind(&mut self, auto_rewind: AutoRewind) {
+        if let Err(e) = self.settings.set(SETTINGS_KEY_AUTO_REWIND, &auto_rewind).await {
+            println!("could not persist auto rewind: {}", e);
+        }
+        self.auto_rewind = auto_rewind;
+    }
+
+    async fn add_bookmark(&mut self, evt_tx: &UnboundedSender<PlayerEvent>, note: Option<String>) {
+        let (Some(item), Some(_)) = (&self.item, &self.sink) else {
+            return;
+        };
+        let position = self.position();
+        let mut bookmark = MediaSourceBookmark::new(item.id.clone(), position);
+        bookmark.chapter_index = self.chapter_index_at(position);
+        bookmark.chapter_name = bookmark.chapter_index.map(|index| self.chapters()[index].name.clone());
+        bookmark.note = note.filter(|n| !n.trim().is_empty());
+
+        match self.media_source.add_bookmark(bookmark).await {
+            Ok(bookmark) => {
+                let _ = evt_tx.send(PlayerEvent::BookmarkAdded(bookmark));
+            }
+            Err(e) => println!("could not add bookmark: {}", e),
+        }
+    }
+
+    /// Plays an item from the given position, e.g. to jump to a bookmark
+    async fn play_media_at(&mut self, evt_tx: &UnboundedSender<PlayerEvent>, id: String, position: Duration) {
+        if self.item.as_ref().is_none_or(|i| i.id != id) {
+            self.play_media(evt_tx, id.clone()).await;
+        }
+        // a different item is playing if the requested one failed
+        if self.item.as_ref().is_none_or(|i| i.id != id) {
+            return;
+        }
+        if self.seek(evt_tx, position).await {
+            self.update_position(evt_tx, position).await;
+        }
+        // jumping to an explicit position must not be rewound
+        self.paused_at = None;
+        if !self.state.is_playing() {
+            self.play(evt_tx).await;
+        }
+    }
+
+    fn try_seek(&mut self, position: Duration) -> Result<(), PlayerError> {
+        let Some(sink) = &self.sink else {
             return Ok(());
+        };
+        if let Err(e) = sink.try_seek(position) {
+            let title = self.item.as_ref().map(|i| i.title.clone()).unwrap_or_default();
+            return Err(PlayerError::Seek(title, e));
+        }
+        // the sink position is the item position again after seeking
+        self.position_offset = Duration::ZERO;
+        Ok(())
+    }
+
+    /// Position in the current item
+    fn position(&self) -> Duration {
+        self.sink
+            .as_ref()
+            .map(|sink| sink.get_pos() + self.position_offset + self.skipped_time.get())
+            .unwrap_or_else(|| self.saved_position.unwrap_or_default())
+    }
+
+    /// Seeking blocks until the decoder reached the new position, which is reported as buffering.
+    /// While playing, the old position fades out and the new one fades in. A failed seek is
+    /// reported and playback continues at the old position, returns whether the seek succeeded.
+    async fn seek(&mut self, evt_tx: &UnboundedSender<PlayerEvent>, position: Duration) -> bool {
+        let audible = self.is_audible();
+        if audible {
+            self.fade_out(self.fades.seek).await;
+        }
+        let previous_state = self.state;
+        let previous_position = self.position();
+        self.set_state(evt_tx, PlayerState::Buffering);
+        let result = self.try_seek(position);
+        self.set_state(evt_tx, previous_state);
+        if audible {
+            self.fade_control.fade_to(1.0, self.fades.seek);
+        }
+        match result {
+            Ok(()) => {
+                if let Some(session) = &mut self.session
+                    && position > previous_position
+                {
+                    session.skipped += position - previous_position;
+                }
+                true
+            }
+            Err(e) => {
+                self.report_error(evt_tx, e);
+                false
+            }
         }
-        let sink = self.sink.as_ref().unwrap();
-        sink.try_seek(position)
     }
 
     fn chapters(&self) -> Vec<MediaSourceChapter> {
-        let self_item = self.item.clone();
-        if self_item.is_none() {
-            return vec![];
+        self.item.as_ref().map(|item| item.metadata.chapters.clone()).unwrap_or_default()
+    }
+
+    fn chapter_index_at(&self, position: Duration) -> Option<usize> {
+        self.chapters()
+            .iter()
+            .position(|chapter| chapter.start <= position && chapter.end() > position)
+    }
+
+    fn item_duration(&self) -> Duration {
+        self.item.as_ref().map(Self::duration_of).unwrap_or(Duration::ZERO)
+    }
+
+    fn duration_of(item: &MediaSourceItem) -> Duration {
+        if !item.metadata.duration.is_zero() {
+            return item.metadata.duration;
+        }
+        item.metadata.chapters.last().map(|c| c.end()).unwrap_or(Duration::ZERO)
+    }
+
+    fn position_at(&self, position: Duration) -> PlayerPosition {
+        let duration = self.item_duration();
+        let chapter_index = self.chapter_index_at(position);
+        let (chapter_position, chapter_duration) = match chapter_index {
+            Some(index) => {
+                let chapter = &self.chapters()[index];
+                (position.saturating_sub(chapter.start), chapter.duration)
+            }
+            // without chapters the whole item is treated as a single chapter
+            None => (position, duration),
+        };
+
+        PlayerPosition {
+            position,
+            duration,
+            remaining: duration.saturating_sub(position),
+            chapter_index,
+            chapter_position,
+            chapter_duration,
+            chapter_remaining: chapter_duration.saturating_sub(chapter_position),
         }
-        let current_item = self_item.unwrap();
-        current_item.metadata.chapters
     }
 
     fn next_chapter(&self) -> Option<MediaSourceChapter> {
-        if let Some(sink) = &self.sink {
-            let current_pos = sink.get_pos();
+        if self.sink.is_some() {
+            let current_pos = self.position();
             let chapters = self.chapters();
             for chapter in chapters {
                 if chapter.start > current_pos {
@@ -204,8 +926,8 @@ impl Player {
     }
 
     fn current_chapter(&self) -> Option<MediaSourceChapter> {
-        if let Some(sink) = &self.sink {
-            let current_pos = sink.get_pos();
+        if self.sink.is_some() {
+            let current_pos = self.position();
             let chapters = self.chapters();
             if chapters.is_empty() {
                 return None;
@@ -220,8 +942,8 @@ impl Player {
     }
 
     fn previous_chapter(&self) -> Option<MediaSourceChapter> {
-        if let Some(sink) = &self.sink {
-            let current_pos = sink.get_pos();
+        if self.sink.is_some() {
+            let current_pos = self.position();
             let chapters = self.chapters();
             if chapters.is_empty() {
                 return None;
@@ -238,183 +960,328 @@ impl Player {
         None
     }
 
-    // todo:
-    // next, previous, set_volume, set_speed
-
-    pub async fn run(
-        &mut self,
-        mut cmd_rx: UnboundedReceiver<PlayerCommand>,
-        evt_tx: UnboundedSender<PlayerEvent>,
-    ) {
-        let mut last_sink_update_attempt = SystemTime::now();
-        loop {
-            // polling in case the audio hardware has not been successfully initialized yet
-
-            let now = SystemTime::now();
+    async fn load_settings(&mut self, evt_tx: &UnboundedSender<PlayerEvent>) {
+        self.auto_rewind = self.settings.get(SETTINGS_KEY_AUTO_REWIND, AutoRewind::defaults()).await;
+        self.repeat_mode = self.settings.get(SETTINGS_KEY_REPEAT_MODE, RepeatMode::Off).await;
+        self.queue = self.settings.get(SETTINGS_KEY_QUEUE, PlayQueue::default()).await.validated();
+        self.replay_gain = self.settings.get(SETTINGS_KEY_REPLAY_GAIN, ReplayGainSettings::defaults()).await;
+        self.voice_boost = self.settings.get(SETTINGS_KEY_VOICE_BOOST, VoiceBoostSettings::default()).await;
+        self.series_autoplay = self.settings.get(SETTINGS_KEY_SERIES_AUTOPLAY, SeriesAutoplaySettings::defaults()).await;
+        self.send_series_autoplay(evt_tx);
+        self.equalizer = self.settings.get(SETTINGS_KEY_EQUALIZER, EqualizerSettings::default()).await;
+        self.equalizer_control.set_bands(self.equalizer.bands());
+        let _ = evt_tx.send(PlayerEvent::EqualizerChanged(self.equalizer.clone()));
+        self.crossfade = self.settings.get(SETTINGS_KEY_CROSSFADE, CrossfadeSettings::defaults()).await;
+        let _ = evt_tx.send(PlayerEvent::CrossfadeChanged(self.crossfade.duration));
+        self.silence_skip = self.settings.get(SETTINGS_KEY_SILENCE_SKIP, SilenceSkipSettings::defaults()).await;
+        self.silence_skip_control
+            .configure(self.silence_skip.threshold_db, self.silence_skip.min_silence);
+        let time_saved_millis: u64 = self.settings.get(SETTINGS_KEY_SILENCE_SKIP_TIME_SAVED, 0).await;
+        self.silence_time_saved = Duration::from_millis(time_saved_millis);
+        self.silence_time_saved_persisted = self.silence_time_saved;
+        let _ = evt_tx.send(PlayerEvent::SilenceSkipChanged(self.silence_skip.enabled));
+        let _ = evt_tx.send(PlayerEvent::SilenceTimeSaved(self.silence_time_saved));
+        self.loop_options = self.settings.get(SETTINGS_KEY_LOOP_OPTIONS, LoopOptions::default()).await;
+        self.fades = self.settings.get(SETTINGS_KEY_FADES, FadeSettings::defaults()).await;
+        self.send_play_mode(evt_tx);
+    }
 
-            if self.sink.is_none() && last_sink_update_attempt + Duration::from_millis(2000) < now {
-                self.connect_sink();
-                last_sink_update_attempt = now;
+    /// Handles a single command, returns false when the player should stop
+    async fn handle_command(&mut self, evt_tx: &UnboundedSender<PlayerEvent>, cmd: PlayerCommand) -> bool {
+        match cmd {
+            PlayerCommand::Update(s) => {
+                self.play_media(evt_tx, s).await;
             }
-
-            if let Some(sink) = &self.sink {
-                tokio::select! {
-
-                    // this part makes the UI crash
-                    /*
-                    Some(btn_cmd) = button_cmd_rx.recv() => {
-                        match btn_cmd {
-                            PlayerCommand::HandleButton(key,action,timestamp) => {
-                                println!("===== handle button =====");
-                            }
-                            _ => {}
-                        }
+            PlayerCommand::PlayTest() => {
+                self.play_test().await;
+                self.set_state(evt_tx, PlayerState::Idle);
+            }
+            PlayerCommand::PlayMedia(s) => {
+                self.play_media(evt_tx, s).await;
+            }
+            PlayerCommand::Play() => {
+                self.play(evt_tx).await;
+            }
+            PlayerCommand::Pause() => {
+                self.pause(evt_tx).await;
+            }
+            PlayerCommand::Toggle() => {
+                self.toggle(evt_tx).await;
+            }
+            PlayerCommand::Stop() => {
+                self.set_state(evt_tx, PlayerState::Idle);
+                let _ = evt_tx.send(PlayerEvent::Stopped);
+                return false;
+            },
+            PlayerCommand::Next() => {
+                if let Some(next_chapter) = self.next_chapter() {
+                    if self.seek(evt_tx, next_chapter.start).await {
+                        self.update_position(evt_tx, next_chapter.start).await;
+                    }
+                } else {
+                    self.skip_to_next(evt_tx).await;
+                }
+            }
+            PlayerCommand::Previous() => {
+                let current_pos = self.position();
+                let is_item_start = current_pos <= self.previous_delay() && self.previous_chapter().is_none();
+                if is_item_start && self.skip_to_previous(evt_tx).await {
+                    // continued with the previous queue item
+                } else if let Some(current_chapter) = self.current_chapter()
+                    && current_pos - current_chapter.start > self.previous_delay() {
+                    if self.seek(evt_tx, current_chapter.start).await {
+                        self.update_position(evt_tx, current_chapter.start).await;
                     }
 
-                     */
-
-                    Some(cmd) = cmd_rx.recv() => {
-                        println!("============== cmd received ==============");
-                        match cmd {
-                            PlayerCommand::Update(s) => {
-                                self.play_media(s.clone()).await;
-                                // format!("Playing {}", x)
-                                // todo: implement player.is_playing / player.status
-
-                                self.update_playing_status(&evt_tx).await;
-                                /*
-                                if self.sink.is_paused() {
-                                    let _ = evt_tx.send(PlayerEvent::Status("paused".to_string()));
-                                } else {
-                                    let _ = evt_tx.send(PlayerEvent::Status("playing".to_string()));
-                                }
-
-                                 */
-                            }
-                            PlayerCommand::PlayTest() => {
-                                self.play_test().await;
-                            }
-                            PlayerCommand::PlayMedia(s) => {
-                                self.play_media(s).await;
-                                self.update_playing_status(&evt_tx).await;
-                            }
-                            PlayerCommand::Play() => {
-                                self.play();
-                                self.update_playing_status(&evt_tx).await;
-                            }
-                            PlayerCommand::Pause() => {
-                                self.pause();
-                                self.update_playing_status(&evt_tx).await;
-                            }
-                            PlayerCommand::Stop() => {
-                                let _ = evt_tx.send(PlayerEvent::Stopped);
-                                break;
-                            },
-                            PlayerCommand::Next() => {
-                                let next_chapter = self.next_chapter();
-                                if next_chapter.is_some() {
-                                    let new_pos = next_chapter.unwrap().start;
-                                    self.try_seek(new_pos).unwrap();
-                                    self.update_position(&evt_tx, new_pos).await;
-                                } else {
-                                    sink.skip_one()
-                                }
-                            }
-                            PlayerCommand::Previous() => {
-                                let current_pos = sink.get_pos();
-                                if current_pos <= self.previous_delay() {
-                                    // todo: skip to previous playlist item
-                                    // return
-                                }
-
-                                if let Some(current_chapter) = self.current_chapter()
-                                    && current_pos - current_chapter.start > self.previous_delay() {
-                                    self.try_seek(current_chapter.start).unwrap();
-                                    self.update_position(&evt_tx, current_chapter.start).await;
-
-                                } else if let Some(previous_chapter) = self.previous_chapter() {
-                                    self.try_seek(previous_chapter.start).unwrap();
-                                    self.update_position(&evt_tx, previous_chapter.start).await;
-
-                                } else {
-                                    let zero = Duration::from_secs(0);
-                                    self.try_seek(zero).unwrap();
-                                    self.update_position(&evt_tx, zero).await;
-                                }
-                            }
-                            PlayerCommand::SeekRelative(millis) => {
-                                let new_pos = max(sink.get_pos().as_millis() as i64 + millis, 0) as u64;
-                                self.try_seek(Duration::from_millis(new_pos));
-                            }
-                            PlayerCommand::SeekTo(_) => {},
-                            _ => {}
-                        }
+                } else if let Some(previous_chapter) = self.previous_chapter() {
+                    if self.seek(evt_tx, previous_chapter.start).await {
+                        self.update_position(evt_tx, previous_chapter.start).await;
                     }
 
-                    _ = tokio::time::sleep(Duration::from_millis(500)) => {
-                        self.update_position(&evt_tx, sink.get_pos()).await;
+                } else {
+                    let zero = Duration::from_secs(0);
+                    if self.seek(evt_tx, zero).await {
+                        self.update_position(evt_tx, zero).await;
                     }
                 }
+            }
+            PlayerCommand::SeekRelative(millis) => {
+                let new_pos = max(self.position().as_millis() as i64 + millis, 0) as u64;
+                self.seek(evt_tx, Duration::from_millis(new_pos)).await;
+            }
+            PlayerCommand::SeekTo(position) => {
+                if self.seek(evt_tx, position).await {
+                    self.update_position(evt_tx, position).await;
+                }
+            },
+            PlayerCommand::PlayMediaAt(id, position) => {
+                self.play_media_at(evt_tx, id, position).await;
+            }
+            PlayerCommand::PlayQueue(item_ids, start_index) => {
+                self.play_queue(evt_tx, item_ids, start_index).await;
+            }
+            PlayerCommand::SetRepeatMode(repeat_mode) => {
+                self.set_repeat_mode(evt_tx, repeat_mode).await;
+            }
+            PlayerCommand::SetShuffle(shuffle) => {
+                self.set_shuffle(evt_tx, shuffle).await;
+            }
+            PlayerCommand::UpdateReplayGain(replay_gain) => {
+                self.update_replay_gain(replay_gain).await;
+            }
+            PlayerCommand::SetVoiceBoost(enabled) => {
+                self.set_voice_boost(evt_tx, enabled).await;
+            }
+            PlayerCommand::SetVoiceBoostForMediaType(media_type, enabled) => {
+                self.set_voice_boost_for_media_type(evt_tx, media_type, enabled).await;
+            }
+            PlayerCommand::SetSeriesAutoplay(enabled) => {
+                self.set_series_autoplay(evt_tx, enabled).await;
+            }
+            PlayerCommand::SetSeriesAutoplayDefault(enabled) => {
+                self.set_series_autoplay_default(evt_tx, enabled).await;
+            }
+            PlayerCommand::SelectEqualizerPreset(preset) => {
+                self.select_equalizer_preset(evt_tx, preset).await;
+            }
+            PlayerCommand::SetEqualizerGain(band_index, gain_db) => {
+                self.set_equalizer_gain(evt_tx, band_index, gain_db).await;
+            }
+            PlayerCommand::SetCrossfade(duration) => {
+                self.set_crossfade(evt_tx, duration).await;
+            }
+            PlayerCommand::SetSilenceSkip(enabled) => {
+                let silence_skip = SilenceSkipSettings { enabled, ..self.silence_skip.clone() };
+                self.update_silence_skip(evt_tx, silence_skip).await;
+            }
+            PlayerCommand::UpdateSilenceSkip(silence_skip) => {
+                self.update_silence_skip(evt_tx, silence_skip).await;
+            }
+            PlayerCommand::SetLoop(start, end) => {
+                self.set_loop(evt_tx, start, end).await;
+            }
+            PlayerCommand::RepeatChapter() => {
+                self.repeat_chapter(evt_tx).await;
+            }
+            PlayerCommand::ClearLoop() => {
+                self.clear_loop(evt_tx);
+            }
+            PlayerCommand::UpdateLoopOptions(loop_options) => {
+                self.update_loop_options(loop_options).await;
+            }
+            PlayerCommand::UpdateFades(fades) => {
+                self.update_fades(fades).await;
+            }
+            PlayerCommand::AddBookmark(note) => {
+                self.add_bookmark(evt_tx, note).await;
+            }
+            PlayerCommand::ListOutputDevices() => {
+                let _ = evt_tx.send(PlayerEvent::OutputDevices(self.device_provider.output_devices()));
+            }
+            PlayerCommand::SelectOutput(device_name) => {
+                self.select_output(evt_tx, device_name).await;
+            }
+            PlayerCommand::UpdateAutoRewind(auto_rewind) => {
+                self.update_auto_rewind(auto_rewind).await;
+            }
+            PlayerCommand::SetUiVisible(visible) => {
+                self.ui_visible = visible;
+            }
+        }
+        true
+    }
+
+    /// Periodic work while playing: position updates, item transitions and loops
+    async fn tick(&mut self, evt_tx: &UnboundedSender<PlayerEvent>) {
+        if self.next_item.is_some() && self.sink.as_ref().is_some_and(|s| s.len() <= 1) {
+            self.advance_to_next_item(evt_tx).await;
+        }
+        let has_ended = self.sink.as_ref().is_some_and(|s| s.empty());
+        if self.section_loop.is_some() || self.loop_resume_at.is_some() {
+            self.check_loop(evt_tx, has_ended && self.state == PlayerState::Playing).await;
+        }
+        let has_ended = self.sink.as_ref().is_some_and(|s| s.empty());
+        let pos = self.position();
+        self.update_position(evt_tx, pos).await;
+        if self.state == PlayerState::Playing {
+            self.prepare_next_item().await;
+        }
+        self.collect_silence_time_saved(evt_tx).await;
+        self.persist_progress().await;
+        if has_ended && self.state == PlayerState::Playing {
+            self.handle_item_ended(evt_tx).await;
+        }
+    }
 
+    /// Time until the next tick, None while nothing is playing. Ticks are aligned to
+    /// full seconds and the start of synced lyrics lines while the position is visible
+    /// and wake up early for loop ends, the preloading of the next item and the
+    /// expected end of the item.
+    fn tick_delay(&self) -> Option<Duration> {
+        if let Some(resume_at) = self.loop_resume_at {
+            return Some(resume_at.duration_since(SystemTime::now()).unwrap_or_default());
+        }
+        if self.sink.is_none() || self.item.is_none() || self.state != PlayerState::Playing {
+            return None;
+        }
 
+        let position = self.position();
+        let mut delay = if self.ui_visible {
+            // the displayed seconds change right on time
+            Duration::from_millis(1000 - (position.as_millis() % 1000) as u64)
+        } else {
+            BACKGROUND_TICK_INTERVAL
+        };
+        if self.ui_visible
+            && let Some(lyrics) = self.item.as_ref().and_then(|i| i.metadata.lyrics.as_ref())
+            && let Some(next_line_start) = lyrics.next_line_start(position)
+        {
+            // the highlighted line changes right on time
+            delay = delay.min(next_line_start - position);
+        }
+        if let Some(section_loop) = &self.section_loop
+            && position < section_loop.end
+        {
+            delay = delay.min(section_loop.end - position);
+        }
 
+        let duration = self.item_duration();
+        let remaining = duration.saturating_sub(position);
+        if duration.is_zero() {
+            // unknown duration, the end is detected by the regular ticks
+        } else if remaining.is_zero() {
+            delay = delay.min(OVERRUN_TICK_INTERVAL);
+        } else {
+            delay = delay.min(remaining);
+            let preload = NEXT_ITEM_PRELOAD + self.crossfade.duration;
+            if !self.next_item_attempted && remaining > preload {
+                delay = delay.min(remaining - preload);
             }
         }
+        Some(delay)
     }
 
-    /*
-    pub async fn run_buttons(
+    /// The preferred device is only polled rarely, everything else is checked more often
+    /// to switch back as soon as the preferred device appears
+    fn device_check_interval(&self) -> Duration {
+        match &self.output {
+            Some(output) if output.device_name == self.preferred_device_name => PREFERRED_DEVICE_CHECK_INTERVAL,
+            _ => DEVICE_CHECK_INTERVAL,
+        }
+    }
+
+    async fn sleep_or_pending(delay: Option<Duration>) {
+        match delay {
+            Some(delay) => sleep(delay).await,
+            None => std::future::pending().await,
+        }
+    }
+
+    pub async fn run(
         &mut self,
         mut cmd_rx: UnboundedReceiver<PlayerCommand>,
+        evt_tx: UnboundedSender<PlayerEvent>,
     ) {
+        self.load_settings(&evt_tx).await;
+        self.check_output_device(&evt_tx);
+        let mut next_device_check = Instant::now() + self.device_check_interval();
+
         loop {
-            if let Some(sink) = &self.sink {
-                tokio::select! {
-                    Some(cmd) = cmd_rx.recv() => {
-                        println!("============== run_buttons cmd received ==============");
-                        match cmd {
-                            PlayerCommand::HandleButton(ButtonKey, ButtonAction, SystemTime) => {
-                                if ButtonAction == ButtonAction::Release {
-                                    self.toggle()
-                                }
-                            },
-                            _ => {}
-                        }
-                    }
+            // everything is awaited, nothing runs while paused except the device checks
+            let tick_delay = self.tick_delay();
+            let lost_signal = self.output.as_ref().map(|output| output.lost_signal());
+            let output_lost = async move {
+                match lost_signal {
+                    Some(lost_signal) => lost_signal.wait().await,
+                    None => std::future::pending().await,
+                }
+            };
 
+            tokio::select! {
+                cmd = cmd_rx.recv() => {
+                    let Some(cmd) = cmd else {
+                        break;
+                    };
+                    let keep_running = self.handle_command(&evt_tx, cmd).await;
+                    self.store_ended_sessions().await;
+                    if !keep_running {
+                        break;
+                    }
+                    // state changes are reported right away, not with the next tick
+                    let pos = self.position();
+                    self.update_position(&evt_tx, pos).await;
+                }
+                _ = Self::sleep_or_pending(tick_delay) => {
+                    self.tick(&evt_tx).await;
+                    self.store_ended_sessions().await;
+                }
+                _ = output_lost => {
+                    self.check_output_device(&evt_tx);
+                    self.store_ended_sessions().await;
+                    next_device_check = Instant::now() + self.device_check_interval();
+                }
+                _ = tokio::time::sleep_until(next_device_check) => {
+                    self.check_output_device(&evt_tx);
+                    self.store_ended_sessions().await;
+                    next_device_check = Instant::now() + self.device_check_interval();
                 }
             }
         }
     }
-    */
-    async fn update_position(&self, evt_tx: &mpsc::UnboundedSender<PlayerEvent>, pos: Duration) {
-        if let Some(item) = self.item.clone() {
-            let _ = evt_tx.send(PlayerEvent::Position(item.id.to_string(), pos));
-        }
-    }
 
-    async fn update_playing_status(&self, evt_tx: &UnboundedSender<PlayerEvent>) {
-        if let Some(sink) = &self.sink {
-            let self_item_opt = self.item.clone();
-            if self_item_opt.is_none() {
-                return;
-            }
-            let self_item_opt = self.item.clone();
-            if self_item_opt.is_none() {
-                return;
-            }
-            let self_item = self_item_opt.unwrap();
-            if sink.is_paused() {
-                let _ = evt_tx.send(PlayerEvent::Status(
-                    self_item.id.to_string(),
-                    "paused".to_string(),
-                ));
-            } else {
-                let _ = evt_tx.send(PlayerEvent::Status(
-                    self_item.id.to_string(),
-                    "playing".to_string(),
-                ));
-            }
+    async fn update_position(&mut self, evt_tx: &mpsc::UnboundedSender<PlayerEvent>, pos: Duration) {
+        let Some(item) = self.item.clone() else {
+            return;
+        };
+        let position = self.position_at(pos);
+
+        if position.chapter_index != self.chapter_index {
+            self.chapter_index = position.chapter_index;
+            let chapter_name = position.chapter_index.map(|index| self.chapters()[index].name.clone());
+            let _ = evt_tx.send(PlayerEvent::ChapterChanged(item.id.to_string(), position.chapter_index, chapter_name));
         }
+
+        let _ = evt_tx.send(PlayerEvent::Position(item.id.to_string(), position));
     }
+
 }
@@ -0,0 +1,85 @@
+use super::{Player, PlayerEvent, PlayerState, SETTINGS_KEY_QUEUE, SETTINGS_KEY_REPEAT_MODE};
+use crate::player::play_queue::PlayQueue;
+use crate::player::repeat_mode::RepeatMode;
+use std::time::SystemTime;
+use tokio::sync::mpsc::UnboundedSender;
+
+impl Player {
+    pub(super) async fn play_queue(&mut self, evt_tx: &UnboundedSender<PlayerEvent>, item_ids: Vec<String>, start_index: usize) {
+        let shuffle = self.queue.is_shuffled();
+        self.queue = PlayQueue::new(item_ids, start_index);
+        if shuffle {
+            self.queue.set_shuffle(Some(Self::create_shuffle_seed()));
+        }
+        self.persist_queue().await;
+        if let Some(id) = self.queue.current() {
+            self.load_media(evt_tx, id).await;
+        }
+    }
+
+    /// Continues with the next queue item, returns false if there is none
+    pub(super) async fn skip_to_next(&mut self, evt_tx: &UnboundedSender<PlayerEvent>) -> bool {
+        let Some(id) = self.queue.next(self.repeat_mode == RepeatMode::All) else {
+            return false;
+        };
+        self.persist_queue().await;
+        self.load_media(evt_tx, id).await;
+        true
+    }
+
+    pub(super) async fn skip_to_previous(&mut self, evt_tx: &UnboundedSender<PlayerEvent>) -> bool {
+        let Some(id) = self.queue.previous(self.repeat_mode == RepeatMode::All) else {
+            return false;
+        };
+        self.persist_queue().await;
+        self.load_media(evt_tx, id).await;
+        true
+    }
+
+    pub(super) async fn handle_item_ended(&mut self, evt_tx: &UnboundedSender<PlayerEvent>) {
+        if self.repeat_mode == RepeatMode::One {
+            self.restart_item(evt_tx);
+            return;
+        }
+        if !self.skip_to_next(evt_tx).await && !self.play_next_in_series(evt_tx).await {
+            self.set_state(evt_tx, PlayerState::Ended);
+        }
+    }
+
+    pub(super) fn create_shuffle_seed() -> u64 {
+        SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .map(|d| d.as_nanos() as u64)
+            .unwrap_or_default()
+    }
+
+    pub(super) async fn set_shuffle(&mut self, evt_tx: &UnboundedSender<PlayerEvent>, shuffle: bool) {
+        if shuffle == self.queue.is_shuffled() {
+            return;
+        }
+        self.queue.set_shuffle(shuffle.then(Self::create_shuffle_seed));
+        // the prepared item is not the next one anymore
+        self.discard_next_item();
+        self.persist_queue().await;
+        self.send_play_mode(evt_tx);
+    }
+
+    pub(super) async fn set_repeat_mode(&mut self, evt_tx: &UnboundedSender<PlayerEvent>, repeat_mode: RepeatMode) {
+        self.repeat_mode = repeat_mode;
+        self.discard_next_item();
+        if let Err(e) = self.settings.set(SETTINGS_KEY_REPEAT_MODE, &repeat_mode).await {
+            println!("could not persist repeat mode: {}", e);
+        }
+        self.send_play_mode(evt_tx);
+    }
+
+    pub(super) fn send_play_mode(&self, evt_tx: &UnboundedSender<PlayerEvent>) {
+        let _ = evt_tx.send(PlayerEvent::PlayModeChanged(self.repeat_mode, self.queue.is_shuffled()));
+    }
+
+    pub(super) async fn persist_queue(&self) {
+        if let Err(e) = self.settings.set(SETTINGS_KEY_QUEUE, &self.queue).await {
+            println!("could not persist queue: {}", e);
+        }
+    }
+}
@@ -0,0 +1,127 @@
+use super::{Player, PlayerEvent, PlayerState, SETTINGS_KEY_LOOP_OPTIONS};
+use crate::player::loop_options::LoopOptions;
+use crate::player::loop_target::LoopTarget;
+use crate::player::section_loop::SectionLoop;
+use std::time::{Duration, SystemTime};
+use tokio::sync::mpsc::UnboundedSender;
+
+// positions this far behind the loop end still count as reaching it, anything beyond was seeked to
+const LOOP_END_TOLERANCE: Duration = Duration::from_secs(1);
+
+impl Player {
+    /// Loops from `start` to `end` in the current item
+    pub(super) async fn set_loop(&mut self, evt_tx: &UnboundedSender<PlayerEvent>, start: Duration, end: Duration) {
+        if self.item.is_none() || end <= start {
+            return;
+        }
+        self.start_loop(evt_tx, SectionLoop::new(LoopTarget::Range(start, end), start, end)).await;
+    }
+
+    /// Loops the current chapter, or the whole item if it has no chapters
+    pub(super) async fn repeat_chapter(&mut self, evt_tx: &UnboundedSender<PlayerEvent>) {
+        if self.item.is_none() {
+            return;
+        }
+        let (start, end) = self.current_chapter_bounds();
+        self.start_loop(evt_tx, SectionLoop::new(LoopTarget::CurrentChapter, start, end)).await;
+    }
+
+    pub(super) fn current_chapter_bounds(&self) -> (Duration, Duration) {
+        match self.current_chapter() {
+            Some(chapter) => (chapter.start, chapter.end()),
+            None => (Duration::ZERO, self.item_duration()),
+        }
+    }
+
+    pub(super) async fn start_loop(&mut self, evt_tx: &UnboundedSender<PlayerEvent>, section_loop: SectionLoop) {
+        // the sink must not move on to the next item while looping
+        self.discard_next_item();
+        let position = self.position();
+        let start = section_loop.start;
+        let outside = !section_loop.contains(position);
+        self.section_loop = Some(section_loop);
+        if outside {
+            self.seek(evt_tx, start).await;
+        }
+        self.send_loop(evt_tx);
+    }
+
+    pub(super) fn clear_loop(&mut self, evt_tx: &UnboundedSender<PlayerEvent>) {
+        if self.loop_resume_at.take().is_some() && self.state.is_playing() {
+            self.resume_sink();
+        }
+        if self.section_loop.take().is_some() {
+            self.send_loop(evt_tx);
+        }
+    }
+
+    pub(super) fn send_loop(&self, evt_tx: &UnboundedSender<PlayerEvent>) {
+        let _ = evt_tx.send(PlayerEvent::LoopChanged(self.section_loop.clone()));
+    }
+
+    pub(super) async fn update_loop_options(&mut self, loop_options: LoopOptions) {
+        if let Err(e) = self.settings.set(SETTINGS_KEY_LOOP_OPTIONS, &loop_options).await {
+            println!("could not persist loop options: {}", e);
+        }
+        self.loop_options = loop_options;
+    }
+
+    /// Jumps back to the loop start when the end was reached, `has_ended` is set
+    /// when the loop end is the end of the item
+    pub(super) async fn check_loop(&mut self, evt_tx: &UnboundedSender<PlayerEvent>, has_ended: bool) {
+        if let Some(resume_at) = self.loop_resume_at {
+            if SystemTime::now() >= resume_at {
+                self.loop_resume_at = None;
+                self.resume_sink();
+            }
+            return;
+        }
+        let Some(mut section_loop) = self.section_loop.clone() else {
+            return;
+        };
+        if self.state != PlayerState::Playing {
+            return;
+        }
+
+        let position = self.position();
+        let beyond_end = position > section_loop.end + LOOP_END_TOLERANCE;
+        if section_loop.target == LoopTarget::CurrentChapter && (position < section_loop.start || beyond_end) {
+            // seeked to another chapter, which is looped from now on
+            let (start, end) = self.current_chapter_bounds();
+            self.section_loop = Some(SectionLoop::new(LoopTarget::CurrentChapter, start, end));
+            self.send_loop(evt_tx);
+            return;
+        }
+        if !has_ended && (position < section_loop.end || beyond_end) {
+            return;
+        }
+
+        section_loop.repetitions += 1;
+        if let Some(max_repetitions) = self.loop_options.max_repetitions
+            && section_loop.repetitions >= max_repetitions
+        {
+            self.clear_loop(evt_tx);
+            return;
+        }
+
+        if has_ended {
+            self.restart_item(evt_tx);
+            if self.item.is_none() {
+                return;
+            }
+        }
+        // pause first, so that the seek happens in silence
+        if !self.loop_options.pause.is_zero() {
+            self.fade_out(self.fades.pause_resume).await;
+            if let Some(sink) = &self.sink {
+                sink.pause();
+            }
+            self.loop_resume_at = Some(SystemTime::now() + self.loop_options.pause);
+        }
+        if self.seek(evt_tx, section_loop.start).await {
+            self.update_position(evt_tx, section_loop.start).await;
+        }
+        self.section_loop = Some(section_loop);
+        self.send_loop(evt_tx);
+    }
+}
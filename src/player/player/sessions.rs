@@ -0,0 +1,82 @@
+use super::Player;
+use crate::media_source::media_source::{MediaSourceListeningSession, MediaSourceListeningState};
+use chrono::Utc;
+use std::time::{Duration, SystemTime};
+
+// shorter playbacks are not stored as listening session
+const MIN_SESSION_LENGTH: Duration = Duration::from_secs(5);
+// the progress is stored when a session ends and in this interval while playing
+const PROGRESS_PERSIST_INTERVAL: Duration = Duration::from_secs(30);
+
+impl Player {
+    pub(super) fn create_session_key(item_id: &str) -> String {
+        let millis = SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .map(|d| d.as_millis())
+            .unwrap_or_default();
+        format!("{}-{}", item_id, millis)
+    }
+
+    pub(super) fn start_session(&mut self) {
+        if self.session.is_some() {
+            return;
+        }
+        if let Some(item) = &self.item {
+            self.session = Some(MediaSourceListeningSession::new(item.id.clone(), self.session_key.clone(), self.position()));
+        }
+    }
+
+    pub(super) fn end_session(&mut self, end_position: Duration) {
+        let Some(mut session) = self.session.take() else {
+            return;
+        };
+        session.end = Utc::now();
+        session.end_position = end_position;
+        session.listened = (session.end - session.start).to_std().unwrap_or_default();
+        let state = MediaSourceListeningState::at(end_position, self.item_duration());
+        self.ended_sessions.push((session, state));
+    }
+
+    /// Stores the progress of the running session from time to time, so that it survives
+    /// e.g. a power loss
+    pub(super) async fn persist_progress(&mut self) {
+        let Some(item_id) = self.session.as_ref().map(|session| session.item_id.clone()) else {
+            return;
+        };
+        if self.progress_persisted_at.elapsed().unwrap_or_default() < PROGRESS_PERSIST_INTERVAL {
+            return;
+        }
+        self.progress_persisted_at = SystemTime::now();
+        let position = self.position();
+        let state = MediaSourceListeningState::at(position, self.item_duration());
+        if let Err(e) = self
+            .media_source
+            .update_listening_progress(&item_id, position, state)
+            .await
+        {
+            println!("could not store listening progress: {}", e);
+        }
+    }
+
+    pub(super) async fn store_ended_sessions(&mut self) {
+        if !self.ended_sessions.is_empty() {
+            self.progress_persisted_at = SystemTime::now();
+        }
+        for (session, state) in std::mem::take(&mut self.ended_sessions) {
+            if let Err(e) = self
+                .media_source
+                .update_listening_progress(&session.item_id, session.end_position, state)
+                .await
+            {
+                println!("could not store listening progress: {}", e);
+            }
+            // e.g. a play immediately followed by skipping to the next item
+            if session.listened < MIN_SESSION_LENGTH {
+                continue;
+            }
+            if let Err(e) = self.media_source.add_listening_session(session).await {
+                println!("could not store listening session: {}", e);
+            }
+        }
+    }
+}
@@ -0,0 +1,109 @@
+use super::{Player, PlayerEvent, NEXT_ITEM_PRELOAD, SETTINGS_KEY_CROSSFADE};
+use crate::audio::crossfade_source::CrossfadeSource;
+use crate::audio::handoff_source::HandoffSource;
+use crate::audio::item_transition::{BoxedSource, ItemTransition};
+use crate::audio::silence_skip_source::SkippedTime;
+use crate::player::prepared_item::PreparedItem;
+use crate::player::repeat_mode::RepeatMode;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::mpsc::UnboundedSender;
+
+impl Player {
+    /// Starts over with a fresh transition, a queued handoff of the old one plays nothing
+    pub(super) fn reset_transition(&mut self) {
+        self.discard_next_item();
+        self.transition = Arc::new(ItemTransition::default());
+        self.position_offset = Duration::ZERO;
+        self.skipped_time = Arc::new(SkippedTime::default());
+    }
+
+    pub(super) fn discard_next_item(&mut self) {
+        self.transition.clear_next();
+        self.next_item = None;
+        self.next_item_attempted = false;
+    }
+
+    /// Decodes the next queue item ahead of time and queues it behind the current item
+    pub(super) async fn prepare_next_item(&mut self) {
+        if self.next_item_attempted
+            || !self.crossfade.gapless
+            || self.repeat_mode == RepeatMode::One
+            || self.section_loop.is_some()
+        {
+            return;
+        }
+        let (Some(sink), Some(item)) = (&self.sink, &self.item) else {
+            return;
+        };
+        let remaining = self.item_duration().saturating_sub(self.position());
+        if sink.len() != 1 || remaining.is_zero() || remaining > NEXT_ITEM_PRELOAD + self.crossfade.duration {
+            return;
+        }
+        self.next_item_attempted = true;
+
+        let Some(next_id) = self.queue.peek_next(self.repeat_mode == RepeatMode::All) else {
+            return;
+        };
+        let Some(next_item) = self.media_source.find(&next_id).await else {
+            return;
+        };
+        let decoder = match Self::open_decoder(&next_item) {
+            Ok(decoder) => decoder,
+            Err(e) => {
+                println!("could not prepare next item {}: {}", next_item.id, e);
+                return;
+            }
+        };
+        let crossfade = self.crossfade.duration_between(item, &next_item);
+        let next_transition = Arc::new(ItemTransition::default());
+        let next_skipped_time = Arc::new(SkippedTime::default());
+        let next_chain = self.processing_chain(decoder, next_skipped_time.clone());
+        let next_source: BoxedSource = Box::new(CrossfadeSource::new(next_chain, next_transition.clone(), Self::duration_of(&next_item)));
+        self.transition.set_next(next_source, crossfade);
+        if let Some(sink) = &self.sink {
+            sink.append(HandoffSource::new(self.transition.clone()));
+        }
+        self.next_item = Some(PreparedItem {
+            item: next_item,
+            transition: next_transition,
+            skipped_time: next_skipped_time,
+        });
+    }
+
+    /// The sink moved on to the prepared item, which becomes the current item
+    pub(super) async fn advance_to_next_item(&mut self, evt_tx: &UnboundedSender<PlayerEvent>) {
+        let Some(next_item) = self.next_item.take() else {
+            return;
+        };
+        self.queue.next(self.repeat_mode == RepeatMode::All);
+        self.persist_queue().await;
+        // the previous item played to its end
+        self.end_session(self.item_duration());
+
+        self.position_offset = self.transition.overlap();
+        self.transition = next_item.transition;
+        self.skipped_time = next_item.skipped_time;
+        self.next_item_attempted = false;
+        self.session_key = Self::create_session_key(&next_item.item.id);
+        self.item = Some(next_item.item);
+        self.chapter_index = None;
+        self.apply_voice_boost(evt_tx);
+        self.apply_silence_skip(evt_tx);
+        self.send_series_autoplay(evt_tx);
+        self.apply_replay_gain();
+        if self.state.is_playing() {
+            self.start_session();
+        }
+        let item_id = self.item.as_ref().map(|i| i.id.to_string()).unwrap_or_default();
+        let _ = evt_tx.send(PlayerEvent::State(item_id, self.state));
+    }
+
+    pub(super) async fn set_crossfade(&mut self, evt_tx: &UnboundedSender<PlayerEvent>, duration: Duration) {
+        self.crossfade.duration = duration;
+        if let Err(e) = self.settings.set(SETTINGS_KEY_CROSSFADE, &self.crossfade).await {
+            println!("could not persist crossfade settings: {}", e);
+        }
+        let _ = evt_tx.send(PlayerEvent::CrossfadeChanged(self.crossfade.duration));
+    }
+}
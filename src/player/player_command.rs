@@ -1,4 +1,12 @@
 use std::time::Duration;
+use crate::player::auto_rewind::AutoRewind;
+use crate::player::repeat_mode::RepeatMode;
+use crate::player::replay_gain_settings::ReplayGainSettings;
+use crate::media_source::media_type::MediaType;
+use crate::audio::equalizer_preset::EqualizerPreset;
+use crate::player::silence_skip_settings::SilenceSkipSettings;
+use crate::player::loop_options::LoopOptions;
+use crate::player::fade_settings::FadeSettings;
 
 #[derive(Debug)]
 pub enum PlayerCommand {
@@ -8,8 +16,44 @@ pub enum PlayerCommand {
     Pause(),
     Stop(),
     Play(),
+    Toggle(),
     Next(),
     Previous(),
     SeekRelative(i64),
     SeekTo(Duration),
+    // item id and position
+    PlayMediaAt(String, Duration),
+    // item ids and the index of the item to start with
+    PlayQueue(Vec<String>, usize),
+    SetRepeatMode(RepeatMode),
+    SetShuffle(bool),
+    UpdateReplayGain(ReplayGainSettings),
+    // for the current item
+    SetVoiceBoost(bool),
+    SetVoiceBoostForMediaType(MediaType, bool),
+    // for the series of the current item
+    SetSeriesAutoplay(bool),
+    // for series without their own setting
+    SetSeriesAutoplayDefault(bool),
+    SelectEqualizerPreset(EqualizerPreset),
+    // custom band index and gain in dB
+    SetEqualizerGain(usize, f32),
+    // zero disables cross fading between music items
+    SetCrossfade(Duration),
+    SetSilenceSkip(bool),
+    UpdateSilenceSkip(SilenceSkipSettings),
+    // start and end of an A-B loop in the current item
+    SetLoop(Duration, Duration),
+    RepeatChapter(),
+    ClearLoop(),
+    UpdateLoopOptions(LoopOptions),
+    UpdateFades(FadeSettings),
+    // optional note
+    AddBookmark(Option<String>),
+    ListOutputDevices(),
+    // device name
+    SelectOutput(String),
+    UpdateAutoRewind(AutoRewind),
+    // whether the position is shown, hidden players get fewer updates
+    SetUiVisible(bool),
 }
\ No newline at end of file
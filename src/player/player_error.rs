@@ -0,0 +1,34 @@
+use rodio::decoder::DecoderError;
+use rodio::source::SeekError;
+use std::fmt;
+use std::io;
+
+/// Failures while playing, the messages are shown to the user as they are
+#[derive(Debug)]
+pub enum PlayerError {
+    // item id
+    NotFound(String),
+    // item title and the reason the file could not be opened
+    Open(String, io::Error),
+    // item title and the reason the file could not be decoded
+    Decode(String, DecoderError),
+    // item title
+    Seek(String, SeekError),
+}
+
+impl fmt::Display for PlayerError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PlayerError::NotFound(_) => write!(f, "The item is not available anymore"),
+            PlayerError::Open(title, e) => match e.kind() {
+                io::ErrorKind::NotFound => write!(f, "\"{}\" is missing, the file was moved or deleted", title),
+                io::ErrorKind::PermissionDenied => write!(f, "\"{}\" cannot be read, permission denied", title),
+                _ => write!(f, "\"{}\" could not be opened", title),
+            },
+            PlayerError::Decode(title, _) => {
+                write!(f, "\"{}\" cannot be played, the file is damaged or its format is not supported", title)
+            }
+            PlayerError::Seek(title, _) => write!(f, "Could not jump to the position in \"{}\"", title),
+        }
+    }
+}
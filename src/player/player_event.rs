@@ -1,10 +1,39 @@
 use std::time::Duration;
+use crate::audio::output_device_info::OutputDeviceInfo;
+use crate::media_source::media_source_bookmark::MediaSourceBookmark;
+use crate::player::equalizer_settings::EqualizerSettings;
+use crate::player::player_error::PlayerError;
+use crate::player::player_position::PlayerPosition;
+use crate::player::player_state::PlayerState;
+use crate::player::repeat_mode::RepeatMode;
+use crate::player::section_loop::SectionLoop;
 use crate::player::trigger_action::TriggerAction;
 
 #[derive(Debug)]
 pub enum PlayerEvent {
-    Status(String, String),
-    Position(String, Duration),
+    State(String, PlayerState),
+    Position(String, PlayerPosition),
+    // item id, chapter index and chapter name, None when the position is outside of all chapters
+    ChapterChanged(String, Option<usize>, Option<String>),
+    // name of the connected output device, None when it was lost
+    OutputDeviceChanged(Option<String>),
+    OutputDevices(Vec<OutputDeviceInfo>),
+    BookmarkAdded(MediaSourceBookmark),
+    // repeat mode and whether shuffle is enabled
+    PlayModeChanged(RepeatMode, bool),
+    // whether voice boost is active for the current item
+    VoiceBoostChanged(bool),
+    // whether series autoplay is enabled by default and for the series of the current item
+    SeriesAutoplayChanged(bool, bool),
+    EqualizerChanged(EqualizerSettings),
+    CrossfadeChanged(Duration),
+    // whether silence skipping is enabled (it is only active for some media types)
+    SilenceSkipChanged(bool),
+    // total time saved by skipping silence
+    SilenceTimeSaved(Duration),
+    // None when no section is looped
+    LoopChanged(Option<SectionLoop>),
+    Error(PlayerError),
     Stopped,
     ExternalTrigger(TriggerAction)
-}
\ No newline at end of file
+}
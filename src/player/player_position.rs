@@ -0,0 +1,14 @@
+use std::time::Duration;
+
+/// Position report with everything the UI needs to display, so it does not
+/// have to recompute chapter boundaries
+#[derive(Debug, Clone)]
+pub struct PlayerPosition {
+    pub position: Duration,
+    pub duration: Duration,
+    pub remaining: Duration,
+    pub chapter_index: Option<usize>,
+    pub chapter_position: Duration,
+    pub chapter_duration: Duration,
+    pub chapter_remaining: Duration,
+}
@@ -0,0 +1,35 @@
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PlayerState {
+    Idle,
+    Loading,
+    Playing,
+    Paused,
+    // waiting for the decoder (seeking) or the audio output
+    Buffering,
+    Ended,
+    Error,
+}
+
+impl PlayerState {
+    pub fn can_transition_to(&self, next: PlayerState) -> bool {
+        use PlayerState::*;
+        if *self == next {
+            return false;
+        }
+        match (self, next) {
+            // a new item can be loaded and playback can be stopped or fail at any time
+            (_, Loading) | (_, Idle) | (_, Error) => true,
+            (Loading, Playing) | (Loading, Paused) => true,
+            (Playing, Paused) | (Playing, Buffering) | (Playing, Ended) => true,
+            (Paused, Playing) | (Paused, Buffering) => true,
+            (Buffering, Playing) | (Buffering, Paused) => true,
+            // restart from the beginning
+            (Ended, Playing) => true,
+            _ => false,
+        }
+    }
+
+    pub fn is_playing(&self) -> bool {
+        matches!(self, PlayerState::Playing | PlayerState::Buffering)
+    }
+}
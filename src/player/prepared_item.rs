@@ -0,0 +1,11 @@
+use crate::audio::item_transition::ItemTransition;
+use crate::audio::silence_skip_source::SkippedTime;
+use crate::media_source::media_source_item::MediaSourceItem;
+use std::sync::Arc;
+
+/// The next queue item, already decoding and queued in the sink behind the current one
+pub(crate) struct PreparedItem {
+    pub item: MediaSourceItem,
+    pub transition: Arc<ItemTransition>,
+    pub skipped_time: Arc<SkippedTime>,
+}
@@ -0,0 +1,11 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[derive(Serialize, Deserialize)]
+pub enum ReplayGainMode {
+    Off,
+    #[default]
+    Track,
+    // falls back to the track gain, if there is no album gain
+    Album,
+}
@@ -0,0 +1,56 @@
+use crate::media_source::media_source_replay_gain::MediaSourceReplayGain;
+use crate::player::replay_gain_mode::ReplayGainMode;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, PartialEq)]
+#[derive(Serialize, Deserialize)]
+pub struct ReplayGainSettings {
+    pub mode: ReplayGainMode,
+    // added to the gain, in dB
+    #[serde(default)]
+    pub preamp: f32,
+    // gain used for items without ReplayGain information, in dB
+    #[serde(default)]
+    pub fallback_gain: f32,
+    // lowers the gain so that the peak does not exceed full scale
+    #[serde(default = "default_prevent_clipping")]
+    pub prevent_clipping: bool,
+}
+
+fn default_prevent_clipping() -> bool {
+    true
+}
+
+impl ReplayGainSettings {
+    pub fn defaults() -> Self {
+        Self {
+            mode: ReplayGainMode::Track,
+            preamp: 0.0,
+            fallback_gain: 0.0,
+            prevent_clipping: true,
+        }
+    }
+
+    /// Linear volume factor for an item
+    pub fn volume_for(&self, replay_gain: Option<&MediaSourceReplayGain>) -> f32 {
+        if self.mode == ReplayGainMode::Off {
+            return 1.0;
+        }
+        let (gain, peak) = match replay_gain {
+            Some(rg) if self.mode == ReplayGainMode::Album && rg.album_gain.is_some() => {
+                (rg.album_gain, rg.album_peak.or(rg.track_peak))
+            }
+            Some(rg) => (rg.track_gain, rg.track_peak),
+            None => (None, None),
+        };
+        let gain = gain.unwrap_or(self.fallback_gain) + self.preamp;
+        let mut volume = 10f32.powf(gain / 20.0);
+        if self.prevent_clipping
+            && let Some(peak) = peak
+            && peak > 0.0
+        {
+            volume = volume.min(1.0 / peak);
+        }
+        volume
+    }
+}
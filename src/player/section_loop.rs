@@ -0,0 +1,26 @@
+use crate::player::loop_target::LoopTarget;
+use std::time::Duration;
+
+/// A section of the current item that is played over and over
+#[derive(Debug, Clone, PartialEq)]
+pub struct SectionLoop {
+    pub target: LoopTarget,
+    pub start: Duration,
+    pub end: Duration,
+    pub repetitions: u32,
+}
+
+impl SectionLoop {
+    pub fn new(target: LoopTarget, start: Duration, end: Duration) -> Self {
+        Self {
+            target,
+            start,
+            end,
+            repetitions: 0,
+        }
+    }
+
+    pub fn contains(&self, position: Duration) -> bool {
+        self.start <= position && position < self.end
+    }
+}
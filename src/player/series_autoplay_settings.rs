@@ -0,0 +1,29 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Continues with the next part of a series when an item ends, single series can override this
+#[derive(Debug, Clone)]
+#[derive(Serialize, Deserialize)]
+pub struct SeriesAutoplaySettings {
+    pub enabled: bool,
+    // series name -> enabled
+    #[serde(default)]
+    pub series: HashMap<String, bool>,
+}
+
+impl SeriesAutoplaySettings {
+    pub fn defaults() -> Self {
+        Self {
+            enabled: true,
+            series: HashMap::new(),
+        }
+    }
+
+    pub fn is_enabled_for(&self, series: &str) -> bool {
+        self.series.get(series).copied().unwrap_or(self.enabled)
+    }
+
+    pub fn set_for_series(&mut self, series: &str, enabled: bool) {
+        self.series.insert(series.to_string(), enabled);
+    }
+}
@@ -0,0 +1,32 @@
+use crate::media_source::media_source_item::MediaSourceItem;
+use crate::media_source::media_type::MediaType;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+#[derive(Debug, Clone, PartialEq)]
+#[derive(Serialize, Deserialize)]
+pub struct SilenceSkipSettings {
+    pub enabled: bool,
+    // only items of these types are shortened, music keeps its pauses
+    pub media_types: Vec<MediaType>,
+    // frames where all samples are below this level count as silence
+    pub threshold_db: f32,
+    // silences are shortened to this length
+    #[serde(with = "crate::serde_json_mods::duration_millis")]
+    pub min_silence: Duration,
+}
+
+impl SilenceSkipSettings {
+    pub fn defaults() -> Self {
+        Self {
+            enabled: false,
+            media_types: vec![MediaType::Audiobook, MediaType::Podcast],
+            threshold_db: -45.0,
+            min_silence: Duration::from_millis(500),
+        }
+    }
+
+    pub fn is_active_for(&self, item: &MediaSourceItem) -> bool {
+        self.enabled && self.media_types.contains(&item.media_type)
+    }
+}
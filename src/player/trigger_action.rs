@@ -5,5 +5,7 @@ pub enum TriggerAction {
     Previous,
     StepBack,
     StepForward,
+    AddBookmark,
+    ClearLoop,
     StopOngoing,
 }
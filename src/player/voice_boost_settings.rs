@@ -0,0 +1,35 @@
+use crate::media_source::media_source_item::MediaSourceItem;
+use crate::media_source::media_type::MediaType;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Voice boost is enabled per media type, single items can override this
+#[derive(Debug, Clone, Default)]
+#[derive(Serialize, Deserialize)]
+pub struct VoiceBoostSettings {
+    #[serde(default)]
+    pub media_types: Vec<MediaType>,
+    // item id -> enabled
+    #[serde(default)]
+    pub items: HashMap<String, bool>,
+}
+
+impl VoiceBoostSettings {
+    pub fn is_enabled_for(&self, item: &MediaSourceItem) -> bool {
+        self.items
+            .get(&item.id)
+            .copied()
+            .unwrap_or_else(|| self.media_types.contains(&item.media_type))
+    }
+
+    pub fn set_for_item(&mut self, item_id: &str, enabled: bool) {
+        self.items.insert(item_id.to_string(), enabled);
+    }
+
+    pub fn set_for_media_type(&mut self, media_type: MediaType, enabled: bool) {
+        self.media_types.retain(|m| *m != media_type);
+        if enabled {
+            self.media_types.push(media_type);
+        }
+    }
+}
@@ -1,4 +1,4 @@
-use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde::{Deserialize, Deserializer, Serializer};
 use std::time::Duration;
 
 pub fn serialize<S>(dur: &Duration, ser: S) -> Result<S::Ok, S::Error>
@@ -1 +1,2 @@
 pub mod duration_millis;
+pub mod option_duration_millis;
\ No newline at end of file
@@ -0,0 +1,21 @@
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::time::Duration;
+
+pub fn serialize<S>(dur: &Option<Duration>, ser: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    let ms: Option<u64> = match dur {
+        Some(d) => Some(d.as_millis().try_into().map_err(serde::ser::Error::custom)?),
+        None => None,
+    };
+    ms.serialize(ser)
+}
+
+pub fn deserialize<'de, D>(de: D) -> Result<Option<Duration>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let ms = Option::<u64>::deserialize(de)?;
+    Ok(ms.map(Duration::from_millis))
+}
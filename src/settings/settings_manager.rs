@@ -0,0 +1,59 @@
+use crate::entity::settings;
+use crate::entity::settings::ActiveModel;
+use chrono::Utc;
+use sea_orm::{ColumnTrait, DatabaseConnection, DbErr, EntityTrait, QueryFilter};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+#[derive(Clone)]
+pub struct SettingsManager {
+    db: DatabaseConnection,
+}
+
+impl SettingsManager {
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self { db }
+    }
+
+    /// Returns the stored value for `key` or `default`, if it is missing or cannot be parsed
+    pub async fn get<T: DeserializeOwned>(&self, key: &str, default: T) -> T {
+        let setting_result = settings::Entity::find()
+            .filter(settings::Column::Key.eq(key))
+            .one(&self.db)
+            .await;
+
+        if let Ok(Some(setting)) = setting_result
+            && let Ok(value) = serde_json::from_str(&setting.value)
+        {
+            return value;
+        }
+        default
+    }
+
+    pub async fn set<T: Serialize>(&self, key: &str, value: &T) -> Result<(), DbErr> {
+        let json = serde_json::to_string(value).map_err(|e| DbErr::Custom(e.to_string()))?;
+        let now = Utc::now();
+
+        let existing = settings::Entity::find()
+            .filter(settings::Column::Key.eq(key))
+            .one(&self.db)
+            .await?;
+
+        // if the key does not exist insert, otherwise update
+        let builder = if let Some(setting) = existing {
+            ActiveModel::builder()
+                .set_id(setting.id)
+                .set_key(key)
+                .set_value(json)
+                .set_date_modified(now)
+        } else {
+            ActiveModel::builder()
+                .set_key(key)
+                .set_value(json)
+                .set_date_modified(now)
+        };
+
+        builder.save(&self.db).await?;
+        Ok(())
+    }
+}
@@ -1,10 +1,18 @@
 use std::path::Path;
 use std::rc::Rc;
-use slint::{ModelRc, Rgb8Pixel, SharedPixelBuffer, SharedString, ToSharedString, VecModel};
+use slint::{Model, ModelRc, Rgb8Pixel, SharedPixelBuffer, SharedString, ToSharedString, VecModel};
 use crate::display::utils;
-use crate::media_source::media_source::{MediaSourceItem, MediaType};
+use crate::media_source::media_source::{MediaSourceItem, MediaSourceMetadataUpdate, MediaType};
+use crate::media_source::media_source_chapter::MediaSourceChapter;
 use crate::media_source::media_source_picture::MediaSourcePicture;
-use crate::{ SlintMediaSourceChapter, SlintMediaSourceItem, SlintPreferences};
+use crate::media_source::media_source_bookmark::MediaSourceBookmark;
+use crate::media_source::media_source_listening_state::MediaSourceListeningState;
+use crate::time::{format_duration, format_duration_short};
+use std::time::Duration;
+use crate::{ SlintBookmark, SlintEqualizerBand, SlintListeningState, SlintStatsBar, SlintLyricsLine, SlintMediaSourceChapter, SlintMediaSourceItem, SlintPlayerState, SlintPreferences, SlintRepeatMode};
+use crate::player::player_state::PlayerState;
+use crate::player::repeat_mode::RepeatMode;
+use crate::player::equalizer_settings::EqualizerSettings;
 use crate::slint_helpers::load_cover_result::LoadCoverResult;
 
 pub fn sync_preferences(pref: SlintPreferences) {
@@ -18,6 +26,18 @@ pub fn sync_preferences(pref: SlintPreferences) {
     println!("color-scheme: {}", pref.get_color_scheme());
 }
 
+pub fn player_state_to_slint(state: PlayerState) -> SlintPlayerState {
+    match state {
+        PlayerState::Idle => SlintPlayerState::Idle,
+        PlayerState::Loading => SlintPlayerState::Loading,
+        PlayerState::Playing => SlintPlayerState::Playing,
+        PlayerState::Paused => SlintPlayerState::Paused,
+        PlayerState::Buffering => SlintPlayerState::Buffering,
+        PlayerState::Ended => SlintPlayerState::Ended,
+        PlayerState::Error => SlintPlayerState::Error,
+    }
+}
+
 pub fn option_to_slint_string(option: &Option<String>) -> SharedString {
     if option.is_some() {
         option.as_ref().unwrap().to_shared_string()
@@ -74,6 +94,61 @@ pub fn empty_cover_result() -> (slint::Image, LoadCoverResult) {
     )
 }
 
+pub fn rust_bookmarks_to_slint_model(bookmarks: Vec<MediaSourceBookmark>) -> ModelRc<SlintBookmark> {
+    let model = VecModel::<SlintBookmark>::from(
+        bookmarks
+            .into_iter()
+            .map(|bookmark| SlintBookmark {
+                id: bookmark.id.to_shared_string(),
+                item_id: bookmark.item_id.to_shared_string(),
+                position: bookmark.position.as_millis() as i64,
+                position_formatted: format_duration(bookmark.position).to_shared_string(),
+                chapter_name: bookmark.chapter_name.unwrap_or_default().to_shared_string(),
+                note: bookmark.note.unwrap_or_default().to_shared_string(),
+            })
+            .collect::<Vec<_>>(),
+    );
+    ModelRc::from(Rc::new(model))
+}
+
+pub fn equalizer_bands_to_slint_model(equalizer: &EqualizerSettings) -> ModelRc<SlintEqualizerBand> {
+    let model = VecModel::<SlintEqualizerBand>::from(
+        equalizer
+            .custom_bands
+            .iter()
+            .map(|band| SlintEqualizerBand {
+                label: format_frequency(band.frequency).to_shared_string(),
+                gain: band.gain_db,
+            })
+            .collect::<Vec<_>>(),
+    );
+    ModelRc::from(Rc::new(model))
+}
+
+/// Listened times as bars relative to the largest one
+pub fn stats_bars_to_slint_model(entries: Vec<(String, Duration)>) -> ModelRc<SlintStatsBar> {
+    let max = entries.iter().map(|(_, listened)| *listened).max().unwrap_or_default();
+    let model = VecModel::<SlintStatsBar>::from(
+        entries
+            .into_iter()
+            .map(|(label, listened)| SlintStatsBar {
+                label: label.to_shared_string(),
+                value: format_duration_short(listened).to_shared_string(),
+                fraction: if max.is_zero() { 0.0 } else { listened.as_secs_f32() / max.as_secs_f32() },
+            })
+            .collect::<Vec<_>>(),
+    );
+    ModelRc::from(Rc::new(model))
+}
+
+fn format_frequency(frequency: f32) -> String {
+    if frequency >= 1000.0 {
+        format!("{} kHz", (frequency / 100.0).round() / 10.0)
+    } else {
+        format!("{} Hz", frequency.round())
+    }
+}
+
 pub fn rust_items_to_slint_model(
     rust_items: Vec<MediaSourceItem>,
     details: bool,
@@ -94,7 +169,7 @@ pub fn rust_items_to_slint_model(
                     empty_cover_result()
                 };
 
-                let mut slint_chapters_vec = VecModel::default();
+                let slint_chapters_vec = VecModel::default();
                 for chapter in &rust_item.metadata.chapters {
                     let start: i64 = chapter
                         .start
@@ -118,6 +193,30 @@ pub fn rust_items_to_slint_model(
 
                 let chapters_model = ModelRc::new(slint_chapters_vec);
 
+                let slint_lyrics_vec = VecModel::default();
+                let mut lyrics_synced = false;
+                if let Some(lyrics) = &rust_item.metadata.lyrics {
+                    lyrics_synced = lyrics.synced;
+                    for (index, line) in lyrics.lines.iter().enumerate() {
+                        // offset is already applied, so slint only needs to compare with the position
+                        let start: i64 = lyrics.line_start(index).as_millis().try_into().unwrap_or(i64::MAX);
+                        let end: i64 = lyrics.line_end(index).as_millis().try_into().unwrap_or(i64::MAX);
+                        slint_lyrics_vec.push(SlintLyricsLine {
+                            start,
+                            end,
+                            text: line.text.to_shared_string(),
+                        });
+                    }
+                }
+                let lyrics_model = ModelRc::new(slint_lyrics_vec);
+
+                let duration = rust_item.metadata.duration;
+                let progress = match rust_item.progress.state {
+                    MediaSourceListeningState::Finished => 1.0,
+                    _ if duration.is_zero() => 0.0,
+                    _ => rust_item.progress.position.as_secs_f32() / duration.as_secs_f32(),
+                };
+
                 SlintMediaSourceItem {
                     id: rust_item.id.clone().into(),
                     media_type: crate::media_source::utils::convert_media_type_to_int(&rust_item.media_type),
@@ -133,6 +232,10 @@ pub fn rust_items_to_slint_model(
                     has_thumbnail: thumbnail_type != LoadCoverResult::None,
                     thumbnail,
                     chapters: chapters_model,
+                    lyrics_synced,
+                    lyrics: lyrics_model,
+                    listening_state: listening_state_to_slint(rust_item.progress.state),
+                    progress,
                 }
             })
             .collect::<Vec<_>>(),
@@ -140,4 +243,76 @@ pub fn rust_items_to_slint_model(
 
     // Explicitly wrap in ModelRc if needed (usually not)
     ModelRc::from(Rc::new(model))
-}
\ No newline at end of file
+}
+pub fn repeat_mode_to_slint(repeat_mode: RepeatMode) -> SlintRepeatMode {
+    match repeat_mode {
+        RepeatMode::Off => SlintRepeatMode::Off,
+        RepeatMode::One => SlintRepeatMode::One,
+        RepeatMode::All => SlintRepeatMode::All,
+    }
+}
+
+pub fn slint_to_repeat_mode(repeat_mode: SlintRepeatMode) -> RepeatMode {
+    match repeat_mode {
+        SlintRepeatMode::Off => RepeatMode::Off,
+        SlintRepeatMode::One => RepeatMode::One,
+        SlintRepeatMode::All => RepeatMode::All,
+    }
+}
+
+pub fn listening_state_to_slint(state: MediaSourceListeningState) -> SlintListeningState {
+    match state {
+        MediaSourceListeningState::New => SlintListeningState::New,
+        MediaSourceListeningState::InProgress => SlintListeningState::InProgress,
+        MediaSourceListeningState::Finished => SlintListeningState::Finished,
+    }
+}
+
+pub fn slint_to_listening_state(state: SlintListeningState) -> MediaSourceListeningState {
+    match state {
+        SlintListeningState::New => MediaSourceListeningState::New,
+        SlintListeningState::InProgress => MediaSourceListeningState::InProgress,
+        SlintListeningState::Finished => MediaSourceListeningState::Finished,
+    }
+}
+
+/// Builds an update with the fields of the metadata editor that differ from the item,
+/// unchanged fields are None so that they are not written to the file
+pub fn slint_metadata_update(
+    item: &SlintMediaSourceItem,
+    title: SharedString,
+    artist: SharedString,
+    series: SharedString,
+    part: SharedString,
+    genre: SharedString,
+    chapter_names: ModelRc<SharedString>,
+) -> MediaSourceMetadataUpdate {
+    let changed = |edited: SharedString, current: &SharedString| {
+        if edited == *current { None } else { Some(edited.to_string()) }
+    };
+
+    let chapters_changed = chapter_names.row_count() == item.chapters.row_count()
+        && chapter_names.iter().zip(item.chapters.iter()).any(|(name, chapter)| name != chapter.name);
+    let chapters = chapters_changed.then(|| {
+        chapter_names
+            .iter()
+            .zip(item.chapters.iter())
+            .map(|(name, chapter)| {
+                MediaSourceChapter::new(
+                    name.to_string(),
+                    Duration::from_millis(chapter.start.max(0) as u64),
+                    Duration::from_millis(chapter.duration.max(0) as u64),
+                )
+            })
+            .collect()
+    });
+
+    MediaSourceMetadataUpdate {
+        title: changed(title, &item.name),
+        artist: changed(artist, &item.artist),
+        series: changed(series, &item.series),
+        part: changed(part, &item.part),
+        genre: changed(genre, &item.genre),
+        chapters,
+    }
+}
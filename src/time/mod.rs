@@ -7,4 +7,17 @@ pub fn format_duration(duration: Duration) -> String {
     let m = (secs / 60) % 60;
     let s = secs % 60;
     format!("{:0>2}:{:0>2}:{:0>2}", h, m, s)
-}
\ No newline at end of file
+}
+
+/// mm:ss for durations below one hour, h:mm:ss otherwise
+pub fn format_duration_short(duration: Duration) -> String {
+    let secs = duration.as_millis() / 1000;
+    let h = secs / (60 * 60);
+    let m = (secs / 60) % 60;
+    let s = secs % 60;
+    if h > 0 {
+        format!("{}:{:0>2}:{:0>2}", h, m, s)
+    } else {
+        format!("{:0>2}:{:0>2}", m, s)
+    }
+}
@@ -0,0 +1,289 @@
+// every test binary compiles its own copy and uses only some of the helpers
+#![allow(dead_code)]
+
+use rust_slint_riscv64_musl_demo::audio::output_device_provider::OutputDeviceProvider;
+use rust_slint_riscv64_musl_demo::audio::wav_writer::WavWriter;
+use rust_slint_riscv64_musl_demo::media_source::media_source::{
+    MediaSource, MediaSourceBookmark, MediaSourceCommand, MediaSourceError, MediaSourceEvent, MediaSourceItem,
+    MediaSourceListeningSession, MediaSourceListeningState, MediaSourceMetadataUpdate, MediaSourceProgressReason,
+    MediaType,
+};
+use rust_slint_riscv64_musl_demo::media_source::media_source_listening_progress::MediaSourceListeningProgress;
+use rust_slint_riscv64_musl_demo::media_source::media_source_metadata::MediaSourceMetadata;
+use rust_slint_riscv64_musl_demo::migrator::Migrator;
+use rust_slint_riscv64_musl_demo::player::fade_settings::FadeSettings;
+use rust_slint_riscv64_musl_demo::player::player::{Player, PlayerCommand, PlayerEvent};
+use rust_slint_riscv64_musl_demo::settings::settings_manager::SettingsManager;
+use sea_orm::{Database, DatabaseConnection};
+use sea_orm_migration::MigratorTrait;
+use std::collections::HashMap;
+use std::f32::consts::PI;
+use std::fs;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+use tokio::sync::mpsc::{unbounded_channel, UnboundedReceiver, UnboundedSender};
+
+const TEST_WAV_SAMPLE_RATE: u32 = 8000;
+const EVENT_TIMEOUT: Duration = Duration::from_secs(10);
+const EVENT_POLL_INTERVAL: Duration = Duration::from_millis(2);
+// the player is considered blocked (e.g. by a seek) if it did not answer within this time
+const EVENT_GRACE_PERIOD: Duration = Duration::from_millis(30);
+
+pub async fn connect_memory_db() -> DatabaseConnection {
+    let db = Database::connect("sqlite::memory:").await.unwrap();
+    Migrator::up(&db, None).await.unwrap();
+    db
+}
+
+/// Creates an empty directory below the system temp dir, the name has to be unique per test
+pub fn temp_dir(name: &str) -> std::path::PathBuf {
+    let dir = std::env::temp_dir().join(format!("player-test-{}", name));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+/// Writes a mono sine tone, silence would be skipped by some players
+pub fn write_wav(path: &Path, duration: Duration) {
+    write_wav_with_gap(path, duration, Duration::ZERO, Duration::ZERO);
+}
+
+/// Writes a mono sine tone with `gap_length` of digital silence starting at `gap_start`
+pub fn write_wav_with_gap(path: &Path, duration: Duration, gap_start: Duration, gap_length: Duration) {
+    let mut writer = WavWriter::create(path, 1, TEST_WAV_SAMPLE_RATE).unwrap();
+    let to_samples = |d: Duration| (d.as_secs_f64() * TEST_WAV_SAMPLE_RATE as f64) as usize;
+    let gap = to_samples(gap_start)..to_samples(gap_start + gap_length);
+    for i in 0..to_samples(duration) {
+        let sample = if gap.contains(&i) {
+            0.0
+        } else {
+            0.3 * (2.0 * PI * 440.0 * i as f32 / TEST_WAV_SAMPLE_RATE as f32).sin()
+        };
+        writer.write_sample(sample).unwrap();
+    }
+    writer.finish().unwrap();
+}
+
+fn id3v2_text_frame(id: &[u8; 4], text: &str) -> Vec<u8> {
+    let mut frame = id.to_vec();
+    frame.extend_from_slice(&(text.len() as u32 + 1).to_be_bytes());
+    frame.extend_from_slice(&[0, 0, 0]);
+    frame.extend_from_slice(text.as_bytes());
+    frame
+}
+
+/// Writes an mp3 of silent MPEG-1 layer 3 frames (128 kbit/s, 44.1 kHz) with an
+/// ID3v2.3 tag (title, artist and the genre Audiobook)
+pub fn write_tagged_mp3(path: &Path) {
+    let mut frames = id3v2_text_frame(b"TIT2", "Title");
+    frames.extend(id3v2_text_frame(b"TPE1", "Artist"));
+    frames.extend(id3v2_text_frame(b"TCON", "Audiobook"));
+
+    let size = frames.len() as u32;
+    let mut data = b"ID3\x03\x00\x00".to_vec();
+    data.extend_from_slice(&[(size >> 21) as u8 & 0x7f, (size >> 14) as u8 & 0x7f, (size >> 7) as u8 & 0x7f, size as u8 & 0x7f]);
+    data.extend(frames);
+    for _ in 0..20 {
+        data.extend_from_slice(&[0xff, 0xfb, 0x90, 0x00]);
+        data.extend(std::iter::repeat_n(0u8, 413));
+    }
+    fs::write(path, data).unwrap();
+}
+
+pub fn test_item(id: &str, path: &Path, duration: Duration) -> MediaSourceItem {
+    let mut metadata = MediaSourceMetadata::new(None, Some(id.to_string()), None, None, None, None, None, None, vec![]);
+    metadata.duration = duration;
+    MediaSourceItem {
+        id: id.to_string(),
+        location: path.to_str().unwrap().to_string(),
+        title: id.to_string(),
+        media_type: MediaType::Unspecified,
+        metadata,
+        progress: MediaSourceListeningProgress::default(),
+    }
+}
+
+/// Media source with a fixed set of items that only remembers the last stored
+/// listening progress of each item
+pub struct TestMediaSource {
+    items: HashMap<String, MediaSourceItem>,
+    saved_positions: Arc<Mutex<HashMap<String, Duration>>>,
+}
+
+impl TestMediaSource {
+    pub fn new(items: Vec<MediaSourceItem>) -> Self {
+        Self {
+            items: items.into_iter().map(|item| (item.id.clone(), item)).collect(),
+            saved_positions: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    /// Positions stored by update_listening_progress by item id, shared with the player
+    pub fn saved_positions(&self) -> Arc<Mutex<HashMap<String, Duration>>> {
+        self.saved_positions.clone()
+    }
+}
+
+#[async_trait::async_trait]
+impl MediaSource for TestMediaSource {
+    fn id(&self) -> String {
+        "test".to_string()
+    }
+
+    async fn filter(&self, _query: &str) -> Vec<MediaSourceItem> {
+        self.items.values().cloned().collect()
+    }
+
+    async fn find(&self, id: &str) -> Option<MediaSourceItem> {
+        self.items.get(id).cloned()
+    }
+
+    async fn series_items(&self, _series: &str) -> Vec<MediaSourceItem> {
+        vec![]
+    }
+
+    async fn update_metadata(&self, id: &str, _update: MediaSourceMetadataUpdate) -> Result<MediaSourceItem, MediaSourceError> {
+        Err(MediaSourceError::Unsupported(id.to_string()))
+    }
+
+    async fn add_progress_history(
+        &self,
+        _id: &str,
+        _session_key: &str,
+        _reason: MediaSourceProgressReason,
+        _position: Duration,
+        _previous_position: Duration,
+    ) -> Result<(), MediaSourceError> {
+        Ok(())
+    }
+
+    async fn add_listening_session(&self, _session: MediaSourceListeningSession) -> Result<(), MediaSourceError> {
+        Ok(())
+    }
+
+    async fn update_listening_progress(
+        &self,
+        id: &str,
+        position: Duration,
+        _state: MediaSourceListeningState,
+    ) -> Result<(), MediaSourceError> {
+        self.saved_positions.lock().unwrap().insert(id.to_string(), position);
+        Ok(())
+    }
+
+    async fn add_bookmark(&self, bookmark: MediaSourceBookmark) -> Result<MediaSourceBookmark, MediaSourceError> {
+        Ok(bookmark)
+    }
+
+    async fn bookmarks(&self, _id: &str) -> Vec<MediaSourceBookmark> {
+        vec![]
+    }
+
+    async fn remove_bookmark(&self, _bookmark_id: &str) -> Result<(), MediaSourceError> {
+        Ok(())
+    }
+
+    async fn run(self, _cmd_rx: UnboundedReceiver<MediaSourceCommand>, _evt_tx: UnboundedSender<MediaSourceEvent>) {}
+}
+
+/// Runs a player on its own thread, sink operations like seeking block the
+/// player until the output consumed some audio
+pub struct TestPlayer {
+    cmd_tx: UnboundedSender<PlayerCommand>,
+    evt_rx: UnboundedReceiver<PlayerEvent>,
+    // called while waiting for events, e.g. to advance a render clock
+    on_wait: Box<dyn Fn()>,
+}
+
+impl TestPlayer {
+    pub fn start(
+        media_source: TestMediaSource,
+        device_provider: Box<dyn OutputDeviceProvider + Send>,
+        preferred_device_name: &str,
+        fallback_device_name: &str,
+        on_wait: impl Fn() + 'static,
+    ) -> Self {
+        let (cmd_tx, cmd_rx) = unbounded_channel();
+        let (evt_tx, evt_rx) = unbounded_channel();
+        let preferred_device_name = preferred_device_name.to_string();
+        let fallback_device_name = fallback_device_name.to_string();
+        thread::spawn(move || {
+            let runtime = tokio::runtime::Builder::new_current_thread().enable_all().build().unwrap();
+            runtime.block_on(async move {
+                let settings = SettingsManager::new(connect_memory_db().await);
+                let mut player = Player::new(
+                    Arc::new(media_source),
+                    settings,
+                    device_provider,
+                    preferred_device_name,
+                    fallback_device_name,
+                );
+                player.run(cmd_rx, evt_tx).await;
+            });
+        });
+
+        let test_player = Self {
+            cmd_tx,
+            evt_rx,
+            on_wait: Box::new(on_wait),
+        };
+        // ramps would make the positions depend on the wall clock
+        test_player.send(PlayerCommand::UpdateFades(FadeSettings {
+            pause_resume: Duration::ZERO,
+            seek: Duration::ZERO,
+            item_switch: Duration::ZERO,
+        }));
+        test_player
+    }
+
+    pub fn send(&self, cmd: PlayerCommand) {
+        self.cmd_tx.send(cmd).unwrap();
+    }
+
+    /// Collects events until one matches, panics after a timeout. `on_wait` is called
+    /// whenever the player did not answer for a while
+    pub fn wait_for(&mut self, mut matches: impl FnMut(&PlayerEvent) -> bool) -> Vec<PlayerEvent> {
+        let started = Instant::now();
+        let mut waiting_since = Instant::now();
+        let mut events = vec![];
+        loop {
+            while let Ok(event) = self.evt_rx.try_recv() {
+                let found = matches(&event);
+                events.push(event);
+                if found {
+                    return events;
+                }
+            }
+            assert!(started.elapsed() < EVENT_TIMEOUT, "no matching event, got {:?}", events);
+            if waiting_since.elapsed() >= EVENT_GRACE_PERIOD {
+                (self.on_wait)();
+                waiting_since = Instant::now();
+            }
+            thread::sleep(EVENT_POLL_INTERVAL);
+        }
+    }
+
+    /// Waits until all commands sent so far have been handled
+    pub fn sync(&mut self) -> Vec<PlayerEvent> {
+        self.send(PlayerCommand::ListOutputDevices());
+        self.wait_for(|event| matches!(event, PlayerEvent::OutputDevices(_)))
+    }
+
+    /// Item id and position, reported right after the next command
+    pub fn position(&mut self) -> (String, Duration) {
+        self.sync();
+        self.send(PlayerCommand::SetUiVisible(true));
+        let events = self.wait_for(|event| matches!(event, PlayerEvent::Position(_, _)));
+        match events.last() {
+            Some(PlayerEvent::Position(id, position)) => (id.clone(), position.position),
+            _ => unreachable!(),
+        }
+    }
+}
+
+pub fn assert_near(actual: Duration, expected: Duration, tolerance: Duration) {
+    let difference = actual.abs_diff(expected);
+    assert!(difference <= tolerance, "expected {:?} (± {:?}), got {:?}", expected, tolerance, actual);
+}
@@ -0,0 +1,52 @@
+mod common;
+
+use rust_slint_riscv64_musl_demo::entity::item;
+use rust_slint_riscv64_musl_demo::media_source::exclusion_rules::ExclusionRules;
+use rust_slint_riscv64_musl_demo::media_source::file_media_source::FileMediaSource;
+use rust_slint_riscv64_musl_demo::media_source::library_root::LibraryRoot;
+use rust_slint_riscv64_musl_demo::settings::settings_manager::SettingsManager;
+use sea_orm::{DatabaseConnection, EntityTrait, QueryOrder};
+use std::fs;
+
+async fn visible_locations(db: &DatabaseConnection) -> Vec<String> {
+    item::Entity::find()
+        .order_by_asc(item::Column::Location)
+        .all(db)
+        .await
+        .unwrap()
+        .into_iter()
+        .filter(|i| !i.is_missing)
+        .map(|i| i.location)
+        .collect()
+}
+
+#[tokio::test]
+async fn changed_exclusion_rules_hide_indexed_items() {
+    let root = common::temp_dir("exclusion-rules");
+    for dir in ["books", "drafts", "@eaDir"] {
+        fs::create_dir_all(root.join(dir)).unwrap();
+        // not decodable, the items are indexed with empty metadata
+        fs::write(root.join(dir).join("a.mp3"), b"not an mp3").unwrap();
+    }
+
+    let db = common::connect_memory_db().await;
+    let library_root = LibraryRoot::new(root.to_str().unwrap().to_string(), false);
+    let source = FileMediaSource::new(db.clone(), SettingsManager::new(db.clone()), vec![library_root], String::new());
+
+    source.scan_media().await;
+    assert_eq!(visible_locations(&db).await, vec!["books/a.mp3", "drafts/a.mp3"]);
+
+    let mut rules = ExclusionRules::defaults();
+    rules.exclude_globs.push("drafts".to_string());
+    source.update_exclusion_rules(rules).await.unwrap();
+    source.scan_media().await;
+    assert_eq!(visible_locations(&db).await, vec!["books/a.mp3"]);
+
+    // hidden items keep their row and come back once the rule is removed
+    source.update_exclusion_rules(ExclusionRules::defaults()).await.unwrap();
+    source.scan_media().await;
+    assert_eq!(visible_locations(&db).await, vec!["books/a.mp3", "drafts/a.mp3"]);
+    assert_eq!(item::Entity::find().all(&db).await.unwrap().len(), 2);
+
+    fs::remove_dir_all(root).unwrap();
+}
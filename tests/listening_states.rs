@@ -0,0 +1,67 @@
+mod common;
+
+use chrono::Utc;
+use rust_slint_riscv64_musl_demo::entity::item;
+use rust_slint_riscv64_musl_demo::entity::item::MediaType;
+use rust_slint_riscv64_musl_demo::media_source::file_media_source::FileMediaSource;
+use rust_slint_riscv64_musl_demo::media_source::media_source::{MediaSource, MediaSourceListeningState};
+use rust_slint_riscv64_musl_demo::settings::settings_manager::SettingsManager;
+use sea_orm::{ActiveModelTrait, DatabaseConnection, Set};
+use std::time::Duration;
+
+async fn insert_item(db: &DatabaseConnection, location: &str, is_offline: bool, is_missing: bool) -> String {
+    let model = item::ActiveModel {
+        file_id: Set(location.to_string()),
+        media_type: Set(MediaType::Audiobook),
+        cover_hash: Set(String::new()),
+        library_root: Set("/media".to_string()),
+        location: Set(location.to_string()),
+        is_offline: Set(is_offline),
+        is_missing: Set(is_missing),
+        duration: Set(60 * 60 * 1000),
+        last_scan_random_key: Set(String::new()),
+        date_modified: Set(Utc::now()),
+        ..Default::default()
+    }
+    .insert(db)
+    .await
+    .unwrap();
+    model.id.to_string()
+}
+
+#[tokio::test]
+async fn continue_listening_skips_unavailable_items() {
+    let db = common::connect_memory_db().await;
+    let source = FileMediaSource::new(db.clone(), SettingsManager::new(db.clone()), vec![], String::new());
+
+    let available = insert_item(&db, "available.mp3", false, false).await;
+    let offline = insert_item(&db, "offline.mp3", true, false).await;
+    let missing = insert_item(&db, "missing.mp3", false, true).await;
+    for id in [&available, &offline, &missing] {
+        source.set_listening_state(id, MediaSourceListeningState::InProgress).await.unwrap();
+    }
+
+    let ids: Vec<String> = source.continue_listening().await.into_iter().map(|i| i.id).collect();
+    assert_eq!(ids, vec![available]);
+}
+
+#[tokio::test]
+async fn sessions_keep_a_manually_finished_state() {
+    let db = common::connect_memory_db().await;
+    let source = FileMediaSource::new(db.clone(), SettingsManager::new(db.clone()), vec![], String::new());
+    let id = insert_item(&db, "book.mp3", false, false).await;
+    let state_of = async |source: &FileMediaSource| source.find(&id).await.unwrap().progress;
+
+    source.set_listening_state(&id, MediaSourceListeningState::Finished).await.unwrap();
+    // listening to a chapter again
+    let position = Duration::from_secs(10 * 60);
+    source.update_listening_progress(&id, position, MediaSourceListeningState::InProgress).await.unwrap();
+    let progress = state_of(&source).await;
+    assert_eq!(progress.state, MediaSourceListeningState::Finished);
+    assert_eq!(progress.position, position);
+
+    // starting over is a manual choice, too, the following sessions apply again
+    source.set_listening_state(&id, MediaSourceListeningState::New).await.unwrap();
+    source.update_listening_progress(&id, position, MediaSourceListeningState::InProgress).await.unwrap();
+    assert_eq!(state_of(&source).await.state, MediaSourceListeningState::InProgress);
+}
@@ -0,0 +1,95 @@
+mod common;
+
+use common::{assert_near, test_item, write_wav, TestMediaSource, TestPlayer};
+use rust_slint_riscv64_musl_demo::audio::fake_device_provider::FakeDeviceProvider;
+use rust_slint_riscv64_musl_demo::player::player::{PlayerCommand, PlayerEvent, PlayerState};
+use std::thread;
+use std::time::Duration;
+
+const ITEM_DURATION: Duration = Duration::from_secs(30);
+// the fake devices play in real time
+const TOLERANCE: Duration = Duration::from_millis(300);
+
+fn start_player(name: &str, devices: &[&str], preferred: &str, fallback: &str) -> (TestPlayer, FakeDeviceProvider) {
+    let path = common::temp_dir(name).join("a.wav");
+    write_wav(&path, ITEM_DURATION);
+    let provider = FakeDeviceProvider::new(devices.iter().map(|d| d.to_string()).collect());
+    let player = TestPlayer::start(
+        TestMediaSource::new(vec![test_item("a", &path, ITEM_DURATION)]),
+        Box::new(provider.clone()),
+        preferred,
+        fallback,
+        || {},
+    );
+    (player, provider)
+}
+
+fn wait_for_device(player: &mut TestPlayer, expected: Option<&str>) -> Vec<PlayerEvent> {
+    player.wait_for(|event| matches!(event, PlayerEvent::OutputDeviceChanged(name) if name.as_deref() == expected))
+}
+
+fn play(player: &mut TestPlayer) {
+    player.send(PlayerCommand::PlayMedia("a".to_string()));
+    player.wait_for(|event| matches!(event, PlayerEvent::State(id, PlayerState::Playing) if id == "a"));
+}
+
+#[test]
+fn preferred_device_beats_the_fallback() {
+    let (mut player, _) = start_player("devices-preferred", &["other", "speaker", "usb"], "usb", "speaker");
+    wait_for_device(&mut player, Some("usb"));
+
+    let (mut player, _) = start_player("devices-fallback", &["other", "speaker"], "usb", "speaker");
+    wait_for_device(&mut player, Some("speaker"));
+}
+
+#[test]
+fn unplugging_pauses_at_the_current_position() {
+    let (mut player, provider) = start_player("devices-unplug", &["usb"], "usb", "speaker");
+    wait_for_device(&mut player, Some("usb"));
+    play(&mut player);
+    thread::sleep(Duration::from_secs(1));
+    let (_, position) = player.position();
+
+    provider.unplug("usb");
+    let events = wait_for_device(&mut player, None);
+    assert!(events.iter().any(|event| matches!(event, PlayerEvent::State(id, PlayerState::Paused) if id == "a")));
+
+    // nothing plays without a device, the position is kept
+    thread::sleep(Duration::from_millis(500));
+    let (id, saved_position) = player.position();
+    assert_eq!(id, "a");
+    assert_near(saved_position, position, TOLERANCE);
+}
+
+#[test]
+fn plugging_reconnects_and_restores_the_position() {
+    let (mut player, provider) = start_player("devices-plug", &["usb"], "usb", "speaker");
+    wait_for_device(&mut player, Some("usb"));
+    play(&mut player);
+    thread::sleep(Duration::from_secs(1));
+    provider.unplug("usb");
+    wait_for_device(&mut player, None);
+    let (_, saved_position) = player.position();
+
+    // devices are polled, so this takes up to the device check interval
+    provider.plug("speaker");
+    wait_for_device(&mut player, Some("speaker"));
+    let (id, position) = player.position();
+    assert_eq!(id, "a");
+    assert_near(position, saved_position, TOLERANCE);
+
+    // a lost device stays paused after reconnecting
+    thread::sleep(Duration::from_millis(500));
+    let (_, paused_position) = player.position();
+    assert_near(paused_position, saved_position, TOLERANCE);
+
+    // switching back to the preferred device keeps playing
+    player.send(PlayerCommand::Play());
+    player.wait_for(|event| matches!(event, PlayerEvent::State(_, PlayerState::Playing)));
+    provider.plug("usb");
+    let events = wait_for_device(&mut player, Some("usb"));
+    assert!(!events.iter().any(|event| matches!(event, PlayerEvent::State(_, PlayerState::Paused))));
+    thread::sleep(Duration::from_millis(500));
+    let (_, position) = player.position();
+    assert!(position > paused_position + Duration::from_millis(300));
+}
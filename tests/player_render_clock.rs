@@ -0,0 +1,249 @@
+mod common;
+
+use common::{assert_near, test_item, write_wav, write_wav_with_gap, TestMediaSource, TestPlayer};
+use rust_slint_riscv64_musl_demo::audio::render_clock::RenderClock;
+use rust_slint_riscv64_musl_demo::audio::rendering_device_provider::{RenderingDeviceProvider, NULL_DEVICE_NAME};
+use rust_slint_riscv64_musl_demo::media_source::media_source::MediaType;
+use rust_slint_riscv64_musl_demo::media_source::media_source_chapter::MediaSourceChapter;
+use rust_slint_riscv64_musl_demo::player::auto_rewind::AutoRewind;
+use rust_slint_riscv64_musl_demo::player::auto_rewind_step::AutoRewindStep;
+use rust_slint_riscv64_musl_demo::player::player::{PlayerCommand, PlayerEvent, PlayerState};
+use rust_slint_riscv64_musl_demo::player::silence_skip_settings::SilenceSkipSettings;
+use std::thread;
+use std::time::Duration;
+
+const ITEM_DURATION: Duration = Duration::from_secs(10);
+// blocked seeks and item switches are answered after one more chunk of audio
+const NUDGE: Duration = Duration::from_millis(10);
+const TOLERANCE: Duration = Duration::from_millis(60);
+// longer than the tick interval while the position is shown
+const TICK_WAIT: Duration = Duration::from_millis(1100);
+
+fn start_player(name: &str, ids: &[&str]) -> (TestPlayer, RenderClock) {
+    let dir = common::temp_dir(name);
+    let items = ids
+        .iter()
+        .map(|id| {
+            let path = dir.join(format!("{}.wav", id));
+            write_wav(&path, ITEM_DURATION);
+            test_item(id, &path, ITEM_DURATION)
+        })
+        .collect();
+    start_queue(TestMediaSource::new(items), ids, |_| {})
+}
+
+/// Plays `ids` once `before_play` configured the player
+fn start_queue(media_source: TestMediaSource, ids: &[&str], before_play: impl FnOnce(&TestPlayer)) -> (TestPlayer, RenderClock) {
+    let clock = RenderClock::new();
+    let nudge_clock = clock.clone();
+    let mut player = TestPlayer::start(
+        media_source,
+        Box::new(RenderingDeviceProvider::null(clock.clone())),
+        NULL_DEVICE_NAME,
+        NULL_DEVICE_NAME,
+        move || nudge_clock.advance(NUDGE),
+    );
+    before_play(&player);
+    player.send(PlayerCommand::PlayQueue(ids.iter().map(|id| id.to_string()).collect(), 0));
+    let first = ids[0].to_string();
+    player.wait_for(|event| matches!(event, PlayerEvent::State(id, PlayerState::Playing) if *id == first));
+    (player, clock)
+}
+
+fn wait_for_playing(player: &mut TestPlayer, expected_id: &str) {
+    player.wait_for(|event| matches!(event, PlayerEvent::State(id, PlayerState::Playing) if id == expected_id));
+}
+
+#[test]
+fn position_follows_the_render_clock() {
+    let (mut player, clock) = start_player("render-clock-position", &["a"]);
+    let (_, start) = player.position();
+
+    clock.advance_and_wait(Duration::from_secs(2));
+    let (id, position) = player.position();
+    assert_eq!(id, "a");
+    assert_near(position, start + Duration::from_secs(2), TOLERANCE);
+
+    // nothing is consumed without the clock
+    thread::sleep(Duration::from_millis(200));
+    let (_, unchanged) = player.position();
+    assert_near(unchanged, position, TOLERANCE);
+}
+
+#[test]
+fn seek_relative() {
+    let (mut player, clock) = start_player("render-clock-seek", &["a"]);
+    clock.advance_and_wait(Duration::from_secs(2));
+
+    player.send(PlayerCommand::SeekRelative(3000));
+    let (_, position) = player.position();
+    assert_near(position, Duration::from_secs(5), TOLERANCE);
+
+    player.send(PlayerCommand::SeekRelative(-1500));
+    let (_, position) = player.position();
+    assert_near(position, Duration::from_millis(3500), TOLERANCE);
+
+    // seeking before the start stops at the start
+    player.send(PlayerCommand::SeekRelative(-60_000));
+    let (id, position) = player.position();
+    assert_eq!(id, "a");
+    assert_near(position, Duration::ZERO, TOLERANCE);
+}
+
+#[test]
+fn next_and_previous() {
+    let (mut player, clock) = start_player("render-clock-next-previous", &["a", "b", "c"]);
+    clock.advance_and_wait(Duration::from_secs(2));
+
+    player.send(PlayerCommand::Next());
+    wait_for_playing(&mut player, "b");
+    let (id, position) = player.position();
+    assert_eq!(id, "b");
+    assert_near(position, Duration::ZERO, TOLERANCE);
+
+    // close to the start of an item previous switches to the previous item
+    clock.advance_and_wait(Duration::from_secs(1));
+    player.send(PlayerCommand::Previous());
+    wait_for_playing(&mut player, "a");
+    let (id, position) = player.position();
+    assert_eq!(id, "a");
+    assert_near(position, Duration::ZERO, TOLERANCE);
+
+    // later it restarts the current item
+    clock.advance_and_wait(Duration::from_secs(5));
+    let (_, position) = player.position();
+    assert_near(position, Duration::from_secs(5), TOLERANCE);
+    player.send(PlayerCommand::Previous());
+    let (id, position) = player.position();
+    assert_eq!(id, "a");
+    assert_near(position, Duration::ZERO, TOLERANCE);
+
+    player.send(PlayerCommand::Next());
+    player.send(PlayerCommand::Next());
+    wait_for_playing(&mut player, "c");
+    let (id, _) = player.position();
+    assert_eq!(id, "c");
+}
+
+#[test]
+fn silence_skip_keeps_the_file_timeline() {
+    let gap_start = Duration::from_secs(2);
+    let gap_length = Duration::from_secs(4);
+    let min_silence = SilenceSkipSettings::defaults().min_silence;
+
+    let dir = common::temp_dir("render-clock-silence-skip");
+    let path = dir.join("a.wav");
+    write_wav_with_gap(&path, ITEM_DURATION, gap_start, gap_length);
+    let mut item = test_item("a", &path, ITEM_DURATION);
+    item.media_type = MediaType::Audiobook;
+    let media_source = TestMediaSource::new(vec![item]);
+    let saved_positions = media_source.saved_positions();
+
+    let (mut player, clock) = start_queue(media_source, &["a"], |player| {
+        player.send(PlayerCommand::UpdateSilenceSkip(SilenceSkipSettings {
+            enabled: true,
+            ..SilenceSkipSettings::defaults()
+        }));
+    });
+    let (_, start) = player.position();
+
+    // the tone before the gap plays in real time
+    clock.advance_and_wait(gap_start - start);
+    let (_, position) = player.position();
+    assert_near(position, gap_start, TOLERANCE);
+
+    // the gap is shortened to the minimum silence, the skipped part still counts
+    // for the position in the file
+    let rendered = min_silence + Duration::from_secs(1);
+    clock.advance_and_wait(rendered);
+    let expected = gap_start + gap_length + Duration::from_secs(1);
+    let (_, position) = player.position();
+    assert_near(position, expected, TOLERANCE);
+
+    player.send(PlayerCommand::Pause());
+    let (_, position) = player.position();
+    assert_near(position, expected, TOLERANCE);
+    let saved = saved_positions.lock().unwrap().get("a").copied().unwrap();
+    assert_near(saved, expected, TOLERANCE);
+}
+
+#[test]
+fn crossfade_starts_the_next_item_after_the_overlap() {
+    let crossfade = Duration::from_secs(2);
+    let dir = common::temp_dir("render-clock-crossfade");
+    let items = ["a", "b"]
+        .iter()
+        .map(|id| {
+            let path = dir.join(format!("{}.wav", id));
+            write_wav(&path, ITEM_DURATION);
+            let mut item = test_item(id, &path, ITEM_DURATION);
+            item.media_type = MediaType::Music;
+            item
+        })
+        .collect();
+    let (mut player, clock) = start_queue(TestMediaSource::new(items), &["a", "b"], |player| {
+        player.send(PlayerCommand::SetCrossfade(crossfade));
+    });
+
+    // the next item is prepared and handed over by the ticks, which follow the wall clock
+    let (_, position) = player.position();
+    clock.advance_and_wait(ITEM_DURATION - crossfade - Duration::from_secs(1) - position);
+    thread::sleep(TICK_WAIT);
+    let (id, _) = player.position();
+    assert_eq!(id, "a");
+
+    // a ends, b plays on its own for half a second
+    let (_, position) = player.position();
+    clock.advance_and_wait(ITEM_DURATION - position + Duration::from_millis(500));
+    thread::sleep(TICK_WAIT);
+    wait_for_playing(&mut player, "b");
+
+    // the overlap already played counts for the position of b
+    let (id, position) = player.position();
+    assert_eq!(id, "b");
+    assert_near(position, crossfade + Duration::from_millis(500), TOLERANCE);
+    clock.advance_and_wait(Duration::from_secs(1));
+    let (_, position) = player.position();
+    assert_near(position, crossfade + Duration::from_millis(1500), TOLERANCE);
+}
+
+/// Position right after resuming
+fn pause_and_resume(player: &mut TestPlayer) -> Duration {
+    player.send(PlayerCommand::Pause());
+    player.send(PlayerCommand::Play());
+    player.position().1
+}
+
+#[test]
+fn auto_rewind_stops_at_the_chapter_start() {
+    let dir = common::temp_dir("render-clock-auto-rewind");
+    let path = dir.join("a.wav");
+    write_wav(&path, ITEM_DURATION);
+    let mut item = test_item("a", &path, ITEM_DURATION);
+    item.metadata.chapters = vec![
+        MediaSourceChapter::new("one".to_string(), Duration::ZERO, Duration::from_secs(4)),
+        MediaSourceChapter::new("two".to_string(), Duration::from_secs(4), ITEM_DURATION - Duration::from_secs(4)),
+    ];
+    let rewind_after_any_pause = |rewind: Duration| {
+        PlayerCommand::UpdateAutoRewind(AutoRewind {
+            enabled: true,
+            steps: vec![AutoRewindStep::new(Duration::ZERO, rewind)],
+        })
+    };
+    let (mut player, clock) = start_queue(TestMediaSource::new(vec![item]), &["a"], |player| {
+        player.send(rewind_after_any_pause(Duration::from_secs(30)));
+    });
+    // within the first chapter the rewind stops at the start of the item
+    let (_, position) = player.position();
+    clock.advance_and_wait(Duration::from_secs(2) - position);
+    assert_near(pause_and_resume(&mut player), Duration::ZERO, TOLERANCE);
+
+    // later on at the start of the current chapter
+    clock.advance_and_wait(Duration::from_secs(5));
+    assert_near(pause_and_resume(&mut player), Duration::from_secs(4), TOLERANCE);
+
+    // shorter rewinds stay within the chapter
+    player.send(rewind_after_any_pause(Duration::from_millis(500)));
+    clock.advance_and_wait(Duration::from_secs(3));
+    assert_near(pause_and_resume(&mut player), Duration::from_millis(6500), TOLERANCE);
+}
@@ -0,0 +1,77 @@
+mod common;
+
+use chrono::Utc;
+use rust_slint_riscv64_musl_demo::entity::item;
+use rust_slint_riscv64_musl_demo::entity::item::MediaType;
+use rust_slint_riscv64_musl_demo::entity::items_metadata;
+use rust_slint_riscv64_musl_demo::entity::items_metadata::TagField;
+use rust_slint_riscv64_musl_demo::media_source::file_media_source::FileMediaSource;
+use rust_slint_riscv64_musl_demo::media_source::media_type;
+use rust_slint_riscv64_musl_demo::media_source::media_type_rule::MediaTypeRule;
+use rust_slint_riscv64_musl_demo::settings::settings_manager::SettingsManager;
+use sea_orm::{ActiveModelTrait, DatabaseConnection, EntityTrait, Set};
+
+async fn insert_item(db: &DatabaseConnection, location: &str, genre: Option<&str>, duration_ms: i64) -> i32 {
+    let model = item::ActiveModel {
+        file_id: Set(location.to_string()),
+        media_type: Set(MediaType::Unspecified),
+        cover_hash: Set(String::new()),
+        library_root: Set("/media".to_string()),
+        location: Set(location.to_string()),
+        is_offline: Set(false),
+        duration: Set(duration_ms),
+        last_scan_random_key: Set(String::new()),
+        date_modified: Set(Utc::now()),
+        ..Default::default()
+    }
+    .insert(db)
+    .await
+    .unwrap();
+
+    if let Some(genre) = genre {
+        items_metadata::ActiveModel {
+            item_id: Set(model.id),
+            tag_field: Set(TagField::Genre),
+            value: Set(genre.to_string()),
+            date_modified: Set(Utc::now()),
+            ..Default::default()
+        }
+        .insert(db)
+        .await
+        .unwrap();
+    }
+    model.id
+}
+
+async fn media_type_of(db: &DatabaseConnection, id: i32) -> MediaType {
+    item::Entity::find_by_id(id).one(db).await.unwrap().unwrap().media_type
+}
+
+#[tokio::test]
+async fn reclassify_applies_the_rules_to_stored_items() {
+    let db = common::connect_memory_db().await;
+    let source = FileMediaSource::new(db.clone(), SettingsManager::new(db.clone()), vec![], String::new());
+
+    let book = insert_item(&db, "audiobooks/book/01.mp3", None, 3 * 60 * 1000).await;
+    let podcast = insert_item(&db, "misc/episode.mp3", Some("News; Podcast"), 40 * 60 * 1000).await;
+    let song = insert_item(&db, "misc/song.mp3", None, 3 * 60 * 1000).await;
+    let unknown = insert_item(&db, "misc/unknown.mp3", None, 0).await;
+
+    assert_eq!(source.reclassify().await, 3);
+    assert_eq!(media_type_of(&db, book).await, MediaType::Audiobook);
+    assert_eq!(media_type_of(&db, podcast).await, MediaType::Podcast);
+    assert_eq!(media_type_of(&db, song).await, MediaType::Music);
+    assert_eq!(media_type_of(&db, unknown).await, MediaType::Unspecified);
+
+    // nothing changed, nothing to update
+    assert_eq!(source.reclassify().await, 0);
+
+    source
+        .update_media_type_rules(vec![MediaTypeRule::new(media_type::MediaType::Podcast).with_path_globs(&["misc/**"])])
+        .await
+        .unwrap();
+    assert_eq!(source.reclassify().await, 3);
+    assert_eq!(media_type_of(&db, book).await, MediaType::Unspecified);
+    assert_eq!(media_type_of(&db, song).await, MediaType::Podcast);
+    assert_eq!(media_type_of(&db, unknown).await, MediaType::Podcast);
+}
@@ -0,0 +1,51 @@
+mod common;
+
+use rust_slint_riscv64_musl_demo::entity::item;
+use rust_slint_riscv64_musl_demo::entity::items_json_metadata;
+use rust_slint_riscv64_musl_demo::entity::items_metadata;
+use rust_slint_riscv64_musl_demo::media_source::file_media_source::FileMediaSource;
+use rust_slint_riscv64_musl_demo::media_source::library_root::LibraryRoot;
+use rust_slint_riscv64_musl_demo::settings::settings_manager::SettingsManager;
+use sea_orm::{DatabaseConnection, EntityTrait, PaginatorTrait};
+use std::fs;
+use std::time::{Duration, SystemTime};
+
+async fn row_counts(db: &DatabaseConnection) -> (u64, u64) {
+    (
+        items_metadata::Entity::find().count(db).await.unwrap(),
+        items_json_metadata::Entity::find().count(db).await.unwrap(),
+    )
+}
+
+#[tokio::test]
+async fn rescan_replaces_the_metadata_rows() {
+    let root = common::temp_dir("rescan");
+    let path = root.join("a.mp3");
+    common::write_tagged_mp3(&path);
+    fs::write(root.join("a.lrc"), "[00:01.00]first line\n[00:02.00]second line\n").unwrap();
+
+    let db = common::connect_memory_db().await;
+    let library_root = LibraryRoot::new(root.to_str().unwrap().to_string(), false);
+    let source = FileMediaSource::new(db.clone(), SettingsManager::new(db.clone()), vec![library_root], String::new());
+
+    source.scan_media().await;
+    // title, artist and genre plus the sidecar lyrics
+    assert_eq!(row_counts(&db).await, (3, 1));
+
+    // touch the file, the next scan has to index it again
+    fs::File::options()
+        .write(true)
+        .open(&path)
+        .unwrap()
+        .set_modified(SystemTime::now() + Duration::from_secs(60))
+        .unwrap();
+    let date_modified = item::Entity::find().one(&db).await.unwrap().unwrap().date_modified;
+    source.scan_media().await;
+
+    let items = item::Entity::find().all(&db).await.unwrap();
+    assert_eq!(items.len(), 1);
+    assert!(items[0].date_modified > date_modified);
+    assert_eq!(row_counts(&db).await, (3, 1));
+
+    fs::remove_dir_all(root).unwrap();
+}
@@ -0,0 +1,70 @@
+mod common;
+
+use rust_slint_riscv64_musl_demo::entity::item;
+use rust_slint_riscv64_musl_demo::entity::items_metadata;
+use rust_slint_riscv64_musl_demo::media_source::file_media_source::FileMediaSource;
+use rust_slint_riscv64_musl_demo::media_source::library_root::LibraryRoot;
+use rust_slint_riscv64_musl_demo::media_source::media_source::{MediaSource, MediaSourceError, MediaSourceMetadataUpdate};
+use rust_slint_riscv64_musl_demo::settings::settings_manager::SettingsManager;
+use sea_orm::EntityTrait;
+use std::fs;
+
+#[tokio::test]
+async fn failed_file_write_rolls_back_the_rows() {
+    let root = common::temp_dir("update-metadata");
+    // not decodable, so the tags cannot be written
+    fs::write(root.join("a.mp3"), b"not an mp3").unwrap();
+
+    let db = common::connect_memory_db().await;
+    let library_root = LibraryRoot::new(root.to_str().unwrap().to_string(), false);
+    let source = FileMediaSource::new(db.clone(), SettingsManager::new(db.clone()), vec![library_root], String::new());
+    source.scan_media().await;
+    let items = item::Entity::find().all(&db).await.unwrap();
+    assert_eq!(items.len(), 1);
+    let date_modified = items[0].date_modified;
+    let id = items[0].id.to_string();
+
+    let update = MediaSourceMetadataUpdate {
+        title: Some("Title".to_string()),
+        series: Some("Series".to_string()),
+        ..Default::default()
+    };
+    let result = source.update_metadata(&id, update).await;
+    assert!(matches!(result, Err(MediaSourceError::Tag(_))));
+
+    assert!(items_metadata::Entity::find().all(&db).await.unwrap().is_empty());
+    let item = item::Entity::find_by_id(items[0].id).one(&db).await.unwrap().unwrap();
+    assert_eq!(item.date_modified, date_modified);
+
+    fs::remove_dir_all(root).unwrap();
+}
+
+#[tokio::test]
+async fn genre_update_reclassifies_the_item() {
+    let root = common::temp_dir("update-metadata-genre");
+    common::write_tagged_mp3(&root.join("a.mp3"));
+
+    let db = common::connect_memory_db().await;
+    let library_root = LibraryRoot::new(root.to_str().unwrap().to_string(), false);
+    let source = FileMediaSource::new(db.clone(), SettingsManager::new(db.clone()), vec![library_root], String::new());
+    source.scan_media().await;
+    let items = item::Entity::find().all(&db).await.unwrap();
+    assert_eq!(items.len(), 1);
+    assert_eq!(items[0].media_type, item::MediaType::Audiobook);
+    let id = items[0].id.to_string();
+
+    let update = MediaSourceMetadataUpdate {
+        genre: Some("Podcast".to_string()),
+        ..Default::default()
+    };
+    let updated = source.update_metadata(&id, update).await.unwrap();
+    assert_eq!(updated.metadata.genre.as_deref(), Some("Podcast"));
+    // fields that are not part of the update keep their value
+    assert_eq!(updated.metadata.title.as_deref(), Some("Title"));
+    assert_eq!(updated.metadata.artist.as_deref(), Some("Artist"));
+
+    let item = item::Entity::find_by_id(items[0].id).one(&db).await.unwrap().unwrap();
+    assert_eq!(item.media_type, item::MediaType::Podcast);
+
+    fs::remove_dir_all(root).unwrap();
+}